@@ -0,0 +1,64 @@
+//! 单遍的 RespParser 解析流水线请求和大命令的吞吐
+//! 输入按 4KB 分块到达，模拟从 socket 分多次读到的流水线请求
+
+use bytes::BytesMut;
+use criterion::{Criterion, Throughput, criterion_group, criterion_main};
+use simple_redis::resp::{BulkString, RespArray, RespEncode, RespFrame, RespParser};
+
+const CHUNK: usize = 4096;
+
+fn decode_parser(input: &[u8]) -> usize {
+    let mut buf = BytesMut::new();
+    let mut parser = RespParser::new();
+    let mut frames = 0;
+    for chunk in input.chunks(CHUNK) {
+        buf.extend_from_slice(chunk);
+        while parser.parse(&mut buf).expect("decode error").is_some() {
+            frames += 1;
+        }
+    }
+    frames
+}
+
+fn command(args: &[&[u8]]) -> Vec<u8> {
+    let frames = args
+        .iter()
+        .map(|arg| BulkString::from_slice(arg).into())
+        .collect::<Vec<RespFrame>>();
+    RespArray::from_vec(frames).encode()
+}
+
+/// n 条 SET key:i value 组成的流水线
+fn pipelined_sets(n: usize) -> Vec<u8> {
+    let value = vec![b'x'; 64];
+    (0..n)
+        .flat_map(|i| command(&[b"set", format!("key:{}", i).as_bytes(), &value]))
+        .collect()
+}
+
+/// 一条带 n 个成员的 SADD
+fn large_command(n: usize) -> Vec<u8> {
+    let members = (0..n)
+        .map(|i| format!("member:{}", i).into_bytes())
+        .collect::<Vec<_>>();
+    let mut args: Vec<&[u8]> = vec![b"sadd", b"set"];
+    args.extend(members.iter().map(|member| member.as_slice()));
+    command(&args)
+}
+
+fn bench_decode(c: &mut Criterion) {
+    for (name, input, frames) in [
+        ("pipelined_set_10k", pipelined_sets(10_000), 10_000),
+        ("sadd_5k_members", large_command(5_000), 1),
+    ] {
+        let mut group = c.benchmark_group(name);
+        group.throughput(Throughput::Bytes(input.len() as u64));
+        group.bench_function("parser", |b| {
+            b.iter(|| assert_eq!(decode_parser(&input), frames))
+        });
+        group.finish();
+    }
+}
+
+criterion_group!(benches, bench_decode);
+criterion_main!(benches);
@@ -0,0 +1,541 @@
+use std::{
+    fs::{self, File, OpenOptions},
+    io::{self, Write},
+    path::{Path, PathBuf},
+    str::FromStr,
+    sync::{
+        Mutex, MutexGuard, PoisonError,
+        atomic::{AtomicBool, Ordering},
+    },
+    thread,
+    time::Duration,
+};
+
+use bytes::{Bytes, BytesMut};
+use thiserror::Error;
+use tracing::{info, warn};
+
+use super::{Backend, Record, Value};
+use crate::resp::{BulkString, RespArray, RespEncode, RespFrame, RespLimits, RespParser};
+
+/// 重写时每条命令最多包含的元素个数
+const REWRITE_ITEMS_PER_CMD: usize = 64;
+
+/// AOF 刷盘策略
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FsyncPolicy {
+    /// 每次追加后刷盘
+    Always,
+    /// 每秒刷盘一次
+    #[default]
+    EverySec,
+    /// 由操作系统决定
+    No,
+}
+
+impl FromStr for FsyncPolicy {
+    type Err = AofError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "always" => Ok(FsyncPolicy::Always),
+            "everysec" => Ok(FsyncPolicy::EverySec),
+            "no" => Ok(FsyncPolicy::No),
+            _ => Err(AofError::Invalid(format!(
+                "unknown appendfsync policy {}",
+                s
+            ))),
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum AofError {
+    #[error("io error: {0}")]
+    Io(#[from] io::Error),
+    #[error("AOF truncated at offset {0}")]
+    Truncated(usize),
+    #[error("invalid AOF: {0}")]
+    Invalid(String),
+    #[error("append only file is disabled")]
+    Disabled,
+    #[error("Background append only file rewriting already in progress")]
+    InProgress,
+}
+
+/// AOF 相关的状态
+#[derive(Debug, Default)]
+pub struct AofState {
+    enabled: AtomicBool,
+    /// 写命令的追加和执行在这把锁内串行，保证 AOF 中的顺序与执行顺序一致
+    order: Mutex<()>,
+    file: Mutex<Option<AofFile>>,
+    rewriting: AtomicBool,
+}
+
+#[derive(Debug)]
+struct AofFile {
+    path: PathBuf,
+    file: File,
+    policy: FsyncPolicy,
+    /// 有尚未刷盘的写入
+    unsynced: bool,
+    /// 重写期间追加的命令，重写完成后接到新文件末尾
+    rewrite_buf: Option<Vec<u8>>,
+    /// 写命令执行期间产生的追加，命令本身写入后再接在它后面
+    staged: Option<Vec<u8>>,
+}
+
+impl AofState {
+    fn file(&self) -> MutexGuard<'_, Option<AofFile>> {
+        self.file.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl AofFile {
+    fn append(&mut self, data: &[u8]) {
+        if let Some(buf) = self.rewrite_buf.as_mut() {
+            buf.extend_from_slice(data);
+        }
+        let ret = self.file.write_all(data).and_then(|_| {
+            if self.policy == FsyncPolicy::Always {
+                self.file.sync_data()
+            } else {
+                self.unsynced = true;
+                Ok(())
+            }
+        });
+        if let Err(e) = ret {
+            warn!("write AOF error: {}", e);
+        }
+    }
+}
+
+impl Backend {
+    /// 打开 AOF 开始追加，应在重放完成后调用
+    pub fn open_aof(&self, path: impl Into<PathBuf>, policy: FsyncPolicy) -> Result<(), AofError> {
+        let path = path.into();
+        let file = OpenOptions::new().create(true).append(true).open(&path)?;
+        *self.aof.file() = Some(AofFile {
+            path,
+            file,
+            policy,
+            unsynced: false,
+            rewrite_buf: None,
+            staged: None,
+        });
+        self.aof.enabled.store(true, Ordering::Release);
+        Ok(())
+    }
+    pub fn aof_enabled(&self) -> bool {
+        self.aof.enabled.load(Ordering::Acquire)
+    }
+    /// 写命令执行期间持有，AOF 关闭时返回 None
+    pub fn aof_order(&self) -> Option<MutexGuard<'_, ()>> {
+        self.aof_enabled().then(|| {
+            self.aof
+                .order
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+        })
+    }
+    /// 把一条命令追加到 AOF，AOF 关闭时什么也不做
+    pub fn propagate(&self, frame: &RespFrame) {
+        if !self.aof_enabled() {
+            return;
+        }
+        let data = frame.encode();
+        let mut aof = self.aof.file();
+        let Some(aof) = aof.as_mut() else {
+            return;
+        };
+        match aof.staged.as_mut() {
+            Some(staged) => staged.extend_from_slice(&data),
+            None => aof.append(&data),
+        }
+    }
+    /// 写命令开始执行，之后的追加先暂存，需要持有 aof_order
+    pub fn stage_propagation(&self) {
+        if let Some(aof) = self.aof.file().as_mut() {
+            aof.staged = Some(Vec::new());
+        }
+    }
+    /// 写命令执行完成，先追加 frame 再追加执行期间暂存的命令
+    /// frame 为 None 表示命令失败或没有修改键空间，不写入 AOF
+    pub fn commit_propagation(&self, frame: Option<&RespFrame>) {
+        let mut aof = self.aof.file();
+        let Some(aof) = aof.as_mut() else {
+            return;
+        };
+        let staged = aof.staged.take().unwrap_or_default();
+        if let Some(frame) = frame {
+            aof.append(&frame.encode());
+        }
+        if !staged.is_empty() {
+            aof.append(&staged);
+        }
+    }
+    /// 由参数构造命令后追加到 AOF
+    pub fn propagate_args(&self, args: &[&[u8]]) {
+        if self.aof_enabled() {
+            self.propagate(&command_frame(
+                args.iter().map(|arg| Bytes::copy_from_slice(arg)),
+            ));
+        }
+    }
+    /// everysec 策略下每秒刷盘，由 main 启动
+    pub async fn run_aof_fsync(self) {
+        let mut interval = tokio::time::interval(Duration::from_secs(1));
+        loop {
+            interval.tick().await;
+            let mut aof = self.aof.file();
+            let Some(aof) = aof.as_mut() else {
+                continue;
+            };
+            if aof.policy == FsyncPolicy::EverySec && aof.unsynced {
+                match aof.file.sync_data() {
+                    Ok(()) => aof.unsynced = false,
+                    Err(e) => warn!("fsync AOF error: {}", e),
+                }
+            }
+        }
+    }
+    /// 重放 AOF，每条命令交给 apply 执行；文件不存在时返回 None，否则返回命令条数
+    /// 末尾不完整的命令在 repair 为 true 时截掉，否则返回 Truncated
+    pub fn load_aof(
+        &self,
+        path: &Path,
+        repair: bool,
+        mut apply: impl FnMut(RespArray) -> Result<(), String>,
+    ) -> Result<Option<usize>, AofError> {
+        let data = match fs::read(path) {
+            Ok(data) => data,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(e.into()),
+        };
+        let mut buf = BytesMut::from(&data[..]);
+        // 与客户端连接使用同一个解析器，不限制大小，AOF 中的命令都已经执行过
+        let mut parser = RespParser::with_limits(RespLimits {
+            max_bulk_len: usize::MAX,
+            max_depth: usize::MAX,
+            max_elements: usize::MAX,
+        });
+        let mut count = 0;
+        while !buf.is_empty() {
+            let offset = data.len() - buf.len();
+            match parser.parse(&mut buf) {
+                Ok(Some(RespFrame::Array(array))) => {
+                    apply(array).map_err(|e| {
+                        AofError::Invalid(format!("bad command at offset {}: {}", offset, e))
+                    })?;
+                    count += 1;
+                }
+                Ok(Some(frame)) => {
+                    return Err(AofError::Invalid(format!(
+                        "offset {}: expect array, but got {:?}",
+                        offset, frame
+                    )));
+                }
+                Ok(None) if repair => {
+                    warn!(
+                        "AOF truncated at offset {}, discarding the last {} bytes",
+                        offset,
+                        data.len() - offset
+                    );
+                    OpenOptions::new()
+                        .write(true)
+                        .open(path)?
+                        .set_len(offset as u64)?;
+                    break;
+                }
+                Ok(None) => return Err(AofError::Truncated(offset)),
+                Err(e) => {
+                    return Err(AofError::Invalid(format!("offset {}: {}", offset, e)));
+                }
+            }
+        }
+        // 重放得到的数据已经在 AOF 中，不需要触发 save 规则
+        self.clear_dirty();
+        Ok(Some(count))
+    }
+    /// BGREWRITEAOF：拍摄快照后在后台线程生成新的 AOF
+    pub fn bgrewriteaof(&self) -> Result<(), AofError> {
+        let records = self.begin_rewrite()?;
+        let backend = self.clone();
+        thread::spawn(move || match backend.finish_rewrite(&records) {
+            Ok(()) => info!("background AOF rewrite terminated with success"),
+            Err(e) => warn!("background AOF rewrite error: {}", e),
+        });
+        Ok(())
+    }
+    /// 在当前线程重写 AOF
+    pub fn rewrite_aof(&self) -> Result<(), AofError> {
+        let records = self.begin_rewrite()?;
+        self.finish_rewrite(&records)
+    }
+    /// 后台重写是否正在进行
+    pub fn is_rewriting(&self) -> bool {
+        self.aof.rewriting.load(Ordering::Acquire)
+    }
+    fn begin_rewrite(&self) -> Result<Vec<Record>, AofError> {
+        if !self.aof_enabled() {
+            return Err(AofError::Disabled);
+        }
+        self.aof
+            .rewriting
+            .compare_exchange(false, true, Ordering::AcqRel, Ordering::Acquire)
+            .map_err(|_| AofError::InProgress)?;
+        // 快照之后的追加同时写入重写缓冲区
+        Ok(self.exclusive(|| {
+            if let Some(aof) = self.aof.file().as_mut() {
+                aof.rewrite_buf = Some(Vec::new());
+            }
+            self.collect_records()
+        }))
+    }
+    fn finish_rewrite(&self, records: &[Record]) -> Result<(), AofError> {
+        let ret = self.write_rewrite(records);
+        if ret.is_err()
+            && let Some(aof) = self.aof.file().as_mut()
+        {
+            aof.rewrite_buf = None;
+        }
+        self.aof.rewriting.store(false, Ordering::Release);
+        ret
+    }
+    fn write_rewrite(&self, records: &[Record]) -> Result<(), AofError> {
+        let path = match self.aof.file().as_ref() {
+            Some(aof) => aof.path.clone(),
+            None => return Err(AofError::Disabled),
+        };
+        let tmp = path.with_file_name(format!("temp-rewriteaof-{}.aof", std::process::id()));
+        let ret = (|| {
+            let mut file = File::create(&tmp)?;
+            for record in records {
+                for frame in rewrite_commands(record) {
+                    file.write_all(&frame.encode())?;
+                }
+            }
+            file.sync_data()?;
+            // 切换文件期间暂停追加
+            let mut aof = self.aof.file();
+            let aof = aof.as_mut().ok_or(AofError::Disabled)?;
+            file.write_all(&aof.rewrite_buf.take().unwrap_or_default())?;
+            file.sync_all()?;
+            fs::rename(&tmp, &aof.path)?;
+            aof.file = file;
+            aof.unsynced = false;
+            Ok(())
+        })();
+        if ret.is_err() {
+            let _ = fs::remove_file(&tmp);
+        }
+        ret
+    }
+}
+
+/// 由参数构造命令，命令名使用小写以匹配命令分发
+pub(super) fn command_frame(args: impl IntoIterator<Item = Bytes>) -> RespFrame {
+    RespArray::new(Some(
+        args.into_iter()
+            .map(|arg| BulkString::new(arg).into())
+            .collect(),
+    ))
+    .into()
+}
+
+/// 重建一个 key 所需的命令
+fn rewrite_commands(record: &Record) -> Vec<RespFrame> {
+    let command = |name: &'static str, items: &mut dyn Iterator<Item = Bytes>| {
+        command_frame(
+            [Bytes::from_static(name.as_bytes()), record.key.clone()]
+                .into_iter()
+                .chain(items),
+        )
+    };
+    let chunked = |name: &'static str, items: Vec<Bytes>, per_item: usize| {
+        items
+            .chunks(REWRITE_ITEMS_PER_CMD * per_item)
+            .map(|chunk| command(name, &mut chunk.iter().cloned()))
+            .collect::<Vec<_>>()
+    };
+    let mut frames = match record.value.as_ref() {
+        Value::String(value) => {
+            let mut args = vec![value.clone()];
+            if let Some(at) = record.expire_at {
+                args.extend([Bytes::from_static(b"PXAT"), at.to_string().into()]);
+            }
+            return vec![command("set", &mut args.into_iter())];
+        }
+        Value::List(list) => chunked("rpush", list.iter().cloned().collect(), 1),
+        Value::Set(set) => chunked("sadd", set.iter().cloned().collect(), 1),
+        Value::ZSet(zset) => chunked(
+            "zadd",
+            zset.iter()
+                .flat_map(|(member, score)| [score.to_string().into(), member.clone()])
+                .collect(),
+            2,
+        ),
+        Value::Hash(hash) => chunked(
+            "hset",
+            hash.iter()
+                .flat_map(|(field, value)| [field.clone(), value.clone()])
+                .collect(),
+            2,
+        ),
+    };
+    if let Some(at) = record.expire_at {
+        frames.push(command(
+            "pexpireat",
+            &mut std::iter::once(at.to_string().into()),
+        ));
+    }
+    frames
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        collections::{HashMap, VecDeque},
+        sync::Arc,
+    };
+
+    use super::*;
+    use crate::backend::SortedSet;
+
+    fn temp_path(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("dredis-{}-aof-{}", std::process::id(), name));
+        fs::create_dir_all(&dir).unwrap();
+        dir.join("appendonly.aof")
+    }
+
+    /// 收集 AOF 中的所有命令
+    fn read_commands(path: &Path) -> Result<Vec<RespArray>, AofError> {
+        let mut commands = Vec::new();
+        Backend::new().load_aof(path, false, |array| {
+            commands.push(array);
+            Ok(())
+        })?;
+        Ok(commands)
+    }
+
+    #[test]
+    fn test_propagate_and_truncated_tail() -> Result<(), AofError> {
+        let path = temp_path("truncated");
+        let backend = Backend::new();
+        backend.propagate_args(&[b"set", b"k", b"v"]);
+        assert!(!path.exists());
+        backend.open_aof(&path, FsyncPolicy::Always)?;
+        backend.propagate_args(&[b"set", b"k", b"v"]);
+        backend.propagate_args(&[b"del", b"k"]);
+        assert_eq!(read_commands(&path)?.len(), 2);
+
+        // 模拟写入一半时宕机
+        let mut file = OpenOptions::new().append(true).open(&path)?;
+        file.write_all(b"*3\r\n$3\r\nSET\r\n$1\r\nk")?;
+        let len = fs::metadata(&path)?.len() as usize;
+        let truncated = len - b"*3\r\n$3\r\nSET\r\n$1\r\nk".len();
+        assert!(matches!(
+            read_commands(&path),
+            Err(AofError::Truncated(offset)) if offset == truncated
+        ));
+        let count = Backend::new().load_aof(&path, true, |_| Ok(()))?;
+        assert_eq!(count, Some(2));
+        assert_eq!(fs::metadata(&path)?.len() as usize, truncated);
+        assert_eq!(
+            Backend::new().load_aof(&temp_path("missing"), false, |_| Ok(()))?,
+            None
+        );
+        fs::remove_dir_all(path.parent().unwrap())?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_rewrite_commands() {
+        let mut zset = SortedSet::new();
+        zset.insert("a".into(), 1.5);
+        zset.insert("b".into(), f64::NEG_INFINITY);
+        let record = |value, expire_at| Record {
+            key: "key".into(),
+            value: Arc::new(value),
+            expire_at,
+        };
+        let commands = rewrite_commands(&record(Value::String("v".into()), Some(100)));
+        assert_eq!(
+            commands,
+            vec![command_frame(
+                ["set", "key", "v", "PXAT", "100"].map(|arg| arg.into())
+            )]
+        );
+        let list = (0..100)
+            .map(|i| Bytes::from(i.to_string()))
+            .collect::<VecDeque<_>>();
+        let commands = rewrite_commands(&record(Value::List(list), Some(100)));
+        assert_eq!(commands.len(), 3);
+        assert_eq!(
+            commands[2],
+            command_frame(["pexpireat", "key", "100"].map(|arg| arg.into()))
+        );
+        let commands = rewrite_commands(&record(Value::ZSet(zset), None));
+        assert_eq!(
+            commands,
+            vec![command_frame(
+                ["zadd", "key", "-inf", "b", "1.5", "a"].map(|arg| arg.into())
+            )]
+        );
+        let hash = HashMap::from([("f".into(), "v".into())]);
+        let commands = rewrite_commands(&record(Value::Hash(hash), None));
+        assert_eq!(
+            commands,
+            vec![command_frame(
+                ["hset", "key", "f", "v"].map(|arg| arg.into())
+            )]
+        );
+        let hash = (0..100)
+            .map(|i| (Bytes::from(format!("f{}", i)), Bytes::from(i.to_string())))
+            .collect::<HashMap<_, _>>();
+        let commands = rewrite_commands(&record(Value::Hash(hash), None));
+        let fields = commands
+            .iter()
+            .map(|frame| match frame {
+                RespFrame::Array(RespArray {
+                    elements: Some(args),
+                }) => (args.len() - 2) / 2,
+                _ => panic!("expect array"),
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(fields, [REWRITE_ITEMS_PER_CMD, 100 - REWRITE_ITEMS_PER_CMD]);
+    }
+
+    #[test]
+    fn test_rewrite_keeps_later_writes() -> Result<(), AofError> {
+        let path = temp_path("rewrite");
+        let backend = Backend::new();
+        backend.open_aof(&path, FsyncPolicy::No)?;
+        for i in 0..10 {
+            let value = i.to_string();
+            backend.set("k".into(), value.clone().into());
+            backend.propagate_args(&[b"set", b"k", value.as_bytes()]);
+        }
+        let records = backend.begin_rewrite()?;
+        assert!(matches!(backend.bgrewriteaof(), Err(AofError::InProgress)));
+        // 重写期间的写入
+        backend.propagate_args(&[b"del", b"k"]);
+        backend.finish_rewrite(&records)?;
+        assert!(!backend.is_rewriting());
+        backend.propagate_args(&[b"set", b"k2", b"v"]);
+        let commands = read_commands(&path)?;
+        assert_eq!(
+            commands
+                .into_iter()
+                .map(RespFrame::from)
+                .collect::<Vec<_>>(),
+            vec![
+                command_frame(["set", "k", "9"].map(|arg| arg.into())),
+                command_frame(["del", "k"].map(|arg| arg.into())),
+                command_frame(["set", "k2", "v"].map(|arg| arg.into())),
+            ]
+        );
+        fs::remove_dir_all(path.parent().unwrap())?;
+        Ok(())
+    }
+}
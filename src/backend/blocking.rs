@@ -0,0 +1,364 @@
+use std::{
+    collections::VecDeque,
+    sync::{Arc, Mutex, atomic::Ordering},
+    time::Duration,
+};
+
+use bytes::Bytes;
+use tokio::sync::oneshot;
+
+use super::{Backend, WrongType, aof::command_frame};
+use crate::resp::RespFrame;
+
+/// 被服务的阻塞客户端收到的结果：(key, 元素)，BLMOVE 的 destination 类型不符时为 WrongType
+type Served = Result<(Bytes, Bytes), WrongType>;
+
+/// BLMOVE / LMOVE 的目标：元素插入 destination 的头部（front 为 true）或尾部
+#[derive(Debug, Clone)]
+pub struct ListTarget {
+    pub destination: Bytes,
+    pub front: bool,
+}
+
+/// 阻塞在列表上的客户端，可以同时等待多个 key
+#[derive(Debug)]
+pub struct ListWaiter {
+    id: u64,
+    /// 从头部（true）还是尾部弹出
+    front: bool,
+    /// BLMOVE 的目标，被服务时弹出和插入一起完成
+    target: Option<ListTarget>,
+    /// 被服务后取走，保证只会收到一个元素
+    sender: Mutex<Option<oneshot::Sender<Served>>>,
+}
+
+/// 离开等待队列时清理：超时或连接断开时，把已经发出但没被接收的元素放回列表
+struct ListWaiterGuard<'a> {
+    backend: &'a Backend,
+    keys: &'a [Bytes],
+    id: u64,
+    front: bool,
+    receiver: oneshot::Receiver<Served>,
+}
+
+impl Drop for ListWaiterGuard<'_> {
+    fn drop(&mut self) {
+        for key in self.keys {
+            if let Some(mut queue) = self.backend.list_waiters.get_mut(key) {
+                queue.retain(|waiter| waiter.id != self.id);
+            }
+            self.backend
+                .list_waiters
+                .remove_if(key, |_, queue| queue.is_empty());
+        }
+        self.receiver.close();
+        // BLMOVE 的元素在被服务时已经插入 destination，不需要放回
+        if let Ok(Ok((key, value))) = self.receiver.try_recv() {
+            let _guard = self.backend.lock_shared();
+            let _order = self.backend.aof_order();
+            // 弹出时已经写入 AOF，放回同样需要记录
+            self.backend
+                .propagate_args(&[push_command(self.front), &key, &value]);
+            let _ = self.backend.list_push_unserved(&key, value, self.front);
+            self.backend.serve_list_waiters(&key);
+        }
+    }
+}
+
+/// 从 front 端弹出对应的命令
+pub fn pop_command(front: bool) -> &'static [u8] {
+    if front { b"lpop" } else { b"rpop" }
+}
+/// 向 front 端写入对应的命令
+pub fn push_command(front: bool) -> &'static [u8] {
+    if front { b"lpush" } else { b"rpush" }
+}
+/// LMOVE 中 front 端对应的方向
+fn direction(front: bool) -> Bytes {
+    Bytes::from_static(if front { b"LEFT" } else { b"RIGHT" })
+}
+/// 阻塞命令实际执行的操作写入 AOF 的形式：弹出为 LPOP/RPOP，移动为 LMOVE
+fn served_frame(key: &Bytes, front: bool, target: Option<&ListTarget>) -> RespFrame {
+    match target {
+        Some(target) => command_frame([
+            Bytes::from_static(b"lmove"),
+            key.clone(),
+            target.destination.clone(),
+            direction(front),
+            direction(target.front),
+        ]),
+        None => command_frame([Bytes::from_static(pop_command(front)), key.clone()]),
+    }
+}
+
+impl Backend {
+    /// 从列表的一端弹出一个元素
+    pub fn list_pop_one(&self, key: &[u8], front: bool) -> Result<Option<Bytes>, WrongType> {
+        self.list_update(key, |list| {
+            let popped = if front {
+                list.pop_front()
+            } else {
+                list.pop_back()
+            };
+            let changes = popped.is_some() as usize;
+            (popped, changes)
+        })
+        .map(Option::flatten)
+    }
+    /// 从 source 的一端弹出一个元素插入 target，返回被移动的元素
+    /// destination 不是列表时不弹出，返回 WrongType；插入后唤醒阻塞在 destination 上的客户端
+    pub fn list_move(
+        &self,
+        source: &Bytes,
+        from_front: bool,
+        target: &ListTarget,
+    ) -> Result<Option<Bytes>, WrongType> {
+        let moved = self.list_move_unserved(source, from_front, target)?;
+        if moved.is_some() {
+            self.serve_list_waiters(&target.destination);
+        }
+        Ok(moved)
+    }
+    /// 同 list_move，但不触发阻塞客户端的服务
+    /// 弹出后 destination 被改写成其他类型时把元素放回 source，不会丢失
+    fn list_move_unserved(
+        &self,
+        source: &Bytes,
+        from_front: bool,
+        target: &ListTarget,
+    ) -> Result<Option<Bytes>, WrongType> {
+        self.list_read(&target.destination, |_| ())?;
+        let Some(value) = self.list_pop_one(source, from_front)? else {
+            return Ok(None);
+        };
+        if let Err(e) = self.list_push_unserved(&target.destination, value.clone(), target.front) {
+            let _ = self.list_push_unserved(source, value, from_front);
+            return Err(e);
+        }
+        Ok(Some(value))
+    }
+    /// 阻塞弹出：按顺序检查 keys，都为空时排队等待，timeout 为 None 表示一直等待
+    /// target 不为 None 时弹出的元素插入 target（BLMOVE），弹出和插入一起完成
+    /// 返回 (key, 元素)，超时返回 None；按顺序检查时遇到非列表的 key 返回 WrongType
+    pub async fn list_blocking_pop(
+        &self,
+        keys: &[Bytes],
+        front: bool,
+        target: Option<&ListTarget>,
+        timeout: Option<Duration>,
+    ) -> Result<Option<(Bytes, Bytes)>, WrongType> {
+        {
+            // 移动涉及两个 key，需要独占键空间
+            let (_shared, _exclusive) = match target {
+                Some(_) => (None, Some(self.lock_exclusive())),
+                None => (Some(self.lock_shared()), None),
+            };
+            let _order = self.aof_order();
+            for key in keys {
+                // 阻塞命令以实际执行的操作写入 AOF，移动唤醒的客户端排在它之后
+                self.stage_propagation();
+                let popped = match target {
+                    Some(target) => self.list_move(key, front, target),
+                    None => self.list_pop_one(key, front),
+                };
+                let frame = served_frame(key, front, target);
+                self.commit_propagation(matches!(popped, Ok(Some(_))).then_some(&frame));
+                if let Some(value) = popped? {
+                    return Ok(Some((key.clone(), value)));
+                }
+            }
+        }
+        let (sender, receiver) = oneshot::channel();
+        let waiter = Arc::new(ListWaiter {
+            id: self.next_waiter_id.fetch_add(1, Ordering::Relaxed),
+            front,
+            target: target.cloned(),
+            sender: Mutex::new(Some(sender)),
+        });
+        for key in keys {
+            self.list_waiters
+                .entry(key.clone())
+                .or_default()
+                .push_back(waiter.clone());
+        }
+        let mut guard = ListWaiterGuard {
+            backend: self,
+            keys,
+            id: waiter.id,
+            front,
+            receiver,
+        };
+        // 排队前后可能有数据写入，排队后再检查一次
+        {
+            let _guard = self.lock_shared();
+            let _order = self.aof_order();
+            for key in keys {
+                self.serve_list_waiters(key);
+            }
+        }
+        let served = match timeout {
+            Some(timeout) => tokio::time::timeout(timeout, &mut guard.receiver)
+                .await
+                .ok()
+                .and_then(Result::ok),
+            None => (&mut guard.receiver).await.ok(),
+        };
+        served.transpose()
+    }
+    /// 按 FIFO 顺序把列表中的元素交给阻塞的客户端，由写入列表的命令调用
+    /// 交付成功的弹出或移动紧跟在触发它的命令之后写入 AOF
+    pub(super) fn serve_list_waiters(&self, key: &Bytes) {
+        let Some(mut queue) = self.list_waiters.get_mut(key) else {
+            return;
+        };
+        // 移动到的 destination 在释放等待队列后再服务，避免重入
+        let mut moved = Vec::new();
+        while let Some(waiter) = queue.front().cloned() {
+            let Ok(Some(value)) = self.list_pop_one(key, waiter.front) else {
+                break;
+            };
+            queue.pop_front();
+            let sender = waiter
+                .sender
+                .lock()
+                .ok()
+                .and_then(|mut sender| sender.take())
+                .filter(|sender| !sender.is_closed());
+            // 已经在其他 key 上被服务或已经离开，元素放回原处
+            let Some(sender) = sender else {
+                let _ = self.list_push_unserved(key, value, waiter.front);
+                continue;
+            };
+            let served = match &waiter.target {
+                Some(target) => {
+                    match self.list_push_unserved(&target.destination, value.clone(), target.front)
+                    {
+                        Ok(_) => {
+                            moved.push(target.destination.clone());
+                            Ok((key.clone(), value))
+                        }
+                        Err(e) => {
+                            let _ = self.list_push_unserved(key, value, waiter.front);
+                            Err(e)
+                        }
+                    }
+                }
+                None => Ok((key.clone(), value)),
+            };
+            let done = served.is_ok();
+            match sender.send(served).err() {
+                // 接收方刚好离开：弹出的元素放回原处，已经完成的移动保留
+                Some(Ok((_, value))) if waiter.target.is_none() => {
+                    let _ = self.list_push_unserved(key, value, waiter.front);
+                }
+                _ if done => {
+                    self.propagate(&served_frame(key, waiter.front, waiter.target.as_ref()))
+                }
+                _ => {}
+            }
+        }
+        let empty = queue.is_empty();
+        drop(queue);
+        if empty {
+            self.list_waiters
+                .remove_if(key, |_, queue| queue.is_empty());
+        }
+        for destination in moved {
+            self.serve_list_waiters(&destination);
+        }
+    }
+    /// 向列表的一端插入元素，不触发阻塞客户端的服务，用于放回弹出的元素和移动
+    fn list_push_unserved(&self, key: &Bytes, value: Bytes, front: bool) -> Result<(), WrongType> {
+        self.upsert(key, |list: &mut VecDeque<Bytes>| {
+            if front {
+                list.push_front(value);
+            } else {
+                list.push_back(value);
+            }
+            ((), 1)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_blocking_pop_timeout() {
+        let backend = Backend::new();
+        let keys = vec![Bytes::from("list")];
+        let ret = backend
+            .list_blocking_pop(&keys, true, None, Some(Duration::from_millis(10)))
+            .await;
+        assert_eq!(ret, Ok(None));
+        assert!(backend.list_waiters.is_empty());
+    }
+    #[tokio::test]
+    async fn test_blocking_pop_fifo() {
+        let backend = Backend::new();
+        let keys = vec![Bytes::from("list")];
+        let first = {
+            let (backend, keys) = (backend.clone(), keys.clone());
+            tokio::spawn(async move { backend.list_blocking_pop(&keys, true, None, None).await })
+        };
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        let second = {
+            let (backend, keys) = (backend.clone(), keys.clone());
+            tokio::spawn(async move { backend.list_blocking_pop(&keys, true, None, None).await })
+        };
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        backend
+            .list_push("list".into(), vec!["a".into(), "b".into()], false)
+            .unwrap();
+        assert_eq!(first.await.unwrap(), Ok(Some(("list".into(), "a".into()))));
+        assert_eq!(second.await.unwrap(), Ok(Some(("list".into(), "b".into()))));
+        assert!(!backend.contains_key(b"list"));
+    }
+    #[tokio::test]
+    async fn test_blocking_move() {
+        let backend = Backend::new();
+        let keys = vec![Bytes::from("src")];
+        let target = ListTarget {
+            destination: "dst".into(),
+            front: true,
+        };
+        let waiting = |backend: &Backend| {
+            let (backend, keys, target) = (backend.clone(), keys.clone(), target.clone());
+            tokio::spawn(async move {
+                backend
+                    .list_blocking_pop(&keys, false, Some(&target), None)
+                    .await
+            })
+        };
+        let first = waiting(&backend);
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        backend
+            .list_push("src".into(), vec!["a".into()], true)
+            .unwrap();
+        assert_eq!(first.await.unwrap(), Ok(Some(("src".into(), "a".into()))));
+        assert_eq!(
+            backend.list_read(b"dst", |list| list.clone()),
+            Ok(Some(["a".into()].into()))
+        );
+
+        // destination 在等待期间变成其他类型：返回 WrongType，元素留在 source
+        let second = waiting(&backend);
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        backend.set("dst".into(), "string".into());
+        backend
+            .list_push("src".into(), vec!["b".into()], true)
+            .unwrap();
+        assert_eq!(second.await.unwrap(), Err(WrongType));
+        assert_eq!(
+            backend.list_read(b"src", |list| list.clone()),
+            Ok(Some(["b".into()].into()))
+        );
+        assert!(backend.list_waiters.is_empty());
+        assert_eq!(
+            backend.list_move(&"src".into(), true, &target),
+            Err(WrongType)
+        );
+        assert!(backend.contains_key(b"src"));
+    }
+}
@@ -0,0 +1,102 @@
+use std::{net::SocketAddr, sync::atomic::Ordering};
+
+use super::{Backend, now_ms};
+
+/// 一个客户端连接的状态，供 CLIENT LIST 等命令查看
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ClientInfo {
+    pub id: u64,
+    pub addr: SocketAddr,
+    /// HELLO SETNAME 设置的连接名
+    pub name: Option<String>,
+    /// 回复使用的协议版本，2 或 3，由 HELLO 协商
+    pub protocol: u8,
+    /// 连接建立时间（unix 毫秒）
+    pub created_at: u64,
+    /// 最近一次执行命令的时间（unix 毫秒）
+    pub last_active: u64,
+    /// 最近执行的命令名（小写）
+    pub last_command: Option<String>,
+    /// 已执行的命令数
+    pub commands: u64,
+}
+
+/// 连接持有的登记句柄，drop 时从客户端表中移除
+#[derive(Debug)]
+pub struct ClientHandle {
+    id: u64,
+    backend: Backend,
+}
+
+impl Backend {
+    /// 登记一个新连接，id 从 1 开始递增，不会复用
+    pub fn register_client(&self, addr: SocketAddr) -> ClientHandle {
+        let id = self.next_client_id.fetch_add(1, Ordering::Relaxed) + 1;
+        let now = now_ms();
+        self.clients.insert(
+            id,
+            ClientInfo {
+                id,
+                addr,
+                name: None,
+                protocol: 2,
+                created_at: now,
+                last_active: now,
+                last_command: None,
+                commands: 0,
+            },
+        );
+        ClientHandle {
+            id,
+            backend: self.clone(),
+        }
+    }
+    /// 当前所有连接，按 id 排序
+    pub fn clients(&self) -> Vec<ClientInfo> {
+        let mut clients = self
+            .clients
+            .iter()
+            .map(|entry| entry.value().clone())
+            .collect::<Vec<_>>();
+        clients.sort_by_key(|client| client.id);
+        clients
+    }
+}
+
+impl ClientHandle {
+    pub fn id(&self) -> u64 {
+        self.id
+    }
+    /// 当前连接的协议版本
+    pub fn protocol(&self) -> u8 {
+        self.backend
+            .clients
+            .get(&self.id)
+            .map(|client| client.protocol)
+            .unwrap_or(2)
+    }
+    pub fn set_protocol(&self, protocol: u8) {
+        if let Some(mut client) = self.backend.clients.get_mut(&self.id) {
+            client.protocol = protocol;
+        }
+    }
+    pub fn set_name(&self, name: Option<String>) {
+        if let Some(mut client) = self.backend.clients.get_mut(&self.id) {
+            client.name = name;
+        }
+    }
+    /// 记录一次命令执行
+    pub fn touch(&self, command: Option<String>) {
+        if let Some(mut client) = self.backend.clients.get_mut(&self.id) {
+            client.last_active = now_ms();
+            client.last_command = command;
+            client.commands += 1;
+        }
+    }
+}
+
+impl Drop for ClientHandle {
+    fn drop(&mut self) {
+        self.backend.clients.remove(&self.id);
+    }
+}
@@ -0,0 +1,227 @@
+use std::{
+    sync::atomic::Ordering,
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+
+use bytes::Bytes;
+use dashmap::mapref::entry::Entry;
+use tracing::info;
+
+use super::Backend;
+
+/// 主动过期每轮抽样的 key 数量
+const ACTIVE_EXPIRE_SAMPLES: usize = 20;
+/// 主动过期每次最多运行的时间
+const ACTIVE_EXPIRE_BUDGET: Duration = Duration::from_millis(25);
+
+/// 当前 unix 时间（毫秒）
+pub fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or_default()
+}
+
+impl Backend {
+    /// 惰性过期：key 已过期则删除，返回是否删除
+    /// 过期删除不计入修改次数，AOF 中的过期时间是绝对时间，重放时同样会过期
+    pub fn expire_if_needed(&self, key: &[u8]) -> bool {
+        let now = now_ms();
+        let expired = |key: &[u8]| self.expires.get(key).is_some_and(|at| *at <= now);
+        // 先只读检查，过期时在 db 的分片锁内重新确认，避免删掉期间重新写入的值
+        expired(key) && self.remove_value_if(key, |_| expired(key)).is_some()
+    }
+    /// 设置过期时间（unix 毫秒），时间已过则直接删除 key
+    /// 返回 key 是否存在
+    pub fn expire_at(&self, key: &Bytes, at: u64) -> bool {
+        if at <= now_ms() {
+            return self.remove_key(key);
+        }
+        self.expire_if_needed(key);
+        // 持有 key 的 entry 写入过期时间，DEL 不能在检查和写入之间删除 key
+        let Some(_entry) = self.db.get_mut(key) else {
+            return false;
+        };
+        self.insert_expire(key.clone(), at);
+        self.mark_dirty(1);
+        true
+    }
+    /// 获取过期时间（unix 毫秒）
+    pub fn expire_time(&self, key: &[u8]) -> Option<u64> {
+        self.expire_if_needed(key);
+        self.expires.get(key).map(|v| *v)
+    }
+    /// 移除过期时间，返回是否移除
+    pub fn persist(&self, key: &[u8]) -> bool {
+        self.expire_if_needed(key);
+        let removed = self.remove_expire(key).is_some();
+        if removed {
+            self.mark_dirty(1);
+        }
+        removed
+    }
+    /// 剩余存活毫秒数：key 不存在返回 -2，没有过期时间返回 -1
+    pub fn pttl(&self, key: &[u8]) -> i64 {
+        if !self.contains_key(key) {
+            return -2;
+        }
+        match self.expire_time(key) {
+            Some(at) => at.saturating_sub(now_ms()) as i64,
+            None => -1,
+        }
+    }
+    /// 主动过期：沿着游标抽样检查设置了过期时间的 key，删除其中已过期的
+    /// 与 Redis 相同，抽样中过期的超过 1/4 时继续下一轮，直到比例降低或用完时间预算
+    /// 返回删除数量
+    pub fn active_expire(&self) -> usize {
+        let start = Instant::now();
+        let mut cursor = self.expire_cursor.load(Ordering::Relaxed);
+        let mut removed = 0;
+        loop {
+            let (next, keys) = self.expire_index.scan(cursor, ACTIVE_EXPIRE_SAMPLES);
+            cursor = next;
+            let expired = keys.iter().filter(|key| self.expire_if_needed(key)).count();
+            removed += expired;
+            if expired * 4 <= keys.len() || start.elapsed() >= ACTIVE_EXPIRE_BUDGET {
+                break;
+            }
+        }
+        self.expire_cursor.store(cursor, Ordering::Relaxed);
+        removed
+    }
+    /// 设置过期时间并维护索引
+    pub(super) fn insert_expire(&self, key: Bytes, at: u64) {
+        match self.expires.entry(key) {
+            Entry::Occupied(mut entry) => {
+                entry.insert(at);
+            }
+            Entry::Vacant(entry) => {
+                self.expire_index.insert(entry.key());
+                entry.insert(at);
+            }
+        }
+    }
+    /// 删除过期时间并维护索引，返回原来的过期时间
+    pub(super) fn remove_expire(&self, key: &[u8]) -> Option<u64> {
+        self.expires
+            .remove_if(key, |key, _| {
+                self.expire_index.remove(key);
+                true
+            })
+            .map(|(_, at)| at)
+    }
+    /// 后台定时清理过期 key，由 main 启动
+    pub async fn run_expire_sweeper(self, period: Duration) {
+        let mut interval = tokio::time::interval(period);
+        loop {
+            interval.tick().await;
+            let count = {
+                let _guard = self.lock_shared();
+                self.active_expire()
+            };
+            if count > 0 {
+                info!("active expire: removed {} keys", count);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        super::{SetCondition, SetTtl},
+        *,
+    };
+
+    #[test]
+    fn test_lazy_expire() {
+        let backend = Backend::new();
+        backend
+            .set_with(
+                "hello".into(),
+                "world".into(),
+                SetCondition::Always,
+                SetTtl::At(now_ms() - 1),
+                false,
+            )
+            .unwrap();
+        assert_eq!(backend.pttl(b"hello"), -2);
+        assert_eq!(backend.get(b"hello"), Ok(None));
+
+        backend.set("hello".into(), "world".into());
+        assert_eq!(backend.pttl(b"hello"), -1);
+        assert!(backend.expire_at(&"hello".into(), now_ms() + 10_000));
+        assert!(backend.pttl(b"hello") > 9_000);
+        assert!(backend.persist(b"hello"));
+        assert_eq!(backend.pttl(b"hello"), -1);
+    }
+    #[test]
+    fn test_expire_does_not_outlive_key() {
+        let backend = Backend::new();
+        let key = Bytes::from("k");
+        let recreate = || {
+            backend
+                .hset(key.clone(), vec![("f".into(), "v".into())])
+                .unwrap()
+        };
+        recreate();
+        assert!(backend.expire_at(&key, now_ms() + 10_000));
+        assert!(backend.remove_key(&key));
+        recreate();
+        assert_eq!(backend.pttl(&key), -1);
+
+        // EXPIRE 与 DEL 并发时，无论谁先执行，删除后重建的 key 都不带过期时间
+        for _ in 0..200 {
+            std::thread::scope(|scope| {
+                scope.spawn(|| backend.expire_at(&key, now_ms() + 10_000));
+                scope.spawn(|| backend.remove_key(&key));
+            });
+            assert!(backend.expires.is_empty());
+            recreate();
+            assert_eq!(backend.pttl(&key), -1);
+        }
+    }
+    #[test]
+    fn test_active_expire() {
+        let backend = Backend::new();
+        backend.set("a".into(), "1".into());
+        backend
+            .hset("b".into(), vec![("f".into(), "2".into())])
+            .unwrap();
+        backend.set("c".into(), "3".into());
+        backend.insert_expire("a".into(), now_ms() - 1);
+        backend.insert_expire("b".into(), now_ms() - 1);
+        backend.insert_expire("c".into(), now_ms() + 10_000);
+        assert_eq!(backend.active_expire(), 2);
+        assert!(!backend.db.contains_key(b"a".as_slice()));
+        assert!(!backend.db.contains_key(b"b".as_slice()));
+        assert!(backend.db.contains_key(b"c".as_slice()));
+    }
+    #[test]
+    fn test_active_expire_sampling() {
+        let backend = Backend::new();
+        for i in 0..100 {
+            let key = Bytes::from(format!("live:{}", i));
+            backend.set(key.clone(), "v".into());
+            backend.insert_expire(key, now_ms() + 10_000);
+        }
+        // 没有过期的 key 时只抽样一轮，下次从游标处继续
+        assert_eq!(backend.active_expire(), 0);
+        assert_ne!(backend.expire_cursor.load(Ordering::Relaxed), 0);
+
+        for i in 0..200 {
+            let key = Bytes::from(format!("dead:{}", i));
+            backend.set(key.clone(), "v".into());
+            backend.insert_expire(key, now_ms() - 1);
+        }
+        // 过期比例高时一次调用会持续抽样，清理掉大部分过期的 key
+        let mut removed = backend.active_expire();
+        assert!(removed > 150, "removed {}", removed);
+        for _ in 0..100 {
+            removed += backend.active_expire();
+        }
+        assert_eq!(removed, 200);
+        assert_eq!(backend.expires.len(), 100);
+        assert_eq!(backend.expire_index.scan(0, usize::MAX).1.len(), 100);
+    }
+}
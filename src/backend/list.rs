@@ -0,0 +1,81 @@
+use std::collections::VecDeque;
+
+use super::{Backend, Changes, WrongType};
+use bytes::Bytes;
+
+impl Backend {
+    /// 向列表头部（front）或尾部插入元素，key 不存在时创建，返回插入后的长度
+    /// 插入后唤醒阻塞在该 key 上的客户端
+    pub fn list_push(
+        &self,
+        key: Bytes,
+        values: Vec<Bytes>,
+        front: bool,
+    ) -> Result<usize, WrongType> {
+        let len = self.upsert(&key, |list: &mut VecDeque<Bytes>| {
+            let pushed = values.len();
+            for value in values {
+                if front {
+                    list.push_front(value);
+                } else {
+                    list.push_back(value);
+                }
+            }
+            (list.len(), pushed)
+        })?;
+        self.serve_list_waiters(&key);
+        Ok(len)
+    }
+    /// 只读访问列表，key 不存在返回 None
+    pub fn list_read<R>(
+        &self,
+        key: &[u8],
+        f: impl FnOnce(&VecDeque<Bytes>) -> R,
+    ) -> Result<Option<R>, WrongType> {
+        self.read(key, f)
+    }
+    /// 修改列表，key 不存在返回 None；修改后列表为空则删除 key
+    pub fn list_update<R: Changes>(
+        &self,
+        key: &[u8],
+        f: impl FnOnce(&mut VecDeque<Bytes>) -> R,
+    ) -> Result<Option<R::Output>, WrongType> {
+        self.update(key, f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_list_push_update() {
+        let backend = Backend::new();
+        assert_eq!(
+            backend.list_push("list".into(), vec!["a".into(), "b".into()], true),
+            Ok(2)
+        );
+        assert_eq!(
+            backend.list_push("list".into(), vec!["c".into()], false),
+            Ok(3)
+        );
+        assert_eq!(
+            backend.list_read(b"list", |list| list.iter().cloned().collect::<Vec<_>>()),
+            Ok(Some(vec!["b".into(), "a".into(), "c".into()]))
+        );
+        assert_eq!(
+            backend.list_update(b"list", |list| {
+                let removed = list.len();
+                list.clear();
+                ((), removed)
+            }),
+            Ok(Some(()))
+        );
+        // 列表为空时 key 被删除
+        assert!(!backend.contains_key(b"list"));
+        assert_eq!(
+            backend.list_update(b"list", |list| (list.len(), 0)),
+            Ok(None)
+        );
+    }
+}
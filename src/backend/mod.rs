@@ -1,17 +1,92 @@
-use std::{ops::Deref, sync::Arc};
+mod aof;
+mod blocking;
+mod client;
+mod expire;
+mod list;
+mod rdb;
+mod scan;
+mod set;
+mod skiplist;
+mod string;
+mod value;
+mod zset;
+use std::{
+    collections::{HashMap, VecDeque},
+    ops::Deref,
+    path::PathBuf,
+    sync::{Arc, RwLock, atomic::AtomicU64},
+};
 
-use dashmap::DashMap;
+use aof::AofState;
+pub use aof::{AofError, FsyncPolicy};
+use blocking::ListWaiter;
+pub use blocking::{ListTarget, pop_command, push_command};
+pub use client::{ClientHandle, ClientInfo};
+use rdb::RdbState;
+pub use rdb::{RdbError, Record};
+use scan::ScanIndex;
+pub use scan::scan_by_hash;
+pub use skiplist::ScoreBound;
+pub use string::MAX_STRING_LEN;
+pub use value::{Changes, Value, ValueKind, WrongType};
+pub use zset::SortedSet;
 
-use crate::resp::RespFrame;
+use bytes::Bytes;
+use dashmap::{DashMap, mapref::entry::Entry};
+pub use expire::now_ms;
+
+use crate::glob::glob_match;
 
 #[derive(Debug, Clone, Default)]
 pub struct Backend(Arc<BackendInner>);
 
 #[derive(Debug, Default)]
 pub struct BackendInner {
-    map: DashMap<String, RespFrame>,
-    hmap: DashMap<String, DashMap<String, RespFrame>>,
+    /// 键空间，所有类型共用；值在快照中共享，修改时写时复制
+    db: DashMap<Bytes, Arc<Value>>,
+    /// 键空间中的 key 按游标哈希排序，SCAN 从游标处直接定位
+    key_index: ScanIndex,
+    /// key -> 过期时间（unix 毫秒）
+    expires: DashMap<Bytes, u64>,
+    /// 设置了过期时间的 key，主动过期沿着游标抽样
+    expire_index: ScanIndex,
+    /// 主动过期下一轮抽样的起点
+    expire_cursor: AtomicU64,
+    /// key -> 阻塞在该列表上的客户端（FIFO）
+    list_waiters: DashMap<Bytes, VecDeque<Arc<ListWaiter>>>,
+    next_waiter_id: AtomicU64,
+    /// 连接 id -> 连接状态
+    clients: DashMap<u64, ClientInfo>,
+    next_client_id: AtomicU64,
+    /// 命令执行时共享持有，拍摄快照时独占
+    gate: RwLock<()>,
+    rdb: RdbState,
+    aof: AofState,
+}
+
+/// SET 的写入条件
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SetCondition {
+    #[default]
+    Always,
+    /// NX: 只在 key 不存在时写入
+    IfNotExists,
+    /// XX: 只在 key 已存在时写入
+    IfExists,
 }
+
+/// SET 写入后对过期时间的处理
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SetTtl {
+    /// 清除原有的过期时间
+    #[default]
+    Clear,
+    /// KEEPTTL: 保留原有的过期时间
+    Keep,
+    /// 设置为指定的过期时间（unix 毫秒）
+    At(u64),
+}
+
 impl Deref for Backend {
     type Target = BackendInner;
     fn deref(&self) -> &Self::Target {
@@ -22,19 +97,140 @@ impl Backend {
     pub fn new() -> Self {
         Self::default()
     }
-    pub fn set(&self, key: String, value: RespFrame) {
-        self.map.insert(key, value);
+    /// 指定快照文件路径
+    pub fn with_rdb_path(path: impl Into<PathBuf>) -> Self {
+        Self(Arc::new(BackendInner {
+            rdb: RdbState::new(path),
+            ..Default::default()
+        }))
+    }
+    pub fn set(&self, key: Bytes, value: Bytes) {
+        let _ = self.set_with(key, value, SetCondition::Always, SetTtl::Clear, false);
+    }
+    /// 按条件写入（覆盖任意类型），返回 (是否写入, 旧值)
+    /// get 为 true 时旧值必须是字符串，否则不写入并返回 WrongType
+    pub fn set_with(
+        &self,
+        key: Bytes,
+        value: Bytes,
+        condition: SetCondition,
+        ttl: SetTtl,
+        get: bool,
+    ) -> Result<(bool, Option<Bytes>), WrongType> {
+        self.expire_if_needed(&key);
+        // 过期时间在持有 entry 时修改，与 DEL 互斥
+        let apply_ttl = || match ttl {
+            SetTtl::Clear => {
+                self.remove_expire(&key);
+            }
+            SetTtl::Keep => {}
+            SetTtl::At(at) => self.insert_expire(key.clone(), at),
+        };
+        let old = match self.db.entry(key.clone()) {
+            Entry::Occupied(mut entry) => {
+                let old = match entry.get().as_ref() {
+                    Value::String(old) => Some(old.clone()),
+                    _ if get => return Err(WrongType),
+                    _ => None,
+                };
+                if condition == SetCondition::IfNotExists {
+                    return Ok((false, old));
+                }
+                entry.insert(Arc::new(Value::String(value)));
+                apply_ttl();
+                old
+            }
+            Entry::Vacant(entry) => {
+                if condition == SetCondition::IfExists {
+                    return Ok((false, None));
+                }
+                self.key_index.insert(entry.key());
+                entry.insert(Arc::new(Value::String(value)));
+                apply_ttl();
+                None
+            }
+        };
+        self.mark_dirty(1);
+        Ok((true, old))
+    }
+    pub fn get(&self, key: &[u8]) -> Result<Option<Bytes>, WrongType> {
+        self.expire_if_needed(key);
+        match self.db.get(key).as_deref().map(Arc::as_ref) {
+            Some(Value::String(value)) => Ok(Some(value.clone())),
+            Some(_) => Err(WrongType),
+            None => Ok(None),
+        }
+    }
+    /// 写入多个字段，返回新增的字段数量
+    pub fn hset(&self, key: Bytes, pairs: Vec<(Bytes, Bytes)>) -> Result<usize, WrongType> {
+        // 与 Redis 相同，每个写入的字段都计一次修改，包括覆盖已有字段
+        let written = pairs.len();
+        self.upsert(&key, |hash: &mut HashMap<Bytes, Bytes>| {
+            let added = pairs
+                .into_iter()
+                .filter(|(field, value)| hash.insert(field.clone(), value.clone()).is_none())
+                .count();
+            (added, written)
+        })
+    }
+    pub fn hget(&self, key: &[u8], field: &[u8]) -> Result<Option<Bytes>, WrongType> {
+        self.read(key, |hash: &HashMap<Bytes, Bytes>| hash.get(field).cloned())
+            .map(Option::flatten)
+    }
+    /// 匹配 glob 模式的所有 key，跳过已过期的 key
+    pub fn keys(&self, pattern: &[u8]) -> Vec<Bytes> {
+        let now = now_ms();
+        self.db
+            .iter()
+            .filter(|entry| glob_match(pattern, entry.key()))
+            .filter(|entry| self.expires.get(entry.key()).is_none_or(|at| *at > now))
+            .map(|entry| entry.key().clone())
+            .collect()
+    }
+    /// 键空间中 key 的数量，包括已过期但还没删除的 key
+    pub fn dbsize(&self) -> usize {
+        self.db.len()
+    }
+    /// 按遍历顺序的第 n 个 key，用于随机取 key
+    pub fn nth_key(&self, n: usize) -> Option<Bytes> {
+        self.db.iter().nth(n).map(|entry| entry.key().clone())
     }
-    pub fn get(&self, key: &str) -> Option<RespFrame> {
-        self.map.get(key).map(|v| v.clone())
+    /// key 是否存在（任意类型）
+    pub fn contains_key(&self, key: &[u8]) -> bool {
+        self.expire_if_needed(key);
+        self.db.contains_key(key)
     }
-    pub fn hset(&self, key: String, field: String, value: RespFrame) {
-        self.hmap.entry(key).or_default().insert(field, value);
+    /// 删除 key（任意类型）及其过期时间，返回 key 是否存在
+    pub fn remove_key(&self, key: &[u8]) -> bool {
+        self.expire_if_needed(key);
+        self.remove_value(key)
     }
-    pub fn hget(&self, key: &str, field: &str) -> Option<RespFrame> {
-        self.hmap.get(key)?.get(field).map(|v| v.clone())
+    /// 把 key 改名为 new_key，覆盖 new_key 原有的值，过期时间跟随 key
+    /// 返回 key 是否存在；调用方需要通过 lock_exclusive 保证其他命令看不到中间状态
+    pub fn rename(&self, key: &[u8], new_key: &Bytes) -> bool {
+        self.expire_if_needed(key);
+        let expire = self.expires.get(key).map(|at| *at);
+        let Some(value) = self.remove_value_if(key, |_| true) else {
+            return false;
+        };
+        self.remove_key(new_key);
+        let is_list = matches!(*value, Value::List(_));
+        self.insert_value(new_key.clone(), value);
+        if let Some(at) = expire {
+            self.insert_expire(new_key.clone(), at);
+        }
+        self.mark_dirty(1);
+        if is_list {
+            self.serve_list_waiters(new_key);
+        }
+        true
     }
-    pub fn hgetall(&self, key: &str) -> Option<DashMap<String, RespFrame>> {
-        self.hmap.get(key).map(|v| v.clone())
+    /// 从键空间中删除 key，返回 key 是否存在
+    fn remove_value(&self, key: &[u8]) -> bool {
+        let removed = self.remove_value_if(key, |_| true).is_some();
+        if removed {
+            self.mark_dirty(1);
+        }
+        removed
     }
 }
@@ -0,0 +1,588 @@
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    fs::{self, File},
+    io::{self, Write},
+    path::{Path, PathBuf},
+    sync::{
+        Arc, PoisonError, RwLockReadGuard, RwLockWriteGuard,
+        atomic::{AtomicBool, AtomicU64, Ordering},
+    },
+    thread,
+    time::Duration,
+};
+
+use bytes::Bytes;
+use thiserror::Error;
+use tracing::{info, warn};
+
+use super::{Backend, SortedSet, Value, now_ms};
+use crate::config::SaveRule;
+
+// 快照文件格式
+// | 部分     | 格式                                                        |
+// |----------|-------------------------------------------------------------|
+// | 文件头   | "DREDIS" + 版本号(1 字节)                                   |
+// | 过期时间 | 0xFC + unix 毫秒(u64 小端)，可选，作用于紧接着的 key        |
+// | key      | 类型(1 字节) + key + 值                                     |
+// | 文件尾   | 0xFF + 之前所有字节的 FNV-1a 校验和(u64 小端)               |
+// 长度使用 LEB128 变长编码，字符串为 长度 + 原始字节，分数为 f64 小端
+const MAGIC: &[u8] = b"DREDIS";
+const VERSION: u8 = 1;
+const OP_EXPIRE_MS: u8 = 0xFC;
+const OP_EOF: u8 = 0xFF;
+const TYPE_STRING: u8 = 0;
+const TYPE_LIST: u8 = 1;
+const TYPE_SET: u8 = 2;
+const TYPE_ZSET: u8 = 3;
+const TYPE_HASH: u8 = 4;
+
+#[derive(Debug, Error)]
+pub enum RdbError {
+    #[error("io error: {0}")]
+    Io(#[from] io::Error),
+    #[error("invalid snapshot: {0}")]
+    Invalid(String),
+    #[error("Background save already in progress")]
+    InProgress,
+}
+
+/// 快照中的一个 key
+#[derive(Debug, Clone, PartialEq)]
+pub struct Record {
+    pub key: Bytes,
+    /// 与键空间共享，拍摄快照时不复制值
+    pub value: Arc<Value>,
+    /// 过期时间（unix 毫秒）
+    pub expire_at: Option<u64>,
+}
+
+/// 快照相关的状态
+#[derive(Debug)]
+pub struct RdbState {
+    path: PathBuf,
+    /// 启动以来的修改次数，只增不减
+    changes: AtomicU64,
+    /// 上次保存的快照包含的修改次数
+    saved: AtomicU64,
+    /// 上次成功保存的时间（unix 毫秒）
+    last_save: AtomicU64,
+    /// SAVE 或 BGSAVE 正在进行
+    saving: AtomicBool,
+}
+
+impl RdbState {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            changes: AtomicU64::new(0),
+            saved: AtomicU64::new(0),
+            last_save: AtomicU64::new(now_ms()),
+            saving: AtomicBool::new(false),
+        }
+    }
+}
+impl Default for RdbState {
+    fn default() -> Self {
+        Self::new("dump.rdb")
+    }
+}
+
+impl Backend {
+    /// 命令执行期间持有的共享锁；快照时独占，保证快照是某一时刻的完整状态
+    pub fn lock_shared(&self) -> RwLockReadGuard<'_, ()> {
+        self.gate.read().unwrap_or_else(PoisonError::into_inner)
+    }
+    /// 跨多个 key 的原子写入期间持有的独占锁，其他命令都被阻塞
+    pub fn lock_exclusive(&self) -> RwLockWriteGuard<'_, ()> {
+        self.gate.write().unwrap_or_else(PoisonError::into_inner)
+    }
+    /// 记录 n 次修改，由修改键空间的操作调用
+    pub fn mark_dirty(&self, n: u64) {
+        self.rdb.changes.fetch_add(n, Ordering::Relaxed);
+    }
+    /// 启动以来的修改次数，用于判断一条命令是否修改了键空间
+    pub fn changes(&self) -> u64 {
+        self.rdb.changes.load(Ordering::Relaxed)
+    }
+    /// 上次保存后的修改次数
+    pub fn dirty(&self) -> u64 {
+        self.changes()
+            .saturating_sub(self.rdb.saved.load(Ordering::Relaxed))
+    }
+    /// 当前的修改都不需要保存，如重放 AOF 之后
+    pub(super) fn clear_dirty(&self) {
+        self.rdb.saved.fetch_max(self.changes(), Ordering::Relaxed);
+    }
+    /// 上次成功保存的时间（unix 毫秒）
+    pub fn last_save(&self) -> u64 {
+        self.rdb.last_save.load(Ordering::Relaxed)
+    }
+    /// 当前键空间的一致性快照，跳过已过期的 key
+    pub fn snapshot(&self) -> Vec<Record> {
+        self.exclusive(|| self.collect_records())
+    }
+    /// 独占键空间执行 f，期间所有命令都被阻塞
+    pub(super) fn exclusive<R>(&self, f: impl FnOnce() -> R) -> R {
+        let _guard = self.lock_exclusive();
+        f()
+    }
+    /// 收集所有未过期的 key，调用方需要通过 exclusive 保证一致性
+    /// 只复制 key 和值的引用，之后的修改写时复制，编码可以在释放独占锁之后进行
+    pub(super) fn collect_records(&self) -> Vec<Record> {
+        let now = now_ms();
+        self.db
+            .iter()
+            .filter_map(|entry| {
+                let expire_at = self.expires.get(entry.key()).map(|at| *at);
+                if expire_at.is_some_and(|at| at <= now) {
+                    return None;
+                }
+                Some(Record {
+                    key: entry.key().clone(),
+                    value: entry.value().clone(),
+                    expire_at,
+                })
+            })
+            .collect()
+    }
+    /// 快照以及拍摄时的修改次数
+    fn snapshot_with_changes(&self) -> (Vec<Record>, u64) {
+        self.exclusive(|| (self.collect_records(), self.changes()))
+    }
+    /// SAVE：在当前线程保存快照
+    pub fn save(&self) -> Result<(), RdbError> {
+        self.begin_save()?;
+        let (records, changes) = self.snapshot_with_changes();
+        let ret = self.write_snapshot(&records, changes);
+        self.rdb.saving.store(false, Ordering::Release);
+        ret
+    }
+    /// BGSAVE：拍摄快照后在后台线程写文件
+    pub fn bgsave(&self) -> Result<(), RdbError> {
+        self.begin_save()?;
+        let (records, changes) = self.snapshot_with_changes();
+        let backend = self.clone();
+        thread::spawn(move || {
+            match backend.write_snapshot(&records, changes) {
+                Ok(()) => info!("background saving terminated with success"),
+                Err(e) => warn!("background saving error: {}", e),
+            }
+            backend.rdb.saving.store(false, Ordering::Release);
+        });
+        Ok(())
+    }
+    /// 后台保存是否正在进行
+    pub fn is_saving(&self) -> bool {
+        self.rdb.saving.load(Ordering::Acquire)
+    }
+    fn begin_save(&self) -> Result<(), RdbError> {
+        self.rdb
+            .saving
+            .compare_exchange(false, true, Ordering::AcqRel, Ordering::Acquire)
+            .map(|_| ())
+            .map_err(|_| RdbError::InProgress)
+    }
+    fn write_snapshot(&self, records: &[Record], changes: u64) -> Result<(), RdbError> {
+        write_atomic(&self.rdb.path, &encode(records))?;
+        // 拍摄快照之后的修改保留到下一次保存
+        self.rdb.saved.fetch_max(changes, Ordering::Relaxed);
+        self.rdb.last_save.store(now_ms(), Ordering::Relaxed);
+        Ok(())
+    }
+    /// 启动时加载快照，文件不存在时返回 0，否则返回加载的 key 数量
+    pub fn load(&self) -> Result<usize, RdbError> {
+        let data = match fs::read(&self.rdb.path) {
+            Ok(data) => data,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(0),
+            Err(e) => return Err(e.into()),
+        };
+        let now = now_ms();
+        let mut count = 0;
+        for record in decode(&data)? {
+            if record.expire_at.is_some_and(|at| at <= now) {
+                continue;
+            }
+            if let Some(at) = record.expire_at {
+                self.insert_expire(record.key.clone(), at);
+            }
+            self.insert_value(record.key, record.value);
+            count += 1;
+        }
+        Ok(count)
+    }
+    /// 按 save 规则定时触发 BGSAVE，由 main 启动
+    pub async fn run_save_scheduler(self, rules: Vec<SaveRule>) {
+        let mut interval = tokio::time::interval(Duration::from_secs(1));
+        loop {
+            interval.tick().await;
+            let dirty = self.dirty();
+            let elapsed = now_ms().saturating_sub(self.last_save()) / 1000;
+            let Some(rule) = rules
+                .iter()
+                .find(|rule| dirty > 0 && dirty >= rule.changes && elapsed >= rule.seconds)
+            else {
+                continue;
+            };
+            info!(
+                "{} changes in {} seconds. Saving...",
+                rule.changes, rule.seconds
+            );
+            match self.bgsave() {
+                Ok(()) | Err(RdbError::InProgress) => {}
+                Err(e) => warn!("background saving error: {}", e),
+            }
+        }
+    }
+}
+
+/// 先写临时文件并刷盘，再原子地改名为目标文件
+fn write_atomic(path: &Path, data: &[u8]) -> io::Result<()> {
+    let tmp = path.with_file_name(format!("temp-{}.rdb", std::process::id()));
+    let ret = File::create(&tmp).and_then(|mut file| {
+        file.write_all(data)?;
+        file.sync_all()
+    });
+    match ret.and_then(|_| fs::rename(&tmp, path)) {
+        Ok(()) => Ok(()),
+        Err(e) => {
+            let _ = fs::remove_file(&tmp);
+            Err(e)
+        }
+    }
+}
+
+fn checksum(data: &[u8]) -> u64 {
+    data.iter().fold(0xcbf29ce484222325, |hash, byte| {
+        (hash ^ *byte as u64).wrapping_mul(0x100000001b3)
+    })
+}
+
+fn put_len(buf: &mut Vec<u8>, mut len: u64) {
+    while len >= 0x80 {
+        buf.push(len as u8 | 0x80);
+        len >>= 7;
+    }
+    buf.push(len as u8);
+}
+fn put_bytes(buf: &mut Vec<u8>, data: &[u8]) {
+    put_len(buf, data.len() as u64);
+    buf.extend_from_slice(data);
+}
+
+/// 编码快照
+pub fn encode(records: &[Record]) -> Vec<u8> {
+    let mut buf = Vec::from(MAGIC);
+    buf.push(VERSION);
+    for record in records {
+        if let Some(at) = record.expire_at {
+            buf.push(OP_EXPIRE_MS);
+            buf.extend_from_slice(&at.to_le_bytes());
+        }
+        match record.value.as_ref() {
+            Value::String(value) => {
+                buf.push(TYPE_STRING);
+                put_bytes(&mut buf, &record.key);
+                put_bytes(&mut buf, value);
+            }
+            Value::List(list) => {
+                buf.push(TYPE_LIST);
+                put_bytes(&mut buf, &record.key);
+                put_len(&mut buf, list.len() as u64);
+                list.iter().for_each(|value| put_bytes(&mut buf, value));
+            }
+            Value::Set(set) => {
+                buf.push(TYPE_SET);
+                put_bytes(&mut buf, &record.key);
+                put_len(&mut buf, set.len() as u64);
+                set.iter().for_each(|member| put_bytes(&mut buf, member));
+            }
+            Value::ZSet(zset) => {
+                buf.push(TYPE_ZSET);
+                put_bytes(&mut buf, &record.key);
+                put_len(&mut buf, zset.len() as u64);
+                for (member, score) in zset.iter() {
+                    put_bytes(&mut buf, member);
+                    buf.extend_from_slice(&score.to_le_bytes());
+                }
+            }
+            Value::Hash(hash) => {
+                buf.push(TYPE_HASH);
+                put_bytes(&mut buf, &record.key);
+                put_len(&mut buf, hash.len() as u64);
+                for (field, value) in hash {
+                    put_bytes(&mut buf, field);
+                    put_bytes(&mut buf, value);
+                }
+            }
+        }
+    }
+    buf.push(OP_EOF);
+    let sum = checksum(&buf);
+    buf.extend_from_slice(&sum.to_le_bytes());
+    buf
+}
+
+/// 解码快照
+pub fn decode(data: &[u8]) -> Result<Vec<Record>, RdbError> {
+    let mut reader = Reader { data, pos: 0 };
+    if reader.take(MAGIC.len())? != MAGIC {
+        return Err(RdbError::Invalid("bad magic".to_string()));
+    }
+    let version = reader.u8()?;
+    if version != VERSION {
+        return Err(RdbError::Invalid(format!(
+            "unsupported version {}",
+            version
+        )));
+    }
+    let mut records = Vec::new();
+    let mut expire_at = None;
+    loop {
+        let kind = reader.u8()?;
+        let value = match kind {
+            OP_EOF => break,
+            OP_EXPIRE_MS => {
+                expire_at = Some(reader.u64()?);
+                continue;
+            }
+            _ => {
+                let key = reader.bytes()?;
+                (key, reader.value(kind)?)
+            }
+        };
+        records.push(Record {
+            key: value.0,
+            value: Arc::new(value.1),
+            expire_at: expire_at.take(),
+        });
+    }
+    let expected = checksum(&data[..reader.pos]);
+    if reader.u64()? != expected {
+        return Err(RdbError::Invalid("checksum mismatch".to_string()));
+    }
+    if reader.pos != data.len() {
+        return Err(RdbError::Invalid("trailing data after EOF".to_string()));
+    }
+    Ok(records)
+}
+
+struct Reader<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, n: usize) -> Result<&'a [u8], RdbError> {
+        if self.data.len() - self.pos < n {
+            return Err(RdbError::Invalid("unexpected end of file".to_string()));
+        }
+        let slice = &self.data[self.pos..self.pos + n];
+        self.pos += n;
+        Ok(slice)
+    }
+    fn u8(&mut self) -> Result<u8, RdbError> {
+        Ok(self.take(1)?[0])
+    }
+    fn u64(&mut self) -> Result<u64, RdbError> {
+        Ok(u64::from_le_bytes(
+            self.take(8)?.try_into().unwrap_or_default(),
+        ))
+    }
+    fn f64(&mut self) -> Result<f64, RdbError> {
+        Ok(f64::from_le_bytes(
+            self.take(8)?.try_into().unwrap_or_default(),
+        ))
+    }
+    fn len(&mut self) -> Result<usize, RdbError> {
+        let mut len = 0u64;
+        for shift in (0..64).step_by(7) {
+            let byte = self.u8()?;
+            len |= ((byte & 0x7f) as u64) << shift;
+            if byte & 0x80 == 0 {
+                return usize::try_from(len)
+                    .map_err(|_| RdbError::Invalid("length overflow".to_string()));
+            }
+        }
+        Err(RdbError::Invalid("length overflow".to_string()))
+    }
+    fn bytes(&mut self) -> Result<Bytes, RdbError> {
+        let len = self.len()?;
+        Ok(Bytes::copy_from_slice(self.take(len)?))
+    }
+    /// 元素个数，用于预分配时不超过剩余字节数
+    fn count(&mut self) -> Result<(usize, usize), RdbError> {
+        let n = self.len()?;
+        Ok((n, n.min(self.data.len() - self.pos)))
+    }
+    fn value(&mut self, kind: u8) -> Result<Value, RdbError> {
+        Ok(match kind {
+            TYPE_STRING => Value::String(self.bytes()?),
+            TYPE_LIST => {
+                let (n, cap) = self.count()?;
+                let mut list = VecDeque::with_capacity(cap);
+                for _ in 0..n {
+                    list.push_back(self.bytes()?);
+                }
+                Value::List(list)
+            }
+            TYPE_SET => {
+                let (n, cap) = self.count()?;
+                let mut set = HashSet::with_capacity(cap);
+                for _ in 0..n {
+                    set.insert(self.bytes()?);
+                }
+                Value::Set(set)
+            }
+            TYPE_ZSET => {
+                let (n, _) = self.count()?;
+                let mut zset = SortedSet::new();
+                for _ in 0..n {
+                    let member = self.bytes()?;
+                    zset.insert(member, self.f64()?);
+                }
+                Value::ZSet(zset)
+            }
+            TYPE_HASH => {
+                let (n, cap) = self.count()?;
+                let mut hash = HashMap::with_capacity(cap);
+                for _ in 0..n {
+                    let field = self.bytes()?;
+                    hash.insert(field, self.bytes()?);
+                }
+                Value::Hash(hash)
+            }
+            _ => {
+                return Err(RdbError::Invalid(format!("unknown value type {}", kind)));
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> Vec<Record> {
+        let mut zset = SortedSet::new();
+        zset.insert("a".into(), 1.5);
+        zset.insert("b".into(), -2.0);
+        vec![
+            Record {
+                key: Bytes::from_static(b"\xffstr"),
+                value: Arc::new(Value::String(Bytes::from_static(b"\x00\r\n"))),
+                expire_at: Some(now_ms() + 100_000),
+            },
+            Record {
+                key: "list".into(),
+                value: Arc::new(Value::List(VecDeque::from(["1".into(), "2".into()]))),
+                expire_at: None,
+            },
+            Record {
+                key: "set".into(),
+                value: Arc::new(Value::Set(HashSet::from(["x".into()]))),
+                expire_at: None,
+            },
+            Record {
+                key: "zset".into(),
+                value: Arc::new(Value::ZSet(zset)),
+                expire_at: None,
+            },
+            Record {
+                key: "hash".into(),
+                value: Arc::new(Value::Hash(HashMap::from([("f".into(), "v".into())]))),
+                expire_at: Some(now_ms() + 100_000),
+            },
+        ]
+    }
+
+    fn temp_path(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("dredis-{}-{}", std::process::id(), name));
+        fs::create_dir_all(&dir).unwrap();
+        dir.join("dump.rdb")
+    }
+
+    #[test]
+    fn test_encode_decode() -> Result<(), RdbError> {
+        let records = sample();
+        let data = encode(&records);
+        assert_eq!(decode(&data)?, records);
+        // 截断或损坏的文件
+        assert!(decode(&data[..data.len() - 1]).is_err());
+        let mut corrupted = data.clone();
+        corrupted[MAGIC.len() + 4] ^= 1;
+        assert!(decode(&corrupted).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_snapshot_shares_values() {
+        let backend = Backend::new();
+        backend
+            .list_push("list".into(), vec!["a".into()], false)
+            .unwrap();
+        let records = backend.snapshot();
+        let shared = |backend: &Backend| {
+            Arc::ptr_eq(
+                &records[0].value,
+                &backend.db.get(b"list".as_slice()).unwrap(),
+            )
+        };
+        assert!(shared(&backend));
+        // 快照之后的修改复制一份新值，快照中的值保持不变
+        backend
+            .list_push("list".into(), vec!["b".into()], false)
+            .unwrap();
+        assert!(!shared(&backend));
+        assert_eq!(*records[0].value, Value::List(VecDeque::from(["a".into()])));
+    }
+
+    #[test]
+    fn test_save_and_load() -> Result<(), RdbError> {
+        let path = temp_path("save");
+        let backend = Backend::with_rdb_path(&path);
+        for record in sample() {
+            backend.insert_value(record.key.clone(), record.value);
+            if let Some(at) = record.expire_at {
+                backend.insert_expire(record.key, at);
+            }
+        }
+        // 已过期的 key 不会写入快照
+        backend.set("expired".into(), "v".into());
+        backend.insert_expire("expired".into(), 1);
+        backend.mark_dirty(3);
+        backend.save()?;
+        assert_eq!(backend.dirty(), 0);
+
+        let loaded = Backend::with_rdb_path(&path);
+        assert_eq!(loaded.load()?, 5);
+        assert_eq!(loaded.type_of(b"zset"), Some("zset"));
+        assert_eq!(
+            loaded.get(b"\xffstr"),
+            Ok(Some(Bytes::from_static(b"\x00\r\n")))
+        );
+        assert!(loaded.pttl(b"hash") > 0);
+        assert_eq!(loaded.pttl(b"list"), -1);
+        assert!(!loaded.contains_key(b"expired"));
+        fs::remove_dir_all(path.parent().unwrap())?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_bgsave() -> Result<(), RdbError> {
+        let path = temp_path("bgsave");
+        let backend = Backend::with_rdb_path(&path);
+        assert_eq!(backend.load()?, 0);
+        backend.set("k".into(), "v".into());
+        backend.bgsave()?;
+        while backend.is_saving() {
+            thread::sleep(Duration::from_millis(1));
+        }
+        // 拍摄快照之后的修改不影响已保存的内容
+        backend.set("k".into(), "v2".into());
+        let loaded = Backend::with_rdb_path(&path);
+        assert_eq!(loaded.load()?, 1);
+        assert_eq!(loaded.get(b"k"), Ok(Some("v".into())));
+        fs::remove_dir_all(path.parent().unwrap())?;
+        Ok(())
+    }
+}
@@ -0,0 +1,269 @@
+use std::{
+    collections::BTreeMap,
+    sync::{Arc, Mutex, MutexGuard, PoisonError},
+};
+
+use bytes::Bytes;
+use dashmap::mapref::entry::Entry;
+
+use super::{Backend, Value, now_ms};
+
+/// 索引按哈希值的高位分段，每段覆盖一个连续的哈希区间
+const INDEX_SHARD_BITS: u32 = 8;
+
+/// 游标对应的哈希值：64 位 FNV-1a 再经过 fmix64 打散高位
+/// 算法固定，不随 Rust 版本和重启变化，重启后游标仍然有效
+pub fn scan_hash(key: &[u8]) -> u64 {
+    let mut hash = key.iter().fold(0xcbf2_9ce4_8422_2325_u64, |hash, byte| {
+        (hash ^ *byte as u64).wrapping_mul(0x0000_0100_0000_01b3)
+    });
+    hash ^= hash >> 33;
+    hash = hash.wrapping_mul(0xff51_afd7_ed55_8ccd);
+    hash ^= hash >> 33;
+    hash = hash.wrapping_mul(0xc4ce_b9fe_1a85_ec53);
+    hash ^ (hash >> 33)
+}
+
+/// 按哈希值从小到大遍历：返回哈希值不小于 cursor 的前 count 个元素和下一个游标，
+/// 遍历结束时游标为 0；每次调用都要遍历全部元素，用于单个容器
+/// 顺序与底层哈希表的布局无关，扩容和缩容期间一直存在的元素至少返回一次；
+/// 哈希值相同的元素总是在同一轮返回
+pub fn scan_by_hash<T>(
+    items: impl IntoIterator<Item = T>,
+    key: impl Fn(&T) -> &[u8],
+    cursor: u64,
+    count: usize,
+) -> (u64, Vec<T>) {
+    let mut candidates = items
+        .into_iter()
+        .map(|item| (scan_hash(key(&item)), item))
+        .filter(|(hash, _)| *hash >= cursor)
+        .collect::<Vec<_>>();
+    let count = count.max(1);
+    let next = if candidates.len() > count {
+        candidates.select_nth_unstable_by_key(count - 1, |(hash, _)| *hash);
+        let boundary = candidates[count - 1].0;
+        candidates.retain(|(hash, _)| *hash <= boundary);
+        boundary.checked_add(1).unwrap_or(0)
+    } else {
+        0
+    };
+    candidates.sort_unstable_by_key(|(hash, _)| *hash);
+    (next, candidates.into_iter().map(|(_, item)| item).collect())
+}
+
+/// 索引的一段：哈希值 -> 哈希值相同的 key
+type IndexShard = BTreeMap<u64, Vec<Bytes>>;
+
+/// 按 scan_hash 排序的 key 索引，游标可以直接定位，每次遍历只访问约 count 个 key
+/// 修改需要在持有对应 DashMap 分片锁时进行，保证与键空间一致
+#[derive(Debug)]
+pub struct ScanIndex {
+    shards: Box<[Mutex<IndexShard>]>,
+}
+
+impl Default for ScanIndex {
+    fn default() -> Self {
+        Self {
+            shards: (0..1 << INDEX_SHARD_BITS)
+                .map(|_| Mutex::default())
+                .collect(),
+        }
+    }
+}
+
+impl ScanIndex {
+    fn shard(&self, i: usize) -> MutexGuard<'_, IndexShard> {
+        self.shards[i]
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+    /// 哈希值所在的段
+    fn shard_of(hash: u64) -> usize {
+        (hash >> (u64::BITS - INDEX_SHARD_BITS)) as usize
+    }
+    pub fn insert(&self, key: &Bytes) {
+        let hash = scan_hash(key);
+        let mut shard = self.shard(Self::shard_of(hash));
+        let keys = shard.entry(hash).or_default();
+        if !keys.contains(key) {
+            keys.push(key.clone());
+        }
+    }
+    pub fn remove(&self, key: &[u8]) {
+        let hash = scan_hash(key);
+        let mut shard = self.shard(Self::shard_of(hash));
+        if let Some(keys) = shard.get_mut(&hash) {
+            keys.retain(|k| k != key);
+            if keys.is_empty() {
+                shard.remove(&hash);
+            }
+        }
+    }
+    /// 语义同 scan_by_hash，从 cursor 所在的段开始依次访问
+    pub fn scan(&self, cursor: u64, count: usize) -> (u64, Vec<Bytes>) {
+        let count = count.max(1);
+        let mut keys = Vec::new();
+        let first = Self::shard_of(cursor);
+        for i in first..self.shards.len() {
+            let shard = self.shard(i);
+            let from = if i == first {
+                cursor
+            } else {
+                (i as u64) << (u64::BITS - INDEX_SHARD_BITS)
+            };
+            for (hash, group) in shard.range(from..) {
+                if keys.len() >= count {
+                    return (*hash, keys);
+                }
+                keys.extend(group.iter().cloned());
+            }
+        }
+        (0, keys)
+    }
+}
+
+impl Backend {
+    /// SCAN：遍历键空间，跳过已过期的 key
+    pub fn scan_keys(&self, cursor: u64, count: usize) -> (u64, Vec<Bytes>) {
+        let (next, keys) = self.key_index.scan(cursor, count);
+        let now = now_ms();
+        let keys = keys
+            .into_iter()
+            .filter(|key| self.expires.get(key).is_none_or(|at| *at > now))
+            .collect();
+        (next, keys)
+    }
+    /// 写入 key 的值并维护索引，返回旧值
+    pub(super) fn insert_value(&self, key: Bytes, value: Arc<Value>) -> Option<Arc<Value>> {
+        match self.db.entry(key) {
+            Entry::Occupied(mut entry) => Some(entry.insert(value)),
+            Entry::Vacant(entry) => {
+                self.key_index.insert(entry.key());
+                entry.insert(value);
+                None
+            }
+        }
+    }
+    /// 满足 f 时删除 key 的值和过期时间并维护索引，返回被删除的值
+    /// 过期时间在 db 的分片锁内删除，不会留下没有 key 的过期时间
+    pub(super) fn remove_value_if(
+        &self,
+        key: &[u8],
+        f: impl FnOnce(&Value) -> bool,
+    ) -> Option<Arc<Value>> {
+        self.db
+            .remove_if(key, |key, value| {
+                let remove = f(value);
+                if remove {
+                    self.key_index.remove(key);
+                    self.remove_expire(key);
+                }
+                remove
+            })
+            .map(|(_, value)| value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use super::*;
+
+    #[test]
+    fn test_scan_by_hash() {
+        let items = (0..100).map(|i| i.to_string()).collect::<Vec<_>>();
+        let mut seen = HashSet::new();
+        let mut cursor = 0;
+        let mut rounds = 0;
+        loop {
+            let (next, batch) = scan_by_hash(items.iter(), |item| item.as_bytes(), cursor, 7);
+            assert!(batch.len() <= 7);
+            for item in batch {
+                assert!(seen.insert(item.clone()));
+            }
+            rounds += 1;
+            if next == 0 {
+                break;
+            }
+            cursor = next;
+        }
+        assert_eq!(seen.len(), 100);
+        assert_eq!(rounds, 15);
+    }
+
+    #[test]
+    fn test_scan_index() {
+        // 哈希算法固定，游标在不同版本和重启之间保持有效
+        assert_eq!(scan_hash(b"hello"), 16844978562278765124);
+        let index = ScanIndex::default();
+        for i in 0..100 {
+            index.insert(&i.to_string().into());
+        }
+        index.insert(&"0".into());
+        for i in 0..50 {
+            index.remove(i.to_string().as_bytes());
+        }
+        let mut seen = HashSet::new();
+        let mut cursor = 0;
+        loop {
+            let (next, batch) = index.scan(cursor, 7);
+            assert!(batch.len() <= 7);
+            for key in batch {
+                assert!(seen.insert(key));
+            }
+            if next == 0 {
+                break;
+            }
+            cursor = next;
+        }
+        let expected = (50..100).map(|i| Bytes::from(i.to_string())).collect();
+        assert_eq!(seen, expected);
+    }
+
+    #[test]
+    fn test_scan_keys_while_growing() {
+        let backend = Backend::new();
+        for i in 0..50 {
+            backend.set(format!("old:{}", i).into(), "v".into());
+        }
+        backend.set("gone".into(), "v".into());
+        backend.insert_expire("gone".into(), now_ms() - 1);
+        let mut seen = HashSet::new();
+        let mut cursor = 0;
+        let mut i = 0;
+        loop {
+            let (next, keys) = backend.scan_keys(cursor, 5);
+            seen.extend(keys);
+            // 遍历期间不断插入新 key，触发扩容
+            for _ in 0..2 {
+                backend.set(format!("new:{}", i).into(), "v".into());
+                i += 1;
+            }
+            if next == 0 {
+                break;
+            }
+            cursor = next;
+        }
+        for i in 0..50 {
+            assert!(seen.contains(format!("old:{}", i).as_bytes()));
+        }
+        assert!(!seen.contains(b"gone".as_slice()));
+        // 删除和过期的 key 同时从索引中移除
+        backend.remove_key(b"old:0");
+        backend.expire_if_needed(b"gone");
+        backend
+            .upsert(&"set".into(), |set: &mut HashSet<Bytes>| {
+                (set.insert("a".into()), 1)
+            })
+            .unwrap();
+        backend
+            .update(b"set", |set: &mut HashSet<Bytes>| (set.clear(), 1))
+            .unwrap();
+        assert_eq!(
+            backend.key_index.scan(0, usize::MAX).1.len(),
+            backend.dbsize()
+        );
+    }
+}
@@ -0,0 +1,61 @@
+use std::{collections::HashSet, sync::Arc};
+
+use bytes::Bytes;
+
+use super::{Backend, Value, WrongType};
+
+impl Backend {
+    /// 添加成员，返回新增的成员数量
+    pub fn set_add(&self, key: Bytes, members: Vec<Bytes>) -> Result<usize, WrongType> {
+        self.upsert(&key, |set: &mut HashSet<Bytes>| {
+            let added = members
+                .into_iter()
+                .filter(|member| set.insert(member.clone()))
+                .count();
+            (added, added)
+        })
+    }
+    /// 删除成员，返回删除的成员数量；集合为空时删除 key
+    pub fn set_remove(&self, key: &[u8], members: &[Bytes]) -> Result<usize, WrongType> {
+        self.update(key, |set: &mut HashSet<Bytes>| {
+            let removed = members.iter().filter(|member| set.remove(*member)).count();
+            (removed, removed)
+        })
+        .map(Option::unwrap_or_default)
+    }
+    /// 只读访问集合，key 不存在返回 None
+    pub fn set_read<R>(
+        &self,
+        key: &[u8],
+        f: impl FnOnce(&HashSet<Bytes>) -> R,
+    ) -> Result<Option<R>, WrongType> {
+        self.read(key, f)
+    }
+    /// 用新集合覆盖 destination（无论原来是什么类型），集合为空时只删除
+    /// 返回新集合的大小；与读取源集合一起需要通过 lock_exclusive 保证原子性
+    pub fn set_store(&self, destination: Bytes, members: HashSet<Bytes>) -> usize {
+        let len = members.len();
+        self.remove_key(&destination);
+        if len > 0 {
+            self.insert_value(destination, Arc::new(Value::Set(members)));
+            self.mark_dirty(1);
+        }
+        len
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_set_add_remove() {
+        let backend = Backend::new();
+        let members = vec!["a".into(), "b".into(), "a".into()];
+        assert_eq!(backend.set_add("set".into(), members), Ok(2));
+        assert_eq!(backend.set_read(b"set", |set| set.len()), Ok(Some(2)));
+        assert_eq!(backend.set_remove(b"set", &["a".into(), "c".into()]), Ok(1));
+        assert_eq!(backend.set_remove(b"set", &["b".into()]), Ok(1));
+        assert!(!backend.contains_key(b"set"));
+    }
+}
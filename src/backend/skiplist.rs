@@ -0,0 +1,459 @@
+use std::{
+    cmp::Ordering,
+    collections::hash_map::RandomState,
+    hash::{BuildHasher, Hasher},
+};
+
+use bytes::Bytes;
+
+// 参考 Redis 的 zskiplist 实现：每层记录 span（跨过的节点数），
+// 按排名查找和按分数查找都是 O(log n)
+const MAX_LEVEL: usize = 32;
+/// 头节点固定在 nodes[0]
+const HEAD: usize = 0;
+
+#[derive(Debug, Clone, Copy)]
+struct Level {
+    forward: Option<usize>,
+    span: usize,
+}
+
+#[derive(Debug, Clone)]
+struct Node {
+    member: Bytes,
+    score: f64,
+    backward: Option<usize>,
+    levels: Vec<Level>,
+}
+
+/// 按 (score, member) 排序的跳表，节点存放在 Vec 中，用下标代替指针
+#[derive(Debug, Clone)]
+pub struct SkipList {
+    nodes: Vec<Node>,
+    /// 被删除节点的下标，供插入时复用
+    free: Vec<usize>,
+    tail: Option<usize>,
+    level: usize,
+    len: usize,
+    /// xorshift 随机数状态，用于生成节点层数
+    seed: u64,
+}
+
+/// 分数区间的边界，exclusive 为 true 时不包含边界值
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ScoreBound {
+    pub value: f64,
+    pub exclusive: bool,
+}
+
+impl ScoreBound {
+    pub fn new(value: f64, exclusive: bool) -> Self {
+        Self { value, exclusive }
+    }
+    /// 作为下界时 score 是否在区间内
+    pub fn above_min(&self, score: f64) -> bool {
+        if self.exclusive {
+            score > self.value
+        } else {
+            score >= self.value
+        }
+    }
+    /// 作为上界时 score 是否在区间内
+    pub fn below_max(&self, score: f64) -> bool {
+        if self.exclusive {
+            score < self.value
+        } else {
+            score <= self.value
+        }
+    }
+}
+
+impl Default for SkipList {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SkipList {
+    pub fn new() -> Self {
+        let head = Node {
+            member: Bytes::new(),
+            score: 0.0,
+            backward: None,
+            levels: vec![
+                Level {
+                    forward: None,
+                    span: 0,
+                };
+                MAX_LEVEL
+            ],
+        };
+        Self {
+            nodes: vec![head],
+            free: Vec::new(),
+            tail: None,
+            level: 1,
+            len: 0,
+            seed: RandomState::new().build_hasher().finish() | 1,
+        }
+    }
+    pub fn len(&self) -> usize {
+        self.len
+    }
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+    fn forward(&self, node: usize, level: usize) -> Option<usize> {
+        self.nodes[node].levels[level].forward
+    }
+    fn span(&self, node: usize, level: usize) -> usize {
+        self.nodes[node].levels[level].span
+    }
+    fn compare(&self, node: usize, score: f64, member: &[u8]) -> Ordering {
+        let node = &self.nodes[node];
+        node.score
+            .partial_cmp(&score)
+            .unwrap_or(Ordering::Equal)
+            .then_with(|| node.member.as_ref().cmp(member))
+    }
+    /// 以 1/4 的概率逐层升高
+    fn random_level(&mut self) -> usize {
+        let mut level = 1;
+        loop {
+            self.seed ^= self.seed << 13;
+            self.seed ^= self.seed >> 7;
+            self.seed ^= self.seed << 17;
+            if level >= MAX_LEVEL || self.seed & 0b11 != 0 {
+                return level;
+            }
+            level += 1;
+        }
+    }
+    fn alloc(&mut self, node: Node) -> usize {
+        match self.free.pop() {
+            Some(index) => {
+                self.nodes[index] = node;
+                index
+            }
+            None => {
+                self.nodes.push(node);
+                self.nodes.len() - 1
+            }
+        }
+    }
+    /// 插入节点，调用方保证 member 不存在
+    pub fn insert(&mut self, score: f64, member: Bytes) {
+        let mut update = [HEAD; MAX_LEVEL];
+        let mut rank = [0usize; MAX_LEVEL];
+        let mut x = HEAD;
+        for i in (0..self.level).rev() {
+            rank[i] = if i == self.level - 1 { 0 } else { rank[i + 1] };
+            while let Some(next) = self.forward(x, i) {
+                if self.compare(next, score, &member) != Ordering::Less {
+                    break;
+                }
+                rank[i] += self.span(x, i);
+                x = next;
+            }
+            update[i] = x;
+        }
+        let level = self.random_level();
+        if level > self.level {
+            for i in self.level..level {
+                rank[i] = 0;
+                update[i] = HEAD;
+                self.nodes[HEAD].levels[i].span = self.len;
+            }
+            self.level = level;
+        }
+        let node = self.alloc(Node {
+            member,
+            score,
+            backward: None,
+            levels: vec![
+                Level {
+                    forward: None,
+                    span: 0,
+                };
+                level
+            ],
+        });
+        for i in 0..level {
+            let prev = update[i];
+            let prev_span = self.span(prev, i);
+            self.nodes[node].levels[i] = Level {
+                forward: self.forward(prev, i),
+                span: prev_span - (rank[0] - rank[i]),
+            };
+            self.nodes[prev].levels[i] = Level {
+                forward: Some(node),
+                span: rank[0] - rank[i] + 1,
+            };
+        }
+        for (i, prev) in update.iter().enumerate().take(self.level).skip(level) {
+            self.nodes[*prev].levels[i].span += 1;
+        }
+        self.nodes[node].backward = (update[0] != HEAD).then_some(update[0]);
+        match self.forward(node, 0) {
+            Some(next) => self.nodes[next].backward = Some(node),
+            None => self.tail = Some(node),
+        }
+        self.len += 1;
+    }
+    /// 删除节点，返回是否存在
+    pub fn delete(&mut self, score: f64, member: &[u8]) -> bool {
+        let mut update = [HEAD; MAX_LEVEL];
+        let mut x = HEAD;
+        for i in (0..self.level).rev() {
+            while let Some(next) = self.forward(x, i) {
+                if self.compare(next, score, member) != Ordering::Less {
+                    break;
+                }
+                x = next;
+            }
+            update[i] = x;
+        }
+        match self.forward(x, 0) {
+            Some(target) if self.compare(target, score, member) == Ordering::Equal => {
+                self.delete_node(target, &update);
+                true
+            }
+            _ => false,
+        }
+    }
+    fn delete_node(&mut self, x: usize, update: &[usize]) {
+        for (i, prev) in update.iter().enumerate().take(self.level) {
+            let prev = *prev;
+            if self.forward(prev, i) == Some(x) {
+                let span = self.span(prev, i) + self.span(x, i) - 1;
+                self.nodes[prev].levels[i] = Level {
+                    forward: self.forward(x, i),
+                    span,
+                };
+            } else {
+                self.nodes[prev].levels[i].span = self.span(prev, i).saturating_sub(1);
+            }
+        }
+        let backward = self.nodes[x].backward;
+        match self.forward(x, 0) {
+            Some(next) => self.nodes[next].backward = backward,
+            None => self.tail = backward,
+        }
+        while self.level > 1 && self.forward(HEAD, self.level - 1).is_none() {
+            self.level -= 1;
+        }
+        self.nodes[x].member = Bytes::new();
+        self.nodes[x].levels.clear();
+        self.free.push(x);
+        self.len -= 1;
+    }
+    /// 从 0 开始的排名
+    pub fn rank(&self, score: f64, member: &[u8]) -> Option<usize> {
+        let mut rank = 0;
+        let mut x = HEAD;
+        for i in (0..self.level).rev() {
+            while let Some(next) = self.forward(x, i) {
+                if self.compare(next, score, member) == Ordering::Greater {
+                    break;
+                }
+                rank += self.span(x, i);
+                x = next;
+            }
+            if x != HEAD && self.compare(x, score, member) == Ordering::Equal {
+                return Some(rank - 1);
+            }
+        }
+        None
+    }
+    /// 按排名（从 0 开始）查找节点
+    fn node_by_rank(&self, rank: usize) -> Option<usize> {
+        let target = rank + 1;
+        let mut traversed = 0;
+        let mut x = HEAD;
+        for i in (0..self.level).rev() {
+            while let Some(next) = self.forward(x, i) {
+                if traversed + self.span(x, i) > target {
+                    break;
+                }
+                traversed += self.span(x, i);
+                x = next;
+            }
+            if traversed == target {
+                return Some(x);
+            }
+        }
+        None
+    }
+    /// 第一个分数满足下界的节点
+    fn first_in_range(&self, min: &ScoreBound) -> Option<usize> {
+        let mut x = HEAD;
+        for i in (0..self.level).rev() {
+            while let Some(next) = self.forward(x, i) {
+                if min.above_min(self.nodes[next].score) {
+                    break;
+                }
+                x = next;
+            }
+        }
+        self.forward(x, 0)
+    }
+    /// 最后一个分数满足上界的节点
+    fn last_in_range(&self, max: &ScoreBound) -> Option<usize> {
+        let mut x = HEAD;
+        for i in (0..self.level).rev() {
+            while let Some(next) = self.forward(x, i) {
+                if !max.below_max(self.nodes[next].score) {
+                    break;
+                }
+                x = next;
+            }
+        }
+        (x != HEAD).then_some(x)
+    }
+    /// 从 start 节点开始正向或反向遍历
+    fn walk(&self, start: Option<usize>, rev: bool) -> impl Iterator<Item = (&Bytes, f64)> {
+        std::iter::successors(start, move |&node| {
+            if rev {
+                self.nodes[node].backward
+            } else {
+                self.forward(node, 0)
+            }
+        })
+        .map(|node| (&self.nodes[node].member, self.nodes[node].score))
+    }
+    /// 按分数从小到大遍历所有元素
+    pub fn iter(&self) -> impl Iterator<Item = (&Bytes, f64)> {
+        self.walk(self.forward(HEAD, 0), false)
+    }
+    /// 按排名返回 [start, stop] 闭区间的元素，rev 为 true 时排名从大到小计算
+    pub fn range_by_rank(&self, start: usize, stop: usize, rev: bool) -> Vec<(Bytes, f64)> {
+        if start > stop || start >= self.len {
+            return Vec::new();
+        }
+        let stop = stop.min(self.len - 1);
+        let first = if rev {
+            self.node_by_rank(self.len - 1 - start)
+        } else {
+            self.node_by_rank(start)
+        };
+        self.walk(first, rev)
+            .take(stop - start + 1)
+            .map(|(member, score)| (member.clone(), score))
+            .collect()
+    }
+    /// 按分数返回 [min, max] 区间的元素，跳过 offset 个后最多返回 count 个
+    pub fn range_by_score(
+        &self,
+        min: &ScoreBound,
+        max: &ScoreBound,
+        rev: bool,
+        offset: usize,
+        count: Option<usize>,
+    ) -> Vec<(Bytes, f64)> {
+        let first = if rev {
+            self.last_in_range(max)
+        } else {
+            self.first_in_range(min)
+        };
+        self.walk(first, rev)
+            .take_while(|(_, score)| {
+                if rev {
+                    min.above_min(*score)
+                } else {
+                    max.below_max(*score)
+                }
+            })
+            .skip(offset)
+            .take(count.unwrap_or(usize::MAX))
+            .map(|(member, score)| (member.clone(), score))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn members(items: Vec<(Bytes, f64)>) -> Vec<Bytes> {
+        items.into_iter().map(|(member, _)| member).collect()
+    }
+
+    #[test]
+    fn test_skiplist_insert_rank_delete() {
+        let mut list = SkipList::new();
+        for i in (0..200).rev() {
+            list.insert(i as f64, format!("m{:03}", i).into());
+        }
+        assert_eq!(list.len(), 200);
+        for i in 0..200 {
+            assert_eq!(
+                list.rank(i as f64, format!("m{:03}", i).as_bytes()),
+                Some(i)
+            );
+        }
+        assert!(list.delete(10.0, b"m010"));
+        assert!(!list.delete(10.0, b"m010"));
+        assert_eq!(list.rank(11.0, b"m011"), Some(10));
+        assert_eq!(list.len(), 199);
+        // 相同分数按 member 排序
+        list.insert(11.0, "a".into());
+        assert_eq!(list.rank(11.0, b"a"), Some(10));
+        assert_eq!(list.rank(11.0, b"m011"), Some(11));
+    }
+    #[test]
+    fn test_skiplist_range() {
+        let mut list = SkipList::new();
+        for (i, member) in ["a", "b", "c", "d", "e"].iter().enumerate() {
+            list.insert(i as f64, Bytes::from_static(member.as_bytes()));
+        }
+        assert_eq!(
+            members(list.range_by_rank(1, 3, false)),
+            vec!["b", "c", "d"]
+        );
+        assert_eq!(members(list.range_by_rank(0, 1, true)), vec!["e", "d"]);
+        assert_eq!(members(list.range_by_rank(3, 100, false)), vec!["d", "e"]);
+
+        let min = ScoreBound::new(1.0, true);
+        let max = ScoreBound::new(4.0, false);
+        assert_eq!(
+            members(list.range_by_score(&min, &max, false, 0, None)),
+            vec!["c", "d", "e"]
+        );
+        assert_eq!(
+            members(list.range_by_score(&min, &max, true, 1, Some(1))),
+            vec!["d"]
+        );
+        let min = ScoreBound::new(f64::NEG_INFINITY, false);
+        let max = ScoreBound::new(0.0, true);
+        assert!(list.range_by_score(&min, &max, false, 0, None).is_empty());
+    }
+    #[test]
+    fn test_skiplist_matches_sorted_vec() {
+        let mut list = SkipList::new();
+        let mut expected: Vec<(f64, Bytes)> = Vec::new();
+        // 插入和删除交替进行，检查每个节点的排名与 span 是否一致
+        for i in 0..500u64 {
+            let score = ((i * 7919) % 97) as f64;
+            let member = Bytes::from(format!("m{}", i));
+            list.insert(score, member.clone());
+            expected.push((score, member));
+            if i % 3 == 0 {
+                let (score, member) = expected.remove((i as usize * 31) % expected.len());
+                assert!(list.delete(score, &member));
+            }
+        }
+        expected.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap().then_with(|| a.1.cmp(&b.1)));
+        assert_eq!(list.len(), expected.len());
+        for (rank, (score, member)) in expected.iter().enumerate() {
+            assert_eq!(list.rank(*score, member), Some(rank));
+        }
+        let all = list.range_by_rank(0, expected.len(), false);
+        assert_eq!(
+            all,
+            expected
+                .iter()
+                .map(|(score, member)| (member.clone(), *score))
+                .collect::<Vec<_>>()
+        );
+    }
+}
@@ -0,0 +1,64 @@
+use bytes::Bytes;
+
+use super::{Backend, Value, WrongType};
+
+/// 字符串的最大长度，与 proto-max-bulk-len 的默认值一致
+pub const MAX_STRING_LEN: usize = 512 * 1024 * 1024;
+
+impl Backend {
+    /// 取出字符串并删除 key，key 不是字符串时不删除并返回 WrongType
+    pub fn getdel(&self, key: &[u8]) -> Result<Option<Bytes>, WrongType> {
+        self.expire_if_needed(key);
+        let mut wrong_type = false;
+        let removed = self.remove_value_if(key, |value| {
+            wrong_type = !matches!(value, Value::String(_));
+            !wrong_type
+        });
+        match removed.as_deref() {
+            Some(Value::String(value)) => {
+                self.mark_dirty(1);
+                Ok(Some(value.clone()))
+            }
+            _ if wrong_type => Err(WrongType),
+            _ => Ok(None),
+        }
+    }
+    /// 写入多个字符串并清除它们的过期时间
+    /// 调用方需要通过 lock_exclusive 保证其他命令看不到只写了一部分的状态
+    pub fn mset(&self, pairs: Vec<(Bytes, Bytes)>) {
+        for (key, value) in pairs {
+            self.set(key, value);
+        }
+    }
+    /// 所有 key 都不存在时才写入，返回是否写入；原子性要求同 mset
+    pub fn msetnx(&self, pairs: Vec<(Bytes, Bytes)>) -> bool {
+        if pairs.iter().any(|(key, _)| self.contains_key(key)) {
+            return false;
+        }
+        self.mset(pairs);
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_getdel_and_msetnx() {
+        let backend = Backend::new();
+        backend.set("k".into(), "v".into());
+        backend
+            .hset("h".into(), vec![("f".into(), "v".into())])
+            .unwrap();
+        assert_eq!(backend.getdel(b"k"), Ok(Some("v".into())));
+        assert_eq!(backend.getdel(b"k"), Ok(None));
+        assert_eq!(backend.getdel(b"h"), Err(WrongType));
+        assert_eq!(backend.type_of(b"h"), Some("hash"));
+
+        assert!(backend.msetnx(vec![("a".into(), "1".into()), ("b".into(), "2".into())]));
+        assert!(!backend.msetnx(vec![("c".into(), "3".into()), ("a".into(), "4".into())]));
+        assert_eq!(backend.get(b"a"), Ok(Some("1".into())));
+        assert_eq!(backend.get(b"c"), Ok(None));
+    }
+}
@@ -0,0 +1,224 @@
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    sync::Arc,
+};
+
+use bytes::Bytes;
+use dashmap::mapref::entry::Entry;
+use thiserror::Error;
+
+use super::{Backend, SortedSet};
+use crate::resp::{RespFrame, SimpleError};
+
+/// 键空间中的值，每个 key 只能是其中一种类型
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    String(Bytes),
+    Hash(HashMap<Bytes, Bytes>),
+    List(VecDeque<Bytes>),
+    Set(HashSet<Bytes>),
+    ZSet(SortedSet),
+}
+
+/// 对 key 执行了与其类型不符的操作
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Error)]
+#[error("WRONGTYPE Operation against a key holding the wrong kind of value")]
+pub struct WrongType;
+
+impl From<WrongType> for SimpleError {
+    fn from(err: WrongType) -> Self {
+        SimpleError::new(err.to_string())
+    }
+}
+impl From<WrongType> for RespFrame {
+    fn from(err: WrongType) -> Self {
+        SimpleError::from(err).into()
+    }
+}
+
+impl Value {
+    /// TYPE 命令返回的类型名
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            Value::String(_) => "string",
+            Value::Hash(_) => "hash",
+            Value::List(_) => "list",
+            Value::Set(_) => "set",
+            Value::ZSet(_) => "zset",
+        }
+    }
+    /// 容器类型为空时 key 应该被删除
+    fn is_empty(&self) -> bool {
+        match self {
+            Value::String(_) => false,
+            Value::Hash(hash) => hash.is_empty(),
+            Value::List(list) => list.is_empty(),
+            Value::Set(set) => set.is_empty(),
+            Value::ZSet(zset) => zset.is_empty(),
+        }
+    }
+}
+
+/// 可以从 Value 中取出的具体容器类型
+pub trait ValueKind: Default + Sized {
+    fn view(value: &Value) -> Option<&Self>;
+    fn view_mut(value: &mut Value) -> Option<&mut Self>;
+    fn wrap(self) -> Value;
+}
+
+macro_rules! impl_value_kind {
+    ($variant:ident, $ty:ty) => {
+        impl ValueKind for $ty {
+            fn view(value: &Value) -> Option<&Self> {
+                match value {
+                    Value::$variant(inner) => Some(inner),
+                    _ => None,
+                }
+            }
+            fn view_mut(value: &mut Value) -> Option<&mut Self> {
+                match value {
+                    Value::$variant(inner) => Some(inner),
+                    _ => None,
+                }
+            }
+            fn wrap(self) -> Value {
+                Value::$variant(self)
+            }
+        }
+    };
+}
+
+/// update/upsert 闭包的返回值，同时报告实际修改的次数，用于 --save 规则和 AOF
+/// 闭包返回 (结果, 修改次数)；可能失败时返回 Result<(结果, 修改次数), E>，失败不计数
+pub trait Changes {
+    type Output;
+    fn split(self) -> (Self::Output, usize);
+}
+
+impl<R> Changes for (R, usize) {
+    type Output = R;
+    fn split(self) -> (R, usize) {
+        self
+    }
+}
+impl<R, E> Changes for Result<(R, usize), E> {
+    type Output = Result<R, E>;
+    fn split(self) -> (Result<R, E>, usize) {
+        match self {
+            Ok((ret, changes)) => (Ok(ret), changes),
+            Err(e) => (Err(e), 0),
+        }
+    }
+}
+
+impl_value_kind!(String, Bytes);
+impl_value_kind!(Hash, HashMap<Bytes, Bytes>);
+impl_value_kind!(List, VecDeque<Bytes>);
+impl_value_kind!(Set, HashSet<Bytes>);
+impl_value_kind!(ZSet, SortedSet);
+
+impl Backend {
+    /// 只读访问 key 的值，key 不存在返回 Ok(None)，类型不符返回 WrongType
+    pub fn read<T: ValueKind, R>(
+        &self,
+        key: &[u8],
+        f: impl FnOnce(&T) -> R,
+    ) -> Result<Option<R>, WrongType> {
+        self.expire_if_needed(key);
+        match self.db.get(key) {
+            Some(value) => T::view(&value).map(|inner| Some(f(inner))).ok_or(WrongType),
+            None => Ok(None),
+        }
+    }
+    /// 修改已存在的 key，key 不存在返回 Ok(None)；修改后容器为空则删除 key
+    /// f 通过 Changes 报告修改次数，只有实际修改才计入
+    pub fn update<T: ValueKind, R: Changes>(
+        &self,
+        key: &[u8],
+        f: impl FnOnce(&mut T) -> R,
+    ) -> Result<Option<R::Output>, WrongType> {
+        self.expire_if_needed(key);
+        let (ret, changes) = match self.db.get_mut(key) {
+            Some(mut value) => T::view_mut(Arc::make_mut(&mut value))
+                .map(f)
+                .ok_or(WrongType)?
+                .split(),
+            None => return Ok(None),
+        };
+        self.mark_dirty(changes as u64);
+        self.remove_if_empty(key);
+        Ok(Some(ret))
+    }
+    /// 修改 key 的值，key 不存在时先创建空容器；修改后容器为空则删除 key
+    pub fn upsert<T: ValueKind, R: Changes>(
+        &self,
+        key: &Bytes,
+        f: impl FnOnce(&mut T) -> R,
+    ) -> Result<R::Output, WrongType> {
+        self.upsert_with(key, |inner, _| f(inner))
+    }
+    /// 同 upsert，f 的第二个参数表示 key 原本是否存在
+    pub fn upsert_with<T: ValueKind, R: Changes>(
+        &self,
+        key: &Bytes,
+        f: impl FnOnce(&mut T, bool) -> R,
+    ) -> Result<R::Output, WrongType> {
+        self.expire_if_needed(key);
+        let (ret, changes) = match self.db.entry(key.clone()) {
+            Entry::Occupied(mut entry) => T::view_mut(Arc::make_mut(entry.get_mut()))
+                .map(|inner| f(inner, true))
+                .ok_or(WrongType)?
+                .split(),
+            Entry::Vacant(entry) => {
+                let mut inner = T::default();
+                let ret = f(&mut inner, false).split();
+                self.key_index.insert(entry.key());
+                entry.insert(Arc::new(inner.wrap()));
+                ret
+            }
+        };
+        self.mark_dirty(changes as u64);
+        self.remove_if_empty(key);
+        Ok(ret)
+    }
+    /// key 的类型名，不存在返回 None
+    pub fn type_of(&self, key: &[u8]) -> Option<&'static str> {
+        self.expire_if_needed(key);
+        self.db.get(key).map(|value| value.type_name())
+    }
+    fn remove_if_empty(&self, key: &[u8]) {
+        self.remove_value_if(key, Value::is_empty);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_typed_access() {
+        let backend = Backend::new();
+        backend.set("str".into(), "hello".into());
+        let ret = backend.upsert(&"set".into(), |set: &mut HashSet<Bytes>| {
+            let added = set.insert("a".into());
+            (added, added as usize)
+        });
+        assert_eq!(ret, Ok(true));
+        assert_eq!(backend.type_of(b"set"), Some("set"));
+        assert_eq!(
+            backend.read(b"str", |set: &HashSet<Bytes>| set.len()),
+            Err(WrongType)
+        );
+        assert_eq!(
+            backend.update(b"missing", |set: &mut HashSet<Bytes>| (set.len(), 0)),
+            Ok(None)
+        );
+        // 容器为空时删除 key
+        let ret = backend.update(b"set", |set: &mut HashSet<Bytes>| {
+            let removed = set.remove(b"a".as_slice());
+            (removed, removed as usize)
+        });
+        assert_eq!(ret, Ok(Some(true)));
+        assert_eq!(backend.type_of(b"set"), None);
+    }
+}
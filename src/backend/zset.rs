@@ -0,0 +1,139 @@
+use std::collections::HashMap;
+
+use bytes::Bytes;
+
+use super::{
+    Backend, Changes, WrongType,
+    skiplist::{ScoreBound, SkipList},
+};
+
+/// 有序集合：member -> score 的哈希表，加上按 (score, member) 排序的跳表索引
+#[derive(Debug, Clone, Default)]
+pub struct SortedSet {
+    scores: HashMap<Bytes, f64>,
+    index: SkipList,
+}
+
+/// 索引由 scores 决定，只比较 scores
+impl PartialEq for SortedSet {
+    fn eq(&self, other: &Self) -> bool {
+        self.scores == other.scores
+    }
+}
+
+impl SortedSet {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    pub fn len(&self) -> usize {
+        self.index.len()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.index.is_empty()
+    }
+    pub fn score(&self, member: &[u8]) -> Option<f64> {
+        self.scores.get(member).copied()
+    }
+    /// 插入或更新分数，返回是否为新成员
+    pub fn insert(&mut self, member: Bytes, score: f64) -> bool {
+        match self.scores.insert(member.clone(), score) {
+            Some(old) => {
+                if old != score {
+                    self.index.delete(old, &member);
+                    self.index.insert(score, member);
+                }
+                false
+            }
+            None => {
+                self.index.insert(score, member);
+                true
+            }
+        }
+    }
+    /// 删除成员，返回是否存在
+    pub fn remove(&mut self, member: &[u8]) -> bool {
+        match self.scores.remove(member) {
+            Some(score) => self.index.delete(score, member),
+            None => false,
+        }
+    }
+    /// 按 (score, member) 顺序遍历所有成员
+    pub fn iter(&self) -> impl Iterator<Item = (&Bytes, f64)> {
+        self.index.iter()
+    }
+    /// 从 0 开始的排名，rev 为 true 时按分数从大到小计算
+    pub fn rank(&self, member: &[u8], rev: bool) -> Option<usize> {
+        let score = self.score(member)?;
+        let rank = self.index.rank(score, member)?;
+        Some(if rev { self.len() - 1 - rank } else { rank })
+    }
+    /// 按排名返回 [start, stop] 闭区间的 (member, score)
+    pub fn range_by_rank(&self, start: usize, stop: usize, rev: bool) -> Vec<(Bytes, f64)> {
+        self.index.range_by_rank(start, stop, rev)
+    }
+    /// 按分数返回区间内的 (member, score)，rev 为 true 时从大到小
+    pub fn range_by_score(
+        &self,
+        min: &ScoreBound,
+        max: &ScoreBound,
+        rev: bool,
+        offset: usize,
+        count: Option<usize>,
+    ) -> Vec<(Bytes, f64)> {
+        self.index.range_by_score(min, max, rev, offset, count)
+    }
+}
+
+impl Backend {
+    /// 修改有序集合，key 不存在时创建；修改后集合为空则删除 key
+    pub fn zset_update<R: Changes>(
+        &self,
+        key: &Bytes,
+        f: impl FnOnce(&mut SortedSet) -> R,
+    ) -> Result<R::Output, WrongType> {
+        self.upsert(key, f)
+    }
+    /// 只读访问有序集合，key 不存在返回 None
+    pub fn zset_read<R>(
+        &self,
+        key: &[u8],
+        f: impl FnOnce(&SortedSet) -> R,
+    ) -> Result<Option<R>, WrongType> {
+        self.read(key, f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sorted_set() {
+        let mut zset = SortedSet::new();
+        assert!(zset.insert("a".into(), 3.0));
+        assert!(zset.insert("b".into(), 1.0));
+        assert!(zset.insert("c".into(), 2.0));
+        assert!(!zset.insert("a".into(), 0.5));
+        assert_eq!(zset.rank(b"a", false), Some(0));
+        assert_eq!(zset.rank(b"a", true), Some(2));
+        assert_eq!(
+            zset.range_by_rank(0, 10, false),
+            vec![("a".into(), 0.5), ("b".into(), 1.0), ("c".into(), 2.0)]
+        );
+        assert!(zset.remove(b"b"));
+        assert!(!zset.remove(b"b"));
+        assert_eq!(zset.len(), 2);
+        assert_eq!(zset.rank(b"c", false), Some(1));
+    }
+    #[test]
+    fn test_zset_update_removes_empty() {
+        let backend = Backend::new();
+        let key = Bytes::from("zset");
+        let ret = backend.zset_update(&key, |zset| (zset.insert("a".into(), 1.0), 1));
+        assert_eq!(ret, Ok(true));
+        assert!(backend.contains_key(b"zset"));
+        let ret = backend.zset_update(&key, |zset| (zset.remove(b"a"), 1));
+        assert_eq!(ret, Ok(true));
+        assert!(!backend.contains_key(b"zset"));
+    }
+}
@@ -0,0 +1,1043 @@
+use std::mem;
+
+use bytes::{Bytes, BytesMut};
+
+use crate::{
+    backend::{Backend, MAX_STRING_LEN},
+    cmd::{
+        CommandError, CommandExecutor, command_args, extract_bytes, extract_int, extract_string,
+    },
+    resp::{RespArray, RespFrame, RespInteger, RespNull},
+};
+// Redis命令与RESP协议格式对应表
+// | 命令     | 参数                          | 对应格式                                                              |
+// |----------|-------------------------------|-----------------------------------------------------------------------|
+// | SETBIT   | key offset bit                | "*4\r\n$6\r\nsetbit\r\n$3\r\nbit\r\n$1\r\n7\r\n$1\r\n1\r\n"               |
+// | GETBIT   | key offset                    | "*3\r\n$6\r\ngetbit\r\n$3\r\nbit\r\n$1\r\n7\r\n"                          |
+// | BITCOUNT | key [start end [BYTE\|BIT]]    | "*5\r\n$8\r\nbitcount\r\n$3\r\nbit\r\n$1\r\n0\r\n$2\r\n-1\r\n$3\r\nBIT\r\n" |
+// | BITPOS   | key bit [start [end [unit]]]  | "*3\r\n$6\r\nbitpos\r\n$3\r\nbit\r\n$1\r\n1\r\n"                          |
+// | BITOP    | op dest key [key ...]         | "*5\r\n$5\r\nbitop\r\n$3\r\nAND\r\n$4\r\ndest\r\n$1\r\na\r\n$1\r\nb\r\n"  |
+// | BITFIELD | key [GET type offset ...]     | "*5\r\n$8\r\nbitfield\r\n$3\r\nbit\r\n$3\r\nGET\r\n$2\r\nu8\r\n$1\r\n0\r\n" |
+
+/// 位偏移的上限，与字符串的最大长度一致
+const MAX_BIT_OFFSET: u64 = MAX_STRING_LEN as u64 * 8;
+
+#[derive(Debug)]
+pub struct CommandSetBit {
+    key: Bytes,
+    offset: u64,
+    bit: u8,
+}
+#[derive(Debug)]
+pub struct CommandGetBit {
+    key: Bytes,
+    offset: u64,
+}
+/// 区间为 None 时统计整个字符串
+#[derive(Debug)]
+pub struct CommandBitCount {
+    key: Bytes,
+    range: Option<BitRange>,
+}
+/// 没有指定 end 时查找 0 会把字符串末尾之后视为 0
+#[derive(Debug)]
+pub struct CommandBitPos {
+    key: Bytes,
+    bit: u8,
+    start: i64,
+    end: Option<i64>,
+    unit: BitUnit,
+}
+#[derive(Debug)]
+pub struct CommandBitOp {
+    operation: BitOperation,
+    destination: Bytes,
+    keys: Vec<Bytes>,
+}
+#[derive(Debug)]
+pub struct CommandBitField {
+    key: Bytes,
+    ops: Vec<BitFieldOp>,
+}
+/// BITCOUNT / BITPOS 的闭区间，负数从末尾算起
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BitRange {
+    start: i64,
+    end: i64,
+    unit: BitUnit,
+}
+/// 区间下标的单位
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BitUnit {
+    Byte,
+    Bit,
+}
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BitOperation {
+    And,
+    Or,
+    Xor,
+    Not,
+}
+/// BITFIELD 的整数类型，如 i16、u8
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BitFieldType {
+    signed: bool,
+    bits: u32,
+}
+/// SET / INCRBY 结果超出类型范围时的处理方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BitFieldOverflow {
+    Wrap,
+    Sat,
+    Fail,
+}
+/// BITFIELD 的子命令，OVERFLOW 在解析时应用到之后的 SET / INCRBY
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BitFieldOp {
+    Get {
+        ty: BitFieldType,
+        offset: u64,
+    },
+    Set {
+        ty: BitFieldType,
+        offset: u64,
+        value: i64,
+        overflow: BitFieldOverflow,
+    },
+    IncrBy {
+        ty: BitFieldType,
+        offset: u64,
+        increment: i64,
+        overflow: BitFieldOverflow,
+    },
+}
+
+impl CommandExecutor for CommandSetBit {
+    fn execute(self, backend: &Backend) -> RespFrame {
+        let ret = backend.upsert(&self.key, |value: &mut Bytes| {
+            let len = (self.offset / 8 + 1) as usize;
+            modify(value, len, |buf| {
+                let old = get_bit(buf, self.offset);
+                set_bit(buf, self.offset, self.bit);
+                (old, 1)
+            })
+        });
+        match ret {
+            Ok(old) => RespInteger::new(old as i64).into(),
+            Err(e) => e.into(),
+        }
+    }
+}
+impl CommandExecutor for CommandGetBit {
+    fn execute(self, backend: &Backend) -> RespFrame {
+        match backend.read(&self.key, |value: &Bytes| get_bit(value, self.offset)) {
+            Ok(bit) => RespInteger::new(bit.unwrap_or_default() as i64).into(),
+            Err(e) => e.into(),
+        }
+    }
+}
+impl CommandExecutor for CommandBitCount {
+    fn execute(self, backend: &Backend) -> RespFrame {
+        let count = backend.read(&self.key, |value: &Bytes| {
+            let range = match self.range {
+                Some(range) => range.resolve(value.len()),
+                None => BitRange::whole(value.len()),
+            };
+            range.map_or(0, |(first, last)| count_bits(value, first, last))
+        });
+        match count {
+            Ok(count) => RespInteger::new(count.unwrap_or_default() as i64).into(),
+            Err(e) => e.into(),
+        }
+    }
+}
+impl CommandExecutor for CommandBitPos {
+    fn execute(self, backend: &Backend) -> RespFrame {
+        let pos = backend.read(&self.key, |value: &Bytes| {
+            let range = BitRange {
+                start: self.start,
+                end: self.end.unwrap_or(-1),
+                unit: self.unit,
+            };
+            let Some((first, last)) = range.resolve(value.len()) else {
+                return -1;
+            };
+            match find_bit(value, self.bit, first, last) {
+                Some(pos) => pos as i64,
+                // 没有限定 end 时，字符串之后的位都视为 0
+                None if self.bit == 0 && self.end.is_none() => value.len() as i64 * 8,
+                None => -1,
+            }
+        });
+        match pos {
+            Ok(Some(pos)) => RespInteger::new(pos).into(),
+            // key 不存在时视为全 0 的字符串
+            Ok(None) => RespInteger::new(if self.bit == 0 { 0 } else { -1 }).into(),
+            Err(e) => e.into(),
+        }
+    }
+}
+impl CommandExecutor for CommandBitOp {
+    /// 读取源 key 到写入 destination 之间需要独占键空间，由 execute_request 持有 lock_exclusive
+    fn execute(self, backend: &Backend) -> RespFrame {
+        let mut values = Vec::with_capacity(self.keys.len());
+        for key in &self.keys {
+            match backend.get(key) {
+                Ok(value) => values.push(value.unwrap_or_default()),
+                Err(e) => return e.into(),
+            }
+        }
+        let len = values.iter().map(Bytes::len).max().unwrap_or_default();
+        // 较短的字符串用 0 字节补齐
+        let byte_at = |value: &Bytes, i: usize| value.get(i).copied().unwrap_or_default();
+        let result = (0..len)
+            .map(|i| {
+                let mut bytes = values.iter().map(|value| byte_at(value, i));
+                let first = bytes.next().unwrap_or_default();
+                match self.operation {
+                    BitOperation::And => bytes.fold(first, |acc, byte| acc & byte),
+                    BitOperation::Or => bytes.fold(first, |acc, byte| acc | byte),
+                    BitOperation::Xor => bytes.fold(first, |acc, byte| acc ^ byte),
+                    BitOperation::Not => !first,
+                }
+            })
+            .collect::<Vec<_>>();
+        // 结果为空时删除目标 key
+        if result.is_empty() {
+            backend.remove_key(&self.destination);
+        } else {
+            backend.set(self.destination, result.into());
+        }
+        RespInteger::new(len as i64).into()
+    }
+}
+impl CommandExecutor for CommandBitField {
+    fn execute(self, backend: &Backend) -> RespFrame {
+        let len = self
+            .ops
+            .iter()
+            .filter_map(|op| match *op {
+                BitFieldOp::Get { .. } => None,
+                BitFieldOp::Set { ty, offset, .. } | BitFieldOp::IncrBy { ty, offset, .. } => {
+                    Some((offset + ty.bits as u64).div_ceil(8) as usize)
+                }
+            })
+            .max();
+        let replies = match len {
+            // 只有 GET 时不创建 key
+            None => backend
+                .read(&self.key, |value: &Bytes| {
+                    self.ops
+                        .iter()
+                        .filter_map(|op| match *op {
+                            BitFieldOp::Get { ty, offset } => {
+                                Some(RespInteger::new(ty.read(value, offset)).into())
+                            }
+                            _ => None,
+                        })
+                        .collect::<Vec<_>>()
+                })
+                .map(|replies| {
+                    replies.unwrap_or_else(|| vec![RespInteger::new(0).into(); self.ops.len()])
+                }),
+            Some(len) => backend.upsert(&self.key, |value: &mut Bytes| {
+                modify(value, len, |buf| {
+                    let replies = self
+                        .ops
+                        .iter()
+                        .map(|op| apply_bitfield_op(buf, *op))
+                        .collect::<Vec<_>>();
+                    // 每个成功的 SET/INCRBY 计一次修改，GET 和 FAIL 跳过的操作不计
+                    let changes = self
+                        .ops
+                        .iter()
+                        .zip(&replies)
+                        .filter(|(op, reply)| {
+                            !matches!(op, BitFieldOp::Get { .. })
+                                && !matches!(reply, RespFrame::RespNull(_))
+                        })
+                        .count();
+                    (replies, changes)
+                })
+            }),
+        };
+        match replies {
+            Ok(replies) => RespArray::from_vec(replies).into(),
+            Err(e) => e.into(),
+        }
+    }
+}
+
+impl BitRange {
+    /// 整个字符串对应的位下标闭区间
+    fn whole(len: usize) -> Option<(u64, u64)> {
+        (len > 0).then(|| (0, len as u64 * 8 - 1))
+    }
+    /// 换算为位下标闭区间，越界部分截断，区间为空时返回 None
+    /// 与 Redis 一致：两端都是负数且 start > end 时为空
+    fn resolve(self, len: usize) -> Option<(u64, u64)> {
+        if self.start < 0 && self.end < 0 && self.start > self.end {
+            return None;
+        }
+        let total = match self.unit {
+            BitUnit::Byte => len as i64,
+            BitUnit::Bit => len as i64 * 8,
+        };
+        let start = if self.start < 0 {
+            total + self.start
+        } else {
+            self.start
+        }
+        .max(0);
+        let end = if self.end < 0 {
+            total + self.end
+        } else {
+            self.end
+        }
+        .max(0)
+        .min(total - 1);
+        if start > end {
+            return None;
+        }
+        let (start, end) = (start as u64, end as u64);
+        Some(match self.unit {
+            BitUnit::Byte => (start * 8, end * 8 + 7),
+            BitUnit::Bit => (start, end),
+        })
+    }
+}
+
+impl BitFieldType {
+    /// 类型能表示的最小值和最大值
+    fn range(self) -> (i128, i128) {
+        if self.signed {
+            (-(1 << (self.bits - 1)), (1 << (self.bits - 1)) - 1)
+        } else {
+            (0, (1 << self.bits) - 1)
+        }
+    }
+    /// 读取 offset 开始的整数，超出字符串的部分视为 0
+    fn read(self, value: &[u8], offset: u64) -> i64 {
+        let raw = (0..self.bits as u64).fold(0u64, |acc, i| {
+            (acc << 1) | get_bit(value, offset + i) as u64
+        });
+        // 有符号数需要符号扩展
+        if self.signed && self.bits < 64 && raw >> (self.bits - 1) & 1 == 1 {
+            (raw | u64::MAX << self.bits) as i64
+        } else {
+            raw as i64
+        }
+    }
+    /// 把整数的低 bits 位写入 offset 开始的位置，调用方需要保证字符串足够长
+    fn write(self, buf: &mut [u8], offset: u64, value: i64) {
+        for i in 0..self.bits {
+            let bit = (value as u64 >> (self.bits - 1 - i)) & 1;
+            set_bit(buf, offset + i as u64, bit as u8);
+        }
+    }
+    /// 按溢出策略把结果调整到类型范围内，FAIL 策略下溢出返回 None
+    fn fit(self, value: i128, overflow: BitFieldOverflow) -> Option<i64> {
+        let (min, max) = self.range();
+        if (min..=max).contains(&value) {
+            return Some(value as i64);
+        }
+        match overflow {
+            BitFieldOverflow::Wrap => {
+                let wrapped = value.rem_euclid(1 << self.bits);
+                Some(if wrapped > max {
+                    wrapped - (1 << self.bits)
+                } else {
+                    wrapped
+                } as i64)
+            }
+            BitFieldOverflow::Sat => Some(value.clamp(min, max) as i64),
+            BitFieldOverflow::Fail => None,
+        }
+    }
+}
+
+/// 执行一个 BITFIELD 子命令：GET 返回当前值，SET 返回旧值，INCRBY 返回新值
+/// FAIL 策略下溢出时不修改并返回 nil
+fn apply_bitfield_op(buf: &mut [u8], op: BitFieldOp) -> RespFrame {
+    let (reply, update) = match op {
+        BitFieldOp::Get { ty, offset } => return RespInteger::new(ty.read(buf, offset)).into(),
+        BitFieldOp::Set {
+            ty,
+            offset,
+            value,
+            overflow,
+        } => {
+            let old = ty.read(buf, offset);
+            let new = ty.fit(value as i128, overflow);
+            (new.map(|_| old), new.map(|new| (ty, offset, new)))
+        }
+        BitFieldOp::IncrBy {
+            ty,
+            offset,
+            increment,
+            overflow,
+        } => {
+            let old = ty.read(buf, offset);
+            let new = ty.fit(old as i128 + increment as i128, overflow);
+            (new, new.map(|new| (ty, offset, new)))
+        }
+    };
+    if let Some((ty, offset, value)) = update {
+        ty.write(buf, offset, value);
+    }
+    match reply {
+        Some(reply) => RespInteger::new(reply).into(),
+        None => RespNull.into(),
+    }
+}
+
+/// 在可写副本上修改字符串，不够 len 字节时先用 0 字节补齐
+fn modify<R>(value: &mut Bytes, len: usize, f: impl FnOnce(&mut [u8]) -> R) -> R {
+    let mut buf = BytesMut::from(mem::take(value));
+    if buf.len() < len {
+        buf.resize(len, 0);
+    }
+    let ret = f(&mut buf);
+    *value = buf.freeze();
+    ret
+}
+
+/// 第 offset 位，每个字节从最高位开始编号，超出字符串的位为 0
+fn get_bit(value: &[u8], offset: u64) -> u8 {
+    value
+        .get((offset / 8) as usize)
+        .map_or(0, |byte| byte >> (7 - offset % 8) & 1)
+}
+
+/// 设置第 offset 位，调用方需要保证字符串足够长
+fn set_bit(buf: &mut [u8], offset: u64, bit: u8) {
+    let mask = 1 << (7 - offset % 8);
+    let byte = &mut buf[(offset / 8) as usize];
+    if bit == 1 {
+        *byte |= mask;
+    } else {
+        *byte &= !mask;
+    }
+}
+
+/// 位下标闭区间 [first, last] 内 1 的个数
+fn count_bits(value: &[u8], first: u64, last: u64) -> u64 {
+    (first / 8..=last / 8)
+        .map(|i| {
+            let mut byte = value[i as usize];
+            if i == first / 8 {
+                byte &= 0xff >> (first % 8);
+            }
+            if i == last / 8 {
+                byte &= 0xff << (7 - last % 8);
+            }
+            byte.count_ones() as u64
+        })
+        .sum()
+}
+
+/// 位下标闭区间 [first, last] 内第一个等于 bit 的位置
+fn find_bit(value: &[u8], bit: u8, first: u64, last: u64) -> Option<u64> {
+    // 整个字节都不可能匹配时直接跳过
+    let skip = if bit == 1 { 0x00 } else { 0xff };
+    let mut pos = first;
+    while pos <= last {
+        if pos.is_multiple_of(8) && pos + 7 <= last && value[(pos / 8) as usize] == skip {
+            pos += 8;
+            continue;
+        }
+        if get_bit(value, pos) == bit {
+            return Some(pos);
+        }
+        pos += 1;
+    }
+    None
+}
+
+/// 解析位偏移，必须在字符串的最大长度之内
+fn parse_offset(frame: &RespFrame, name: &str) -> Result<u64, CommandError> {
+    extract_int(frame, name)
+        .ok()
+        .and_then(|offset| u64::try_from(offset).ok())
+        .filter(|offset| *offset < MAX_BIT_OFFSET)
+        .ok_or_else(|| {
+            CommandError::InvalidArguments("bit offset is not an integer or out of range".into())
+        })
+}
+
+/// 解析 0 / 1
+fn parse_bit(frame: &RespFrame, name: &str, error: &str) -> Result<u8, CommandError> {
+    match extract_int(frame, name) {
+        Ok(bit @ (0 | 1)) => Ok(bit as u8),
+        _ => Err(CommandError::InvalidArguments(error.to_string())),
+    }
+}
+
+/// 解析 BYTE / BIT
+fn parse_unit(frame: &RespFrame, name: &str) -> Result<BitUnit, CommandError> {
+    match extract_string(frame, name)?.to_ascii_uppercase().as_str() {
+        "BYTE" => Ok(BitUnit::Byte),
+        "BIT" => Ok(BitUnit::Bit),
+        _ => Err(CommandError::InvalidArguments("syntax error".to_string())),
+    }
+}
+
+/// 解析 BITFIELD 的类型（i1 到 i64，u1 到 u63）和偏移（#N 表示第 N 个该类型的整数）
+fn parse_field(ty: &RespFrame, offset: &RespFrame) -> Result<(BitFieldType, u64), CommandError> {
+    let ty = extract_string(ty, "BITFIELD")?;
+    let ty = match (
+        ty.get(..1),
+        ty.get(1..).and_then(|bits| bits.parse::<u32>().ok()),
+    ) {
+        (Some("i"), Some(bits @ 1..=64)) => BitFieldType { signed: true, bits },
+        (Some("u"), Some(bits @ 1..=63)) => BitFieldType {
+            signed: false,
+            bits,
+        },
+        _ => {
+            return Err(CommandError::InvalidArguments(
+                "Invalid bitfield type. Use something like i16 u8. Note that u64 is not supported but i64 is.".to_string(),
+            ));
+        }
+    };
+    let offset_error =
+        || CommandError::InvalidArguments("bit offset is not an integer or out of range".into());
+    let offset = extract_string(offset, "BITFIELD")?;
+    let offset = match offset.strip_prefix('#') {
+        Some(index) => index
+            .parse::<u64>()
+            .ok()
+            .and_then(|index| index.checked_mul(ty.bits as u64)),
+        None => offset.parse::<u64>().ok(),
+    }
+    .filter(|offset| {
+        offset
+            .checked_add(ty.bits as u64)
+            .is_some_and(|end| end <= MAX_BIT_OFFSET)
+    })
+    .ok_or_else(offset_error)?;
+    Ok((ty, offset))
+}
+
+impl TryFrom<RespArray> for CommandSetBit {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        let args = command_args(&value)?;
+        Ok(CommandSetBit {
+            key: extract_bytes(args[0], "SETBIT")?,
+            offset: parse_offset(args[1], "SETBIT")?,
+            bit: parse_bit(args[2], "SETBIT", "bit is not an integer or out of range")?,
+        })
+    }
+}
+impl TryFrom<RespArray> for CommandGetBit {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        let args = command_args(&value)?;
+        Ok(CommandGetBit {
+            key: extract_bytes(args[0], "GETBIT")?,
+            offset: parse_offset(args[1], "GETBIT")?,
+        })
+    }
+}
+impl TryFrom<RespArray> for CommandBitCount {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        let args = command_args(&value)?;
+        let key = extract_bytes(args[0], "BITCOUNT")?;
+        let range = match args[1..] {
+            [] => None,
+            [start, end] | [start, end, _] => Some(BitRange {
+                start: extract_int(start, "BITCOUNT")?,
+                end: extract_int(end, "BITCOUNT")?,
+                unit: match args.get(3) {
+                    Some(unit) => parse_unit(unit, "BITCOUNT")?,
+                    None => BitUnit::Byte,
+                },
+            }),
+            _ => return Err(CommandError::InvalidArguments("syntax error".to_string())),
+        };
+        Ok(CommandBitCount { key, range })
+    }
+}
+impl TryFrom<RespArray> for CommandBitPos {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        let args = command_args(&value)?;
+        if args.len() > 5 {
+            return Err(CommandError::InvalidArguments("syntax error".to_string()));
+        }
+        Ok(CommandBitPos {
+            key: extract_bytes(args[0], "BITPOS")?,
+            bit: parse_bit(args[1], "BITPOS", "The bit argument must be 1 or 0.")?,
+            start: match args.get(2) {
+                Some(start) => extract_int(start, "BITPOS")?,
+                None => 0,
+            },
+            end: args
+                .get(3)
+                .map(|end| extract_int(end, "BITPOS"))
+                .transpose()?,
+            unit: match args.get(4) {
+                Some(unit) => parse_unit(unit, "BITPOS")?,
+                None => BitUnit::Byte,
+            },
+        })
+    }
+}
+impl TryFrom<RespArray> for CommandBitOp {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        let args = command_args(&value)?;
+        let operation = match extract_string(args[0], "BITOP")?
+            .to_ascii_uppercase()
+            .as_str()
+        {
+            "AND" => BitOperation::And,
+            "OR" => BitOperation::Or,
+            "XOR" => BitOperation::Xor,
+            "NOT" => BitOperation::Not,
+            _ => return Err(CommandError::InvalidArguments("syntax error".to_string())),
+        };
+        let keys = args[2..]
+            .iter()
+            .map(|arg| extract_bytes(arg, "BITOP"))
+            .collect::<Result<Vec<_>, _>>()?;
+        if operation == BitOperation::Not && keys.len() != 1 {
+            return Err(CommandError::InvalidArguments(
+                "BITOP NOT must be called with a single source key.".to_string(),
+            ));
+        }
+        Ok(CommandBitOp {
+            operation,
+            destination: extract_bytes(args[1], "BITOP")?,
+            keys,
+        })
+    }
+}
+impl TryFrom<RespArray> for CommandBitField {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        let args = command_args(&value)?;
+        let key = extract_bytes(args[0], "BITFIELD")?;
+        let syntax_error = || CommandError::InvalidArguments("syntax error".to_string());
+        let mut overflow = BitFieldOverflow::Wrap;
+        let mut ops = Vec::new();
+        let mut options = args[1..].iter();
+        while let Some(option) = options.next() {
+            let option = extract_string(option, "BITFIELD")?.to_ascii_uppercase();
+            let mut next = || options.next().ok_or_else(syntax_error);
+            match option.as_str() {
+                "GET" => {
+                    let (ty, offset) = parse_field(next()?, next()?)?;
+                    ops.push(BitFieldOp::Get { ty, offset });
+                }
+                "SET" => {
+                    let (ty, offset) = parse_field(next()?, next()?)?;
+                    let value = extract_int(next()?, "BITFIELD")?;
+                    ops.push(BitFieldOp::Set {
+                        ty,
+                        offset,
+                        value,
+                        overflow,
+                    });
+                }
+                "INCRBY" => {
+                    let (ty, offset) = parse_field(next()?, next()?)?;
+                    let increment = extract_int(next()?, "BITFIELD")?;
+                    ops.push(BitFieldOp::IncrBy {
+                        ty,
+                        offset,
+                        increment,
+                        overflow,
+                    });
+                }
+                "OVERFLOW" => {
+                    overflow = match extract_string(next()?, "BITFIELD")?
+                        .to_ascii_uppercase()
+                        .as_str()
+                    {
+                        "WRAP" => BitFieldOverflow::Wrap,
+                        "SAT" => BitFieldOverflow::Sat,
+                        "FAIL" => BitFieldOverflow::Fail,
+                        _ => {
+                            return Err(CommandError::InvalidArguments(
+                                "Invalid OVERFLOW type specified".to_string(),
+                            ));
+                        }
+                    };
+                }
+                _ => return Err(syntax_error()),
+            }
+        }
+        Ok(CommandBitField { key, ops })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{cmd::Command, resp::SimpleError};
+
+    fn run(backend: &Backend, args: &[&str]) -> RespFrame {
+        let frames = args.iter().map(|arg| (*arg).into()).collect::<Vec<_>>();
+        match Command::try_from(RespArray::from(frames)) {
+            Ok(cmd) => cmd.execute(backend),
+            Err(e) => SimpleError::new(format!("ERR {}", e)).into(),
+        }
+    }
+
+    fn int(n: i64) -> RespFrame {
+        RespInteger::new(n).into()
+    }
+
+    #[test]
+    fn test_setbit_getbit() {
+        let backend = Backend::new();
+        assert_eq!(run(&backend, &["setbit", "bit", "7", "1"]), int(0));
+        assert_eq!(run(&backend, &["setbit", "bit", "7", "1"]), int(1));
+        assert_eq!(backend.get(b"bit"), Ok(Some(Bytes::from_static(b"\x01"))));
+        // 自动扩展并用 0 字节补齐
+        assert_eq!(run(&backend, &["setbit", "bit", "20", "1"]), int(0));
+        assert_eq!(
+            backend.get(b"bit"),
+            Ok(Some(Bytes::from_static(b"\x01\x00\x08")))
+        );
+        assert_eq!(run(&backend, &["getbit", "bit", "20"]), int(1));
+        assert_eq!(run(&backend, &["getbit", "bit", "21"]), int(0));
+        assert_eq!(run(&backend, &["getbit", "bit", "1000"]), int(0));
+        assert_eq!(run(&backend, &["getbit", "missing", "0"]), int(0));
+        assert_eq!(run(&backend, &["setbit", "bit", "7", "0"]), int(1));
+        assert_eq!(run(&backend, &["getbit", "bit", "7"]), int(0));
+
+        assert!(matches!(
+            run(&backend, &["setbit", "bit", "-1", "1"]),
+            RespFrame::SimpleError(_)
+        ));
+        assert!(matches!(
+            run(&backend, &["setbit", "bit", "4294967296", "1"]),
+            RespFrame::SimpleError(_)
+        ));
+        assert!(matches!(
+            run(&backend, &["setbit", "bit", "0", "2"]),
+            RespFrame::SimpleError(_)
+        ));
+        backend
+            .hset("h".into(), vec![("f".into(), "v".into())])
+            .unwrap();
+        assert!(matches!(
+            run(&backend, &["setbit", "h", "0", "1"]),
+            RespFrame::SimpleError(_)
+        ));
+    }
+
+    #[test]
+    fn test_bitcount_bitpos() {
+        let backend = Backend::new();
+        backend.set("s".into(), "foobar".into());
+        assert_eq!(run(&backend, &["bitcount", "s"]), int(26));
+        assert_eq!(run(&backend, &["bitcount", "s", "0", "0"]), int(4));
+        assert_eq!(run(&backend, &["bitcount", "s", "1", "1"]), int(6));
+        assert_eq!(run(&backend, &["bitcount", "s", "1", "1", "BYTE"]), int(6));
+        assert_eq!(run(&backend, &["bitcount", "s", "5", "30", "BIT"]), int(17));
+        assert_eq!(run(&backend, &["bitcount", "s", "-2", "-1"]), int(7));
+        assert_eq!(run(&backend, &["bitcount", "s", "-1", "-2"]), int(0));
+        assert_eq!(run(&backend, &["bitcount", "missing"]), int(0));
+        assert!(matches!(
+            run(&backend, &["bitcount", "s", "0"]),
+            RespFrame::SimpleError(_)
+        ));
+
+        backend.set("p".into(), Bytes::from_static(b"\xff\xf0\x00"));
+        assert_eq!(run(&backend, &["bitpos", "p", "0"]), int(12));
+        assert_eq!(run(&backend, &["bitpos", "p", "1", "2"]), int(-1));
+        assert_eq!(run(&backend, &["bitpos", "p", "1", "1"]), int(8));
+        assert_eq!(run(&backend, &["bitpos", "p", "0", "2", "-1"]), int(16));
+        assert_eq!(
+            run(&backend, &["bitpos", "p", "1", "7", "15", "BIT"]),
+            int(7)
+        );
+        assert_eq!(
+            run(&backend, &["bitpos", "p", "0", "7", "11", "BIT"]),
+            int(-1)
+        );
+        // 没有指定 end 时全 1 字符串之后的第一位视为 0
+        backend.set("ones".into(), Bytes::from_static(b"\xff\xff"));
+        assert_eq!(run(&backend, &["bitpos", "ones", "0"]), int(16));
+        assert_eq!(run(&backend, &["bitpos", "ones", "0", "0", "-1"]), int(-1));
+        assert_eq!(run(&backend, &["bitpos", "missing", "0"]), int(0));
+        assert_eq!(run(&backend, &["bitpos", "missing", "1"]), int(-1));
+        assert!(matches!(
+            run(&backend, &["bitpos", "p", "2"]),
+            RespFrame::SimpleError(_)
+        ));
+    }
+
+    #[test]
+    fn test_bitop() {
+        let backend = Backend::new();
+        backend.set("a".into(), Bytes::from_static(b"\xf0\x0f"));
+        backend.set("b".into(), Bytes::from_static(b"\x3c"));
+        assert_eq!(run(&backend, &["bitop", "AND", "dest", "a", "b"]), int(2));
+        assert_eq!(
+            backend.get(b"dest"),
+            Ok(Some(Bytes::from_static(b"\x30\x00")))
+        );
+        run(&backend, &["bitop", "or", "dest", "a", "b", "missing"]);
+        assert_eq!(
+            backend.get(b"dest"),
+            Ok(Some(Bytes::from_static(b"\xfc\x0f")))
+        );
+        run(&backend, &["bitop", "XOR", "dest", "a", "b"]);
+        assert_eq!(
+            backend.get(b"dest"),
+            Ok(Some(Bytes::from_static(b"\xcc\x0f")))
+        );
+        run(&backend, &["bitop", "NOT", "dest", "a"]);
+        assert_eq!(
+            backend.get(b"dest"),
+            Ok(Some(Bytes::from_static(b"\x0f\xf0")))
+        );
+        // 所有源都不存在时删除目标 key
+        assert_eq!(run(&backend, &["bitop", "AND", "dest", "x", "y"]), int(0));
+        assert!(!backend.contains_key(b"dest"));
+        assert!(matches!(
+            run(&backend, &["bitop", "NOT", "dest", "a", "b"]),
+            RespFrame::SimpleError(_)
+        ));
+        assert!(matches!(
+            run(&backend, &["bitop", "NAND", "dest", "a"]),
+            RespFrame::SimpleError(_)
+        ));
+    }
+
+    #[test]
+    fn test_bitfield() {
+        let backend = Backend::new();
+        let array = |frames: Vec<RespFrame>| RespFrame::from(RespArray::from_vec(frames));
+        assert_eq!(
+            run(&backend, &["bitfield", "bf", "GET", "u8", "0"]),
+            array(vec![int(0)])
+        );
+        assert!(!backend.contains_key(b"bf"));
+        assert_eq!(
+            run(
+                &backend,
+                &["bitfield", "bf", "SET", "i8", "0", "-100", "GET", "u4", "0"]
+            ),
+            array(vec![int(0), int(0x9)])
+        );
+        assert_eq!(backend.get(b"bf"), Ok(Some(Bytes::from_static(b"\x9c"))));
+        // #N 表示第 N 个该类型的整数
+        assert_eq!(
+            run(
+                &backend,
+                &[
+                    "bitfield", "bf", "SET", "u8", "#1", "255", "GET", "u16", "0"
+                ]
+            ),
+            array(vec![int(0), int(0x9cff)])
+        );
+        assert_eq!(
+            run(
+                &backend,
+                &[
+                    "bitfield", "bf", "INCRBY", "u8", "8", "10", "INCRBY", "i8", "0", "-100"
+                ]
+            ),
+            array(vec![int(9), int(56)])
+        );
+        assert_eq!(
+            run(
+                &backend,
+                &[
+                    "bitfield", "bf", "OVERFLOW", "SAT", "INCRBY", "u8", "8", "300", "INCRBY",
+                    "i8", "0", "-300", "OVERFLOW", "FAIL", "INCRBY", "u8", "8", "1", "SET", "i8",
+                    "0", "200",
+                ]
+            ),
+            array(vec![int(255), int(-128), RespNull.into(), RespNull.into()])
+        );
+        assert_eq!(
+            run(
+                &backend,
+                &["bitfield", "bf", "GET", "i8", "0", "GET", "u8", "8"]
+            ),
+            array(vec![int(-128), int(255)])
+        );
+        assert_eq!(
+            run(
+                &backend,
+                &[
+                    "bitfield",
+                    "bf",
+                    "SET",
+                    "i64",
+                    "64",
+                    "9223372036854775807",
+                    "INCRBY",
+                    "i64",
+                    "64",
+                    "1"
+                ]
+            ),
+            array(vec![int(0), int(i64::MIN)])
+        );
+        assert!(matches!(
+            run(&backend, &["bitfield", "bf", "GET", "u64", "0"]),
+            RespFrame::SimpleError(_)
+        ));
+        assert!(matches!(
+            run(&backend, &["bitfield", "bf", "OVERFLOW", "LOOP"]),
+            RespFrame::SimpleError(_)
+        ));
+        assert!(matches!(
+            run(&backend, &["bitfield", "bf", "SET", "u8", "0"]),
+            RespFrame::SimpleError(_)
+        ));
+    }
+
+    #[test]
+    fn test_bitfield_overflow_modes() {
+        let backend = Backend::new();
+        let array = |frames: Vec<RespFrame>| RespFrame::from(RespArray::from_vec(frames));
+        let bitfield = |args: &[&str]| {
+            let mut request = vec!["bitfield", "w"];
+            request.extend_from_slice(args);
+            run(&backend, &request)
+        };
+        // 默认 WRAP
+        assert_eq!(bitfield(&["SET", "u8", "0", "250"]), array(vec![int(0)]));
+        assert_eq!(bitfield(&["INCRBY", "u8", "0", "10"]), array(vec![int(4)]));
+        assert_eq!(
+            bitfield(&["SET", "i8", "8", "127", "INCRBY", "i8", "8", "1"]),
+            array(vec![int(0), int(-128)])
+        );
+        assert_eq!(
+            bitfield(&["OVERFLOW", "WRAP", "SET", "u8", "0", "260"]),
+            array(vec![int(4)])
+        );
+        assert_eq!(bitfield(&["GET", "u8", "0"]), array(vec![int(4)]));
+
+        // SAT 对 SET 和 INCRBY 都生效，两个方向都饱和
+        assert_eq!(
+            bitfield(&[
+                "OVERFLOW", "SAT", "SET", "u8", "0", "300", "INCRBY", "i8", "8", "-1", "INCRBY",
+                "u8", "0", "-1000",
+            ]),
+            array(vec![int(4), int(-128), int(0)])
+        );
+        assert_eq!(
+            bitfield(&["OVERFLOW", "sat", "INCRBY", "i8", "8", "1000"]),
+            array(vec![int(127)])
+        );
+
+        // FAIL 时回复 nil 且不修改，OVERFLOW 只影响其后的操作
+        assert_eq!(
+            bitfield(&[
+                "OVERFLOW", "FAIL", "INCRBY", "u8", "0", "-1", "INCRBY", "u8", "0", "256",
+                "OVERFLOW", "WRAP", "INCRBY", "u8", "0", "257", "GET", "u8", "0",
+            ]),
+            array(vec![RespNull.into(), RespNull.into(), int(1), int(1)])
+        );
+        assert_eq!(
+            bitfield(&[
+                "OVERFLOW", "FAIL", "SET", "i8", "8", "-129", "GET", "i8", "8"
+            ]),
+            array(vec![RespNull.into(), int(127)])
+        );
+    }
+
+    #[test]
+    fn test_bitfield_offsets() {
+        let backend = Backend::new();
+        let array = |frames: Vec<RespFrame>| RespFrame::from(RespArray::from_vec(frames));
+        // #N 按类型宽度换算偏移
+        assert_eq!(
+            run(
+                &backend,
+                &[
+                    "bitfield", "idx", "SET", "u4", "#1", "15", "SET", "u4", "#3", "15", "GET",
+                    "u16", "0", "GET", "i16", "#1",
+                ]
+            ),
+            array(vec![int(0), int(0), int(0x0f0f), int(0)])
+        );
+        assert_eq!(
+            backend.get(b"idx"),
+            Ok(Some(Bytes::from_static(b"\x0f\x0f")))
+        );
+
+        // 最后一个完整字段可以读取，越过上限或在计算中溢出的偏移都被拒绝
+        let last = (MAX_BIT_OFFSET - 8).to_string();
+        assert_eq!(
+            run(&backend, &["bitfield", "big", "GET", "u8", &last]),
+            array(vec![int(0)])
+        );
+        let error = SimpleError::new("ERR bit offset is not an integer or out of range");
+        for offset in [
+            "4294967289",
+            "18446744073709551615",
+            "#2305843009213693951",
+            "#18446744073709551615",
+            "#-1",
+            "#",
+            "-1",
+        ] {
+            assert_eq!(
+                run(&backend, &["bitfield", "big", "GET", "u8", offset]),
+                error.clone().into()
+            );
+            assert_eq!(
+                run(&backend, &["bitfield", "big", "SET", "u8", offset, "1"]),
+                error.clone().into()
+            );
+        }
+        assert!(!backend.contains_key(b"big"));
+    }
+
+    #[test]
+    fn test_bitcount_bitpos_ranges() {
+        let backend = Backend::new();
+        backend.set("s".into(), "foobar".into());
+        let bitcount = |args: &[&str]| {
+            let mut request = vec!["bitcount", "s"];
+            request.extend_from_slice(args);
+            run(&backend, &request)
+        };
+        assert_eq!(bitcount(&["0", "-1", "BIT"]), int(26));
+        assert_eq!(bitcount(&["-8", "-1", "bit"]), int(4));
+        assert_eq!(bitcount(&["1", "6", "BIT"]), int(4));
+        assert_eq!(bitcount(&["100", "200", "BIT"]), int(0));
+        assert_eq!(bitcount(&["0", "1000", "BYTE"]), int(26));
+        assert_eq!(bitcount(&["-100", "-1"]), int(26));
+        assert_eq!(bitcount(&["2", "1", "BYTE"]), int(0));
+        assert!(matches!(
+            bitcount(&["0", "-1", "BITS"]),
+            RespFrame::SimpleError(_)
+        ));
+        assert!(matches!(bitcount(&["a", "-1"]), RespFrame::SimpleError(_)));
+
+        backend.set("p".into(), Bytes::from_static(b"\x00\xff\xf0"));
+        let bitpos = |args: &[&str]| {
+            let mut request = vec!["bitpos", "p"];
+            request.extend_from_slice(args);
+            run(&backend, &request)
+        };
+        assert_eq!(bitpos(&["1"]), int(8));
+        assert_eq!(bitpos(&["1", "0", "0"]), int(-1));
+        assert_eq!(bitpos(&["1", "-1"]), int(16));
+        assert_eq!(bitpos(&["0", "1"]), int(20));
+        assert_eq!(bitpos(&["0", "1", "1"]), int(-1));
+        assert_eq!(bitpos(&["1", "2", "1"]), int(-1));
+        assert_eq!(bitpos(&["1", "3", "9", "BIT"]), int(8));
+        assert_eq!(bitpos(&["0", "8", "19", "BIT"]), int(-1));
+        assert_eq!(bitpos(&["0", "8", "20", "bit"]), int(20));
+        assert_eq!(bitpos(&["1", "-4", "-1", "BIT"]), int(-1));
+        assert!(matches!(
+            bitpos(&["1", "0", "-1", "BITS"]),
+            RespFrame::SimpleError(_)
+        ));
+    }
+}
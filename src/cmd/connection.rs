@@ -0,0 +1,171 @@
+use crate::{
+    backend::ClientHandle,
+    cmd::{CommandError, command_args, extract_string},
+    resp::{RespArray, RespFrame, RespInteger, RespMap, SimpleError},
+};
+
+// Redis命令与RESP协议格式对应表
+// | 命令    | 参数         | 对应格式                                                                 |
+// |---------|--------------|--------------------------------------------------------------------------|
+// | HELLO   | [protover [AUTH user pass] [SETNAME name]] | "*2\r\n$5\r\nhello\r\n$1\r\n3\r\n"         |
+
+/// HELLO 修改的是连接状态，需要连接句柄才能执行
+#[derive(Debug, PartialEq)]
+pub struct CommandHello {
+    /// None 表示不切换协议，只返回连接信息
+    protocol: Option<i64>,
+    auth: Option<(String, String)>,
+    name: Option<String>,
+}
+
+impl CommandHello {
+    pub fn execute_for_client(self, client: &ClientHandle) -> RespFrame {
+        if let Some(protocol) = self.protocol
+            && !(2..=3).contains(&protocol)
+        {
+            return SimpleError::new("NOPROTO sorry, this protocol version is not supported.")
+                .into();
+        }
+        // 没有配置密码，default 用户不需要密码
+        if let Some((user, _)) = &self.auth
+            && user != "default"
+        {
+            return SimpleError::new(
+                "WRONGPASS invalid username-password pair or user is disabled.",
+            )
+            .into();
+        }
+        if let Some(protocol) = self.protocol {
+            client.set_protocol(protocol as u8);
+        }
+        if let Some(name) = self.name {
+            // 空字符串表示清除连接名
+            client.set_name((!name.is_empty()).then_some(name));
+        }
+
+        let mut map = RespMap::default();
+        map.insert("server".to_string(), "dredis".into());
+        map.insert("version".to_string(), env!("CARGO_PKG_VERSION").into());
+        map.insert(
+            "proto".to_string(),
+            RespInteger::new(client.protocol() as i64).into(),
+        );
+        map.insert(
+            "id".to_string(),
+            RespInteger::new(client.id() as i64).into(),
+        );
+        map.insert("mode".to_string(), "standalone".into());
+        map.insert("role".to_string(), "master".into());
+        map.insert("modules".to_string(), RespArray::from_vec(vec![]).into());
+        map.into()
+    }
+}
+
+impl TryFrom<RespArray> for CommandHello {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        let args = command_args(&value)?;
+        let mut args = args.into_iter();
+        let Some(protocol) = args.next() else {
+            return Ok(CommandHello {
+                protocol: None,
+                auth: None,
+                name: None,
+            });
+        };
+        let protocol = extract_string(protocol, "HELLO")?.parse().map_err(|_| {
+            CommandError::InvalidArguments(
+                "Protocol version is not an integer or out of range".into(),
+            )
+        })?;
+        let mut hello = CommandHello {
+            protocol: Some(protocol),
+            auth: None,
+            name: None,
+        };
+        while let Some(option) = args.next() {
+            let option = extract_string(option, "HELLO")?;
+            match option.to_ascii_lowercase().as_str() {
+                "auth" => match (args.next(), args.next()) {
+                    (Some(user), Some(pass)) => {
+                        hello.auth = Some((
+                            extract_string(user, "HELLO")?,
+                            extract_string(pass, "HELLO")?,
+                        ));
+                    }
+                    _ => return Err(syntax_error(&option)),
+                },
+                "setname" => {
+                    let name = extract_string(args.next().ok_or(syntax_error(&option))?, "HELLO")?;
+                    // 与 Redis 相同，连接名只能包含可见字符且不能有空格
+                    if name.bytes().any(|b| !(b'!'..=b'~').contains(&b)) {
+                        return Err(CommandError::InvalidArguments(
+                            "Client names cannot contain spaces, newlines or special characters."
+                                .into(),
+                        ));
+                    }
+                    hello.name = Some(name);
+                }
+                _ => return Err(syntax_error(&option)),
+            }
+        }
+        Ok(hello)
+    }
+}
+
+fn syntax_error(option: &str) -> CommandError {
+    CommandError::InvalidArguments(format!("Syntax error in HELLO option '{}'", option))
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+
+    use super::*;
+    use crate::backend::Backend;
+
+    fn hello(args: &[&str]) -> Result<CommandHello, CommandError> {
+        let frames = args.iter().map(|arg| (*arg).into()).collect::<Vec<_>>();
+        CommandHello::try_from(RespArray::from(frames))
+    }
+
+    #[test]
+    fn test_hello() -> Result<()> {
+        let backend = Backend::new();
+        let client = backend.register_client("127.0.0.1:40000".parse()?);
+        assert_eq!(client.protocol(), 2);
+
+        let RespFrame::Map(map) =
+            hello(&["hello", "3", "SETNAME", "conn"])?.execute_for_client(&client)
+        else {
+            panic!("HELLO should reply with a map");
+        };
+        assert_eq!(map.get("proto"), Some(&RespInteger::new(3).into()));
+        assert_eq!(client.protocol(), 3);
+        assert_eq!(backend.clients()[0].name.as_deref(), Some("conn"));
+
+        // 不带参数时不切换协议
+        hello(&["hello"])?.execute_for_client(&client);
+        assert_eq!(client.protocol(), 3);
+
+        // 校验失败时连接状态不变
+        assert_eq!(
+            hello(&["hello", "4"])?.execute_for_client(&client),
+            SimpleError::new("NOPROTO sorry, this protocol version is not supported.").into()
+        );
+        assert_eq!(
+            hello(&["hello", "2", "AUTH", "admin", "secret"])?.execute_for_client(&client),
+            SimpleError::new("WRONGPASS invalid username-password pair or user is disabled.")
+                .into()
+        );
+        assert_eq!(client.protocol(), 3);
+        hello(&["hello", "2", "auth", "default", "any"])?.execute_for_client(&client);
+        assert_eq!(client.protocol(), 2);
+
+        assert!(hello(&["hello", "x"]).is_err());
+        assert!(hello(&["hello", "3", "AUTH", "default"]).is_err());
+        assert!(hello(&["hello", "3", "SETNAME", "a b"]).is_err());
+        assert!(hello(&["hello", "3", "FOO"]).is_err());
+        Ok(())
+    }
+}
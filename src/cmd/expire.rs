@@ -0,0 +1,423 @@
+use bytes::Bytes;
+
+use crate::{
+    backend::{Backend, now_ms},
+    cmd::{
+        CommandError, CommandExecutor, bulk_array, command_args, extract_bytes, extract_int,
+        extract_string,
+    },
+    resp::{RespArray, RespFrame, RespInteger},
+};
+
+// Redis命令与RESP协议格式对应表
+// | 命令    | 参数         | 对应格式                                                                 |
+// |---------|--------------|--------------------------------------------------------------------------|
+// | EXPIRE  | key seconds  | "*3\r\n$6\r\nexpire\r\n$5\r\nhello\r\n$2\r\n10\r\n"                      |
+// | PEXPIRE | key millis   | "*3\r\n$7\r\npexpire\r\n$5\r\nhello\r\n$4\r\n1000\r\n"                   |
+// | EXPIREAT  | key unix-sec | "*3\r\n$8\r\nexpireat\r\n$5\r\nhello\r\n$10\r\n1700000000\r\n"          |
+// | PEXPIREAT | key unix-ms  | "*3\r\n$9\r\npexpireat\r\n$5\r\nhello\r\n$13\r\n1700000000000\r\n"      |
+// | TTL     | key          | "*2\r\n$3\r\nttl\r\n$5\r\nhello\r\n"                                     |
+// | PTTL    | key          | "*2\r\n$4\r\npttl\r\n$5\r\nhello\r\n"                                    |
+// | PERSIST | key          | "*2\r\n$7\r\npersist\r\n$5\r\nhello\r\n"                                 |
+
+/// EXPIRE 的条件选项
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ExpireCondition {
+    #[default]
+    Always,
+    /// NX: 只在 key 没有过期时间时设置
+    Nx,
+    /// XX: 只在 key 已有过期时间时设置
+    Xx,
+    /// GT: 只在新过期时间大于当前过期时间时设置
+    Gt,
+    /// LT: 只在新过期时间小于当前过期时间时设置
+    Lt,
+}
+
+impl ExpireCondition {
+    /// 命令中对应的选项
+    pub fn option(&self) -> Option<&'static str> {
+        match self {
+            ExpireCondition::Always => None,
+            ExpireCondition::Nx => Some("NX"),
+            ExpireCondition::Xx => Some("XX"),
+            ExpireCondition::Gt => Some("GT"),
+            ExpireCondition::Lt => Some("LT"),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct CommandExpire {
+    key: Bytes,
+    seconds: i64,
+    condition: ExpireCondition,
+}
+#[derive(Debug)]
+pub struct CommandPExpire {
+    key: Bytes,
+    millis: i64,
+    condition: ExpireCondition,
+}
+#[derive(Debug)]
+pub struct CommandExpireAt {
+    key: Bytes,
+    seconds: i64,
+    condition: ExpireCondition,
+}
+#[derive(Debug)]
+pub struct CommandPExpireAt {
+    key: Bytes,
+    millis: i64,
+    condition: ExpireCondition,
+}
+#[derive(Debug)]
+pub struct CommandTtl {
+    key: Bytes,
+}
+#[derive(Debug)]
+pub struct CommandPTtl {
+    key: Bytes,
+}
+#[derive(Debug)]
+pub struct CommandPersist {
+    key: Bytes,
+}
+
+/// 相对时间（毫秒）转换为 unix 毫秒
+fn deadline(millis: i64) -> i64 {
+    (now_ms() as i64).saturating_add(millis)
+}
+
+/// 按条件设置过期时间（unix 毫秒），返回 1 表示设置成功，0 表示 key 不存在或条件不满足
+fn expire_with_condition(
+    backend: &Backend,
+    key: &Bytes,
+    at: i64,
+    condition: ExpireCondition,
+) -> RespFrame {
+    if !backend.contains_key(key) {
+        return RespInteger::new(0).into();
+    }
+    let at = at.max(0) as u64;
+    let current = backend.expire_time(key);
+    let ok = match (condition, current) {
+        (ExpireCondition::Always, _) => true,
+        (ExpireCondition::Nx, current) => current.is_none(),
+        (ExpireCondition::Xx, current) => current.is_some(),
+        // 没有过期时间视为永久，任何时间都不大于永久
+        (ExpireCondition::Gt, current) => current.is_some_and(|current| at > current),
+        (ExpireCondition::Lt, current) => current.is_none_or(|current| at < current),
+    };
+    if ok && backend.expire_at(key, at) {
+        RespInteger::new(1).into()
+    } else {
+        RespInteger::new(0).into()
+    }
+}
+
+impl CommandExecutor for CommandExpire {
+    fn execute(self, backend: &Backend) -> RespFrame {
+        let at = deadline(self.seconds.saturating_mul(1000));
+        expire_with_condition(backend, &self.key, at, self.condition)
+    }
+}
+impl CommandExecutor for CommandPExpire {
+    fn execute(self, backend: &Backend) -> RespFrame {
+        expire_with_condition(backend, &self.key, deadline(self.millis), self.condition)
+    }
+}
+impl CommandExecutor for CommandExpireAt {
+    fn execute(self, backend: &Backend) -> RespFrame {
+        let at = self.seconds.saturating_mul(1000);
+        expire_with_condition(backend, &self.key, at, self.condition)
+    }
+}
+impl CommandExecutor for CommandPExpireAt {
+    fn execute(self, backend: &Backend) -> RespFrame {
+        expire_with_condition(backend, &self.key, self.millis, self.condition)
+    }
+}
+
+/// 相对过期时间写入 AOF 时转换为 PEXPIREAT，重放时不受重启时间影响
+fn pexpireat_frame(key: &Bytes, at: i64, condition: ExpireCondition) -> RespFrame {
+    let mut args = vec![
+        Bytes::from_static(b"pexpireat"),
+        key.clone(),
+        Bytes::from(at.to_string()),
+    ];
+    args.extend(condition.option().map(Bytes::from));
+    bulk_array(args)
+}
+impl CommandExpire {
+    /// 换算为 PEXPIREAT，执行和写入 AOF 使用同一个过期时间
+    pub fn absolute(self) -> CommandPExpireAt {
+        CommandPExpireAt {
+            key: self.key,
+            millis: deadline(self.seconds.saturating_mul(1000)),
+            condition: self.condition,
+        }
+    }
+}
+impl CommandPExpire {
+    /// 换算为 PEXPIREAT，同 CommandExpire::absolute
+    pub fn absolute(self) -> CommandPExpireAt {
+        CommandPExpireAt {
+            key: self.key,
+            millis: deadline(self.millis),
+            condition: self.condition,
+        }
+    }
+}
+impl CommandPExpireAt {
+    pub fn aof_frame(&self) -> RespFrame {
+        pexpireat_frame(&self.key, self.millis, self.condition)
+    }
+}
+impl CommandExecutor for CommandTtl {
+    fn execute(self, backend: &Backend) -> RespFrame {
+        let ttl = match backend.pttl(&self.key) {
+            ttl if ttl < 0 => ttl,
+            // 四舍五入到秒
+            ttl => (ttl + 500) / 1000,
+        };
+        RespInteger::new(ttl).into()
+    }
+}
+impl CommandExecutor for CommandPTtl {
+    fn execute(self, backend: &Backend) -> RespFrame {
+        RespInteger::new(backend.pttl(&self.key)).into()
+    }
+}
+impl CommandExecutor for CommandPersist {
+    fn execute(self, backend: &Backend) -> RespFrame {
+        RespInteger::new(backend.persist(&self.key) as i64).into()
+    }
+}
+
+/// 解析 EXPIRE/PEXPIRE/EXPIREAT/PEXPIREAT 的参数：key time [NX|XX|GT|LT]
+fn parse_expire(
+    value: &RespArray,
+    name: &'static str,
+) -> Result<(Bytes, i64, ExpireCondition), CommandError> {
+    let args = command_args(value)?;
+    let key = extract_bytes(args[0], name)?;
+    let time = extract_int(args[1], name)?;
+    let condition = match args.get(2) {
+        None => ExpireCondition::Always,
+        Some(option) if args.len() == 3 => {
+            match extract_string(option, name)?.to_ascii_uppercase().as_str() {
+                "NX" => ExpireCondition::Nx,
+                "XX" => ExpireCondition::Xx,
+                "GT" => ExpireCondition::Gt,
+                "LT" => ExpireCondition::Lt,
+                option => {
+                    return Err(CommandError::InvalidArguments(format!(
+                        "Unsupported option {}",
+                        option
+                    )));
+                }
+            }
+        }
+        Some(_) => {
+            return Err(CommandError::InvalidArguments(
+                "NX and XX, GT or LT options at the same time are not compatible".to_string(),
+            ));
+        }
+    };
+    Ok((key, time, condition))
+}
+
+impl TryFrom<RespArray> for CommandExpire {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        let (key, seconds, condition) = parse_expire(&value, "EXPIRE")?;
+        Ok(CommandExpire {
+            key,
+            seconds,
+            condition,
+        })
+    }
+}
+impl TryFrom<RespArray> for CommandPExpire {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        let (key, millis, condition) = parse_expire(&value, "PEXPIRE")?;
+        Ok(CommandPExpire {
+            key,
+            millis,
+            condition,
+        })
+    }
+}
+impl TryFrom<RespArray> for CommandExpireAt {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        let (key, seconds, condition) = parse_expire(&value, "EXPIREAT")?;
+        Ok(CommandExpireAt {
+            key,
+            seconds,
+            condition,
+        })
+    }
+}
+impl TryFrom<RespArray> for CommandPExpireAt {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        let (key, millis, condition) = parse_expire(&value, "PEXPIREAT")?;
+        Ok(CommandPExpireAt {
+            key,
+            millis,
+            condition,
+        })
+    }
+}
+impl TryFrom<RespArray> for CommandTtl {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        let args = command_args(&value)?;
+        Ok(CommandTtl {
+            key: extract_bytes(args[0], "TTL")?,
+        })
+    }
+}
+impl TryFrom<RespArray> for CommandPTtl {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        let args = command_args(&value)?;
+        Ok(CommandPTtl {
+            key: extract_bytes(args[0], "PTTL")?,
+        })
+    }
+}
+impl TryFrom<RespArray> for CommandPersist {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        let args = command_args(&value)?;
+        Ok(CommandPersist {
+            key: extract_bytes(args[0], "PERSIST")?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::BytesMut;
+
+    use super::*;
+    use crate::resp::RespDecode;
+
+    #[test]
+    fn test_command_expire_try_from() -> Result<(), CommandError> {
+        let mut buf = BytesMut::from("*3\r\n$6\r\nexpire\r\n$5\r\nhello\r\n$2\r\n10\r\n");
+        let cmd = CommandExpire::try_from(RespArray::decode(&mut buf)?)?;
+        assert_eq!(cmd.key, "hello");
+        assert_eq!(cmd.seconds, 10);
+        assert_eq!(cmd.condition, ExpireCondition::Always);
+
+        let cmd: RespArray =
+            vec!["PEXPIRE".into(), "hello".into(), "10".into(), "gt".into()].into();
+        let cmd = CommandPExpire::try_from(cmd)?;
+        assert_eq!(cmd.millis, 10);
+        assert_eq!(cmd.condition, ExpireCondition::Gt);
+
+        let cmd: RespArray = vec!["EXPIRE".into(), "hello".into(), "ten".into()].into();
+        assert!(CommandExpire::try_from(cmd).is_err());
+        Ok(())
+    }
+    #[test]
+    fn test_expire_ttl_persist_commands() -> Result<(), CommandError> {
+        let backend = Backend::new();
+        let ttl = |key: &'static str| CommandTtl { key: key.into() }.execute(&backend);
+        assert_eq!(ttl("hello"), RespInteger::new(-2).into());
+        let cmd = CommandExpire {
+            key: "hello".into(),
+            seconds: 100,
+            condition: ExpireCondition::Always,
+        };
+        assert_eq!(cmd.execute(&backend), RespInteger::new(0).into());
+
+        backend.set("hello".into(), "world".into());
+        assert_eq!(ttl("hello"), RespInteger::new(-1).into());
+        let cmd = CommandExpire {
+            key: "hello".into(),
+            seconds: 100,
+            condition: ExpireCondition::Xx,
+        };
+        assert_eq!(cmd.execute(&backend), RespInteger::new(0).into());
+        let cmd = CommandExpire {
+            key: "hello".into(),
+            seconds: 100,
+            condition: ExpireCondition::Nx,
+        };
+        assert_eq!(cmd.execute(&backend), RespInteger::new(1).into());
+        assert_eq!(ttl("hello"), RespInteger::new(100).into());
+        let cmd = CommandPExpire {
+            key: "hello".into(),
+            millis: 5000,
+            condition: ExpireCondition::Gt,
+        };
+        assert_eq!(cmd.execute(&backend), RespInteger::new(0).into());
+
+        let cmd = CommandPersist {
+            key: "hello".into(),
+        };
+        assert_eq!(cmd.execute(&backend), RespInteger::new(1).into());
+        assert_eq!(ttl("hello"), RespInteger::new(-1).into());
+
+        // 过期时间为负数时直接删除 key
+        let cmd = CommandExpire {
+            key: "hello".into(),
+            seconds: -1,
+            condition: ExpireCondition::Always,
+        };
+        assert_eq!(cmd.execute(&backend), RespInteger::new(1).into());
+        assert_eq!(backend.get(b"hello"), Ok(None));
+        Ok(())
+    }
+    #[test]
+    fn test_expireat_commands() -> Result<(), CommandError> {
+        let backend = Backend::new();
+        backend.set("hello".into(), "world".into());
+        let at = now_ms() as i64 + 10_000;
+        let cmd: RespArray = vec![
+            "PEXPIREAT".into(),
+            "hello".into(),
+            at.to_string().as_str().into(),
+        ]
+        .into();
+        let cmd = CommandPExpireAt::try_from(cmd)?;
+        assert_eq!(cmd.execute(&backend), RespInteger::new(1).into());
+        assert_eq!(backend.expire_time(b"hello"), Some(at as u64));
+
+        let cmd = CommandExpireAt {
+            key: "hello".into(),
+            seconds: 1,
+            condition: ExpireCondition::Always,
+        };
+        assert_eq!(cmd.execute(&backend), RespInteger::new(1).into());
+        assert!(!backend.contains_key(b"hello"));
+
+        // 写入 AOF 时相对时间转换为绝对时间
+        let cmd = CommandPExpire {
+            key: "hello".into(),
+            millis: 5000,
+            condition: ExpireCondition::Nx,
+        };
+        let cmd = cmd.absolute();
+        let at = cmd.millis;
+        assert!(at >= now_ms() as i64 + 4000);
+        let RespFrame::Array(RespArray {
+            elements: Some(args),
+        }) = cmd.aof_frame()
+        else {
+            panic!("expect array");
+        };
+        assert_eq!(args.len(), 4);
+        assert_eq!(args[0], "pexpireat".into());
+        assert_eq!(args[2], at.to_string().as_str().into());
+        assert_eq!(args[3], "NX".into());
+        Ok(())
+    }
+}
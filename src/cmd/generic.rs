@@ -0,0 +1,362 @@
+use bytes::Bytes;
+
+use crate::{
+    backend::Backend,
+    cmd::{
+        CommandError, CommandExecutor, RESP_OK, bulk_array, command_args, extract_bytes,
+        random_index,
+    },
+    resp::{RespArray, RespFrame, RespInteger, RespNull, SimpleError, SimpleString},
+};
+
+// Redis命令与RESP协议格式对应表
+// | 命令    | 参数         | 对应格式                                                                 |
+// |---------|--------------|--------------------------------------------------------------------------|
+// | TYPE    | key          | "*2\r\n$4\r\ntype\r\n$5\r\nhello\r\n"                                    |
+// | DEL     | key [key ..] | "*3\r\n$3\r\ndel\r\n$5\r\nhello\r\n$5\r\nworld\r\n"                      |
+// | UNLINK  | key [key ..] | "*2\r\n$6\r\nunlink\r\n$5\r\nhello\r\n"                                  |
+// | EXISTS  | key [key ..] | "*3\r\n$6\r\nexists\r\n$5\r\nhello\r\n$5\r\nhello\r\n"                   |
+// | RENAME  | key newkey   | "*3\r\n$6\r\nrename\r\n$5\r\nhello\r\n$5\r\nworld\r\n"                   |
+// | KEYS    | pattern      | "*2\r\n$4\r\nkeys\r\n$6\r\nuser:*\r\n"                                   |
+// | RANDOMKEY |            | "*1\r\n$9\r\nrandomkey\r\n"                                               |
+// | DBSIZE  |              | "*1\r\n$6\r\ndbsize\r\n"                                                  |
+
+#[derive(Debug)]
+pub struct CommandType {
+    key: Bytes,
+}
+#[derive(Debug)]
+pub struct CommandDel {
+    keys: Vec<Bytes>,
+}
+/// 与 DEL 相同，这里没有后台释放内存的线程
+#[derive(Debug)]
+pub struct CommandUnlink {
+    keys: Vec<Bytes>,
+}
+#[derive(Debug)]
+pub struct CommandExists {
+    keys: Vec<Bytes>,
+}
+#[derive(Debug)]
+pub struct CommandRename {
+    key: Bytes,
+    new_key: Bytes,
+}
+/// 遍历整个键空间，key 很多时会阻塞其他请求
+#[derive(Debug)]
+pub struct CommandKeys {
+    pattern: Bytes,
+}
+#[derive(Debug)]
+pub struct CommandRandomKey;
+#[derive(Debug)]
+pub struct CommandDbSize;
+
+/// RANDOMKEY 抽到已过期的 key 时重试的次数
+const RANDOM_KEY_TRIES: usize = 100;
+
+/// 删除多个 key，返回实际删除的数量
+fn remove_keys(backend: &Backend, keys: &[Bytes]) -> RespFrame {
+    let removed = keys.iter().filter(|key| backend.remove_key(key)).count();
+    RespInteger::new(removed as i64).into()
+}
+
+impl CommandExecutor for CommandType {
+    fn execute(self, backend: &Backend) -> RespFrame {
+        SimpleString::new(backend.type_of(&self.key).unwrap_or("none")).into()
+    }
+}
+impl CommandExecutor for CommandDel {
+    fn execute(self, backend: &Backend) -> RespFrame {
+        remove_keys(backend, &self.keys)
+    }
+}
+impl CommandExecutor for CommandUnlink {
+    fn execute(self, backend: &Backend) -> RespFrame {
+        remove_keys(backend, &self.keys)
+    }
+}
+impl CommandExecutor for CommandExists {
+    fn execute(self, backend: &Backend) -> RespFrame {
+        // 重复的 key 会被重复计数
+        let count = self
+            .keys
+            .iter()
+            .filter(|key| backend.contains_key(key))
+            .count();
+        RespInteger::new(count as i64).into()
+    }
+}
+impl CommandExecutor for CommandRename {
+    fn execute(self, backend: &Backend) -> RespFrame {
+        if backend.rename(&self.key, &self.new_key) {
+            RESP_OK.clone()
+        } else {
+            SimpleError::new("ERR no such key").into()
+        }
+    }
+}
+
+/// 解析 key [key ...]
+fn parse_keys(value: &RespArray, name: &'static str) -> Result<Vec<Bytes>, CommandError> {
+    command_args(value)?
+        .iter()
+        .map(|arg| extract_bytes(arg, name))
+        .collect()
+}
+
+impl TryFrom<RespArray> for CommandType {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        let args = command_args(&value)?;
+        Ok(CommandType {
+            key: extract_bytes(args[0], "TYPE")?,
+        })
+    }
+}
+impl TryFrom<RespArray> for CommandDel {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        Ok(CommandDel {
+            keys: parse_keys(&value, "DEL")?,
+        })
+    }
+}
+impl TryFrom<RespArray> for CommandUnlink {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        Ok(CommandUnlink {
+            keys: parse_keys(&value, "UNLINK")?,
+        })
+    }
+}
+impl TryFrom<RespArray> for CommandExists {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        Ok(CommandExists {
+            keys: parse_keys(&value, "EXISTS")?,
+        })
+    }
+}
+impl TryFrom<RespArray> for CommandRename {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        let args = command_args(&value)?;
+        Ok(CommandRename {
+            key: extract_bytes(args[0], "RENAME")?,
+            new_key: extract_bytes(args[1], "RENAME")?,
+        })
+    }
+}
+impl CommandExecutor for CommandKeys {
+    fn execute(self, backend: &Backend) -> RespFrame {
+        bulk_array(backend.keys(&self.pattern))
+    }
+}
+impl CommandExecutor for CommandRandomKey {
+    fn execute(self, backend: &Backend) -> RespFrame {
+        for _ in 0..RANDOM_KEY_TRIES {
+            let len = backend.dbsize();
+            if len == 0 {
+                break;
+            }
+            // 抽到的 key 可能已过期，contains_key 会顺便删除它
+            if let Some(key) = backend.nth_key(random_index(len))
+                && backend.contains_key(&key)
+            {
+                return key.into();
+            }
+        }
+        RespNull.into()
+    }
+}
+impl CommandExecutor for CommandDbSize {
+    fn execute(self, backend: &Backend) -> RespFrame {
+        RespInteger::new(backend.dbsize() as i64).into()
+    }
+}
+
+impl TryFrom<RespArray> for CommandKeys {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        let args = command_args(&value)?;
+        Ok(CommandKeys {
+            pattern: extract_bytes(args[0], "KEYS")?,
+        })
+    }
+}
+impl TryFrom<RespArray> for CommandRandomKey {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        command_args(&value)?;
+        Ok(CommandRandomKey)
+    }
+}
+impl TryFrom<RespArray> for CommandDbSize {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        command_args(&value)?;
+        Ok(CommandDbSize)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use bytes::BytesMut;
+
+    use super::*;
+    use crate::{
+        backend::now_ms,
+        cmd::{CommandGet, CommandLPush, CommandSAdd},
+        resp::RespDecode,
+    };
+
+    fn execute<T>(backend: &Backend, buf: &str) -> Result<RespFrame>
+    where
+        T: CommandExecutor + TryFrom<RespArray, Error = CommandError>,
+    {
+        let mut buf = BytesMut::from(buf);
+        Ok(T::try_from(RespArray::decode(&mut buf)?)?.execute(backend))
+    }
+
+    #[test]
+    fn test_wrong_type() -> Result<()> {
+        let backend = Backend::new();
+        execute::<CommandLPush>(&backend, "*3\r\n$5\r\nlpush\r\n$3\r\nkey\r\n$1\r\na\r\n")?;
+        let ret = execute::<CommandGet>(&backend, "*2\r\n$3\r\nget\r\n$3\r\nkey\r\n")?;
+        assert_eq!(
+            ret,
+            SimpleError::new("WRONGTYPE Operation against a key holding the wrong kind of value")
+                .into()
+        );
+        let ret = execute::<CommandSAdd>(&backend, "*3\r\n$4\r\nsadd\r\n$3\r\nkey\r\n$1\r\na\r\n")?;
+        assert!(matches!(ret, RespFrame::SimpleError(_)));
+        let ret = execute::<CommandType>(&backend, "*2\r\n$4\r\ntype\r\n$3\r\nkey\r\n")?;
+        assert_eq!(ret, SimpleString::new("list").into());
+        Ok(())
+    }
+    #[test]
+    fn test_del_exists_rename() -> Result<()> {
+        let backend = Backend::new();
+        backend.set("a".into(), "1".into());
+        backend.set_add("b".into(), vec!["x".into()])?;
+        let ret = execute::<CommandExists>(
+            &backend,
+            "*4\r\n$6\r\nexists\r\n$1\r\na\r\n$1\r\na\r\n$1\r\nc\r\n",
+        )?;
+        assert_eq!(ret, RespInteger::new(2).into());
+
+        let ret =
+            execute::<CommandRename>(&backend, "*3\r\n$6\r\nrename\r\n$1\r\nb\r\n$1\r\na\r\n")?;
+        assert_eq!(ret, RESP_OK.clone());
+        assert_eq!(backend.type_of(b"a"), Some("set"));
+        assert_eq!(backend.type_of(b"b"), None);
+        let ret =
+            execute::<CommandRename>(&backend, "*3\r\n$6\r\nrename\r\n$1\r\nb\r\n$1\r\na\r\n")?;
+        assert_eq!(ret, SimpleError::new("ERR no such key").into());
+
+        let ret = execute::<CommandDel>(&backend, "*3\r\n$3\r\ndel\r\n$1\r\na\r\n$1\r\nb\r\n")?;
+        assert_eq!(ret, RespInteger::new(1).into());
+        let ret = execute::<CommandUnlink>(&backend, "*2\r\n$6\r\nunlink\r\n$1\r\na\r\n")?;
+        assert_eq!(ret, RespInteger::new(0).into());
+        Ok(())
+    }
+
+    #[test]
+    fn test_keys_randomkey_dbsize() -> Result<()> {
+        let backend = Backend::new();
+        assert_eq!(
+            execute::<CommandRandomKey>(&backend, "*1\r\n$9\r\nrandomkey\r\n")?,
+            RespNull.into()
+        );
+        for key in ["user:1", "user:2", "user:10", "order:1"] {
+            backend.set(key.into(), "v".into());
+        }
+        backend.set("user:3".into(), "v".into());
+        backend.expire_at(&"user:3".into(), now_ms() + 1);
+        assert_eq!(
+            execute::<CommandDbSize>(&backend, "*1\r\n$6\r\ndbsize\r\n")?,
+            RespInteger::new(5).into()
+        );
+        std::thread::sleep(std::time::Duration::from_millis(5));
+
+        let keys = |pattern: &str| -> Result<Vec<Bytes>> {
+            let request = format!("*2\r\n$4\r\nkeys\r\n${}\r\n{}\r\n", pattern.len(), pattern);
+            let RespFrame::Array(RespArray {
+                elements: Some(keys),
+            }) = execute::<CommandKeys>(&backend, &request)?
+            else {
+                panic!("expect array");
+            };
+            let mut keys = keys
+                .into_iter()
+                .map(|key| match key {
+                    RespFrame::BulkString(bulk) => bulk.content.unwrap(),
+                    _ => panic!("expect bulk string"),
+                })
+                .collect::<Vec<_>>();
+            keys.sort();
+            Ok(keys)
+        };
+        assert_eq!(keys("user:?")?, ["user:1", "user:2"]);
+        assert_eq!(keys("user:*")?, ["user:1", "user:10", "user:2"]);
+        assert_eq!(keys("*:1")?, ["order:1", "user:1"]);
+        assert_eq!(keys("[^u]*")?, ["order:1"]);
+        assert_eq!(keys("*")?.len(), 4);
+
+        let RespFrame::BulkString(key) =
+            execute::<CommandRandomKey>(&backend, "*1\r\n$9\r\nrandomkey\r\n")?
+        else {
+            panic!("expect bulk string");
+        };
+        assert!(backend.contains_key(key.content.as_deref().unwrap()));
+        Ok(())
+    }
+
+    #[test]
+    fn test_keys_edge_cases() -> Result<()> {
+        let backend = Backend::new();
+        for key in ["a*b", "axb", "a?", "ab", "[x]"] {
+            backend.set(key.into(), "v".into());
+        }
+        let keys = |pattern: &str| -> Result<usize> {
+            let request = format!("*2\r\n$4\r\nkeys\r\n${}\r\n{}\r\n", pattern.len(), pattern);
+            match execute::<CommandKeys>(&backend, &request)? {
+                RespFrame::Array(RespArray {
+                    elements: Some(keys),
+                }) => Ok(keys.len()),
+                other => panic!("expect array, got {:?}", other),
+            }
+        };
+        assert_eq!(keys("a\\*b")?, 1);
+        assert_eq!(keys("a*b")?, 3);
+        assert_eq!(keys("a\\?")?, 1);
+        assert_eq!(keys("\\[x\\]")?, 1);
+        assert_eq!(keys("nomatch*")?, 0);
+        assert_eq!(keys("")?, 0);
+
+        // 参数个数不对时拒绝
+        assert!(execute::<CommandKeys>(&backend, "*1\r\n$4\r\nkeys\r\n").is_err());
+        assert!(
+            execute::<CommandKeys>(&backend, "*3\r\n$4\r\nkeys\r\n$1\r\n*\r\n$1\r\n*\r\n").is_err()
+        );
+        assert!(execute::<CommandDbSize>(&backend, "*2\r\n$6\r\ndbsize\r\n$1\r\nx\r\n").is_err());
+
+        // 只剩过期 key 时 RANDOMKEY 返回 nil，DBSIZE 随之归零
+        let backend = Backend::new();
+        backend.set("gone".into(), "v".into());
+        backend.expire_at(&"gone".into(), now_ms() + 1);
+        std::thread::sleep(std::time::Duration::from_millis(5));
+        assert_eq!(
+            execute::<CommandRandomKey>(&backend, "*1\r\n$9\r\nrandomkey\r\n")?,
+            RespNull.into()
+        );
+        assert_eq!(
+            execute::<CommandDbSize>(&backend, "*1\r\n$6\r\ndbsize\r\n")?,
+            RespInteger::new(0).into()
+        );
+        Ok(())
+    }
+}
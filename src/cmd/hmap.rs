@@ -1,112 +1,536 @@
+use std::collections::HashMap;
+
+use bytes::Bytes;
+
 use crate::{
     backend::Backend,
     cmd::{
-        CommandError, CommandExecutor, CommandHGet, CommandHGetAll, CommandHSet, RESP_OK,
-        valid_command,
+        CommandError, CommandExecutor, CommandHGet, CommandHGetAll, CommandHSet, bulk_array,
+        command_args, extract_bytes, extract_int, extract_strict_int, extract_string,
+        parse_strict_int, random_index,
     },
-    resp::{BulkString, RespArray, RespFrame, RespNull},
+    resp::{BulkString, RespArray, RespFrame, RespInteger, RespNull, SimpleError},
 };
 // Redis命令与RESP协议格式对应表
-// | 命令    | 参数         | 对应格式                                                                 |
-// |---------|--------------|--------------------------------------------------------------------------|
-// | SET     | key val      | "*3\r\n$3\r\nset\r\n$5\r\nhello\r\n$5\r\nworld\r\n"                      |
-// | GET     | key          | "*2\r\n$3\r\nget\r\n$5\r\nhello\r\n"                                     |
-// | HSET    | key field val| "*4\r\n$4\r\nhset\r\n$3\r\nmap\r\n$5\r\nhello\r\n$5\r\nworld\r\n"         |
-// | HGET    | key field    | "*3\r\n$4\r\nhget\r\n$3\r\nmap\r\n$5\r\nhello\r\n"                       |
-// | HGETALL | key          | "*2\r\n$7\r\nhgetall\r\n$3\r\nmap\r\n"                                   |
-// | HSET    | key field val| "*4\r\n$4\r\nhset\r\n$3\r\nmap\r\n$5\r\nhello\r\n$5\r\nworld\r\n"         |
+// | 命令         | 参数                  | 对应格式                                                          |
+// |--------------|-----------------------|-------------------------------------------------------------------|
+// | HSET         | key field val [f v ..]| "*4\r\n$4\r\nhset\r\n$3\r\nmap\r\n$5\r\nhello\r\n$5\r\nworld\r\n"  |
+// | HSETNX       | key field val         | "*4\r\n$6\r\nhsetnx\r\n$3\r\nmap\r\n$5\r\nhello\r\n$5\r\nworld\r\n"|
+// | HGET         | key field             | "*3\r\n$4\r\nhget\r\n$3\r\nmap\r\n$5\r\nhello\r\n"                |
+// | HMGET        | key field [field ...] | "*3\r\n$5\r\nhmget\r\n$3\r\nmap\r\n$5\r\nhello\r\n"               |
+// | HGETALL      | key                   | "*2\r\n$7\r\nhgetall\r\n$3\r\nmap\r\n"                            |
+// | HDEL         | key field [field ...] | "*3\r\n$4\r\nhdel\r\n$3\r\nmap\r\n$5\r\nhello\r\n"                |
+// | HEXISTS      | key field             | "*3\r\n$7\r\nhexists\r\n$3\r\nmap\r\n$5\r\nhello\r\n"             |
+// | HLEN         | key                   | "*2\r\n$4\r\nhlen\r\n$3\r\nmap\r\n"                               |
+// | HKEYS        | key                   | "*2\r\n$5\r\nhkeys\r\n$3\r\nmap\r\n"                              |
+// | HVALS        | key                   | "*2\r\n$5\r\nhvals\r\n$3\r\nmap\r\n"                              |
+// | HINCRBY      | key field increment   | "*4\r\n$7\r\nhincrby\r\n$3\r\nmap\r\n$1\r\nn\r\n$1\r\n5\r\n"         |
+// | HINCRBYFLOAT | key field increment   | "*4\r\n$12\r\nhincrbyfloat\r\n$3\r\nmap\r\n$1\r\nf\r\n$3\r\n0.5\r\n"  |
+// | HSTRLEN      | key field             | "*3\r\n$7\r\nhstrlen\r\n$3\r\nmap\r\n$5\r\nhello\r\n"             |
+// | HRANDFIELD   | key [count [WITHVALUES]] | "*3\r\n$10\r\nhrandfield\r\n$3\r\nmap\r\n$2\r\n-5\r\n"        |
+
+type Hash = HashMap<Bytes, Bytes>;
+
+/// HRANDFIELD 负数 count 允许重复，回复长度等于 |count|，超过该值时拒绝
+const MAX_REPEATED_COUNT: i64 = 1 << 20;
+
+#[derive(Debug)]
+pub struct CommandHSetNx {
+    key: Bytes,
+    field: Bytes,
+    value: Bytes,
+}
+#[derive(Debug)]
+pub struct CommandHMGet {
+    key: Bytes,
+    fields: Vec<Bytes>,
+}
+#[derive(Debug)]
+pub struct CommandHDel {
+    key: Bytes,
+    fields: Vec<Bytes>,
+}
+#[derive(Debug)]
+pub struct CommandHExists {
+    key: Bytes,
+    field: Bytes,
+}
+#[derive(Debug)]
+pub struct CommandHLen {
+    key: Bytes,
+}
+#[derive(Debug)]
+pub struct CommandHKeys {
+    key: Bytes,
+}
+#[derive(Debug)]
+pub struct CommandHVals {
+    key: Bytes,
+}
+#[derive(Debug)]
+pub struct CommandHIncrBy {
+    key: Bytes,
+    field: Bytes,
+    increment: i64,
+}
+#[derive(Debug)]
+pub struct CommandHIncrByFloat {
+    key: Bytes,
+    field: Bytes,
+    increment: f64,
+}
+#[derive(Debug)]
+pub struct CommandHStrLen {
+    key: Bytes,
+    field: Bytes,
+}
+/// count 为正数时返回不重复的字段，负数时允许重复
+#[derive(Debug)]
+pub struct CommandHRandField {
+    key: Bytes,
+    count: Option<i64>,
+    with_values: bool,
+}
+
 impl CommandExecutor for CommandHGet {
     fn execute(self, backend: &Backend) -> RespFrame {
-        backend
-            .hget(&self.key, &self.field)
-            .unwrap_or(RespFrame::RespNull(RespNull))
+        match backend.hget(&self.key, &self.field) {
+            Ok(value) => value
+                .map(RespFrame::from)
+                .unwrap_or(RespFrame::RespNull(RespNull)),
+            Err(e) => e.into(),
+        }
     }
 }
 impl CommandExecutor for CommandHGetAll {
     fn execute(self, backend: &Backend) -> RespFrame {
-        let map = backend.hgetall(&self.key);
-        match map {
-            Some(map) => {
-                let mut data = map
-                    .iter()
-                    .map(|v| (v.key().clone(), v.value().clone()))
-                    .collect::<Vec<_>>();
-                data.sort_unstable_by(|a, b| a.0.cmp(&b.0));
-                let mut ret = Vec::with_capacity(data.len() * 2);
-
-                for (k, v) in data {
-                    ret.push(BulkString::from_slice(k).into());
-                    ret.push(v);
-                }
-                RespArray::new(Some(ret)).into()
-            }
-            None => RespFrame::RespNull(RespNull),
+        // 只复制字段和值的引用计数，不复制整个哈希表；key 不存在时回复空数组
+        match backend.read(&self.key, sorted_pairs) {
+            Ok(pairs) => bulk_array(
+                pairs
+                    .unwrap_or_default()
+                    .into_iter()
+                    .flat_map(|(k, v)| [k, v]),
+            ),
+            Err(e) => e.into(),
         }
     }
 }
 
 impl CommandExecutor for CommandHSet {
     fn execute(self, backend: &Backend) -> RespFrame {
-        backend.hset(self.key, self.field, self.value.clone());
-        RESP_OK.clone()
+        match backend.hset(self.key, self.pairs) {
+            Ok(added) => RespInteger::new(added as i64).into(),
+            Err(e) => e.into(),
+        }
+    }
+}
+impl CommandExecutor for CommandHSetNx {
+    fn execute(self, backend: &Backend) -> RespFrame {
+        let ret = backend.upsert(&self.key, |hash: &mut Hash| {
+            if hash.contains_key(&self.field) {
+                return (false, 0);
+            }
+            hash.insert(self.field, self.value);
+            (true, 1)
+        });
+        match ret {
+            Ok(inserted) => RespInteger::new(inserted as i64).into(),
+            Err(e) => e.into(),
+        }
+    }
+}
+impl CommandExecutor for CommandHMGet {
+    fn execute(self, backend: &Backend) -> RespFrame {
+        let values = backend.read(&self.key, |hash: &Hash| {
+            self.fields
+                .iter()
+                .map(|field| hash.get(field).cloned())
+                .collect::<Vec<_>>()
+        });
+        match values {
+            Ok(values) => {
+                let values = values.unwrap_or_else(|| vec![None; self.fields.len()]);
+                RespArray::from_vec(
+                    values
+                        .into_iter()
+                        .map(|value| value.map(RespFrame::from).unwrap_or(RespNull.into()))
+                        .collect(),
+                )
+                .into()
+            }
+            Err(e) => e.into(),
+        }
+    }
+}
+impl CommandExecutor for CommandHDel {
+    fn execute(self, backend: &Backend) -> RespFrame {
+        let removed = backend.update(&self.key, |hash: &mut Hash| {
+            let removed = self
+                .fields
+                .iter()
+                .filter(|field| hash.remove(*field).is_some())
+                .count();
+            (removed, removed)
+        });
+        match removed {
+            Ok(removed) => RespInteger::new(removed.unwrap_or_default() as i64).into(),
+            Err(e) => e.into(),
+        }
+    }
+}
+impl CommandExecutor for CommandHExists {
+    fn execute(self, backend: &Backend) -> RespFrame {
+        match backend.read(&self.key, |hash: &Hash| hash.contains_key(&self.field)) {
+            Ok(exists) => RespInteger::new(exists.unwrap_or_default() as i64).into(),
+            Err(e) => e.into(),
+        }
     }
 }
+impl CommandExecutor for CommandHLen {
+    fn execute(self, backend: &Backend) -> RespFrame {
+        match backend.read(&self.key, |hash: &Hash| hash.len()) {
+            Ok(len) => RespInteger::new(len.unwrap_or_default() as i64).into(),
+            Err(e) => e.into(),
+        }
+    }
+}
+/// 按字段排序，与 HGETALL 的顺序一致
+fn sorted_pairs(hash: &Hash) -> Vec<(Bytes, Bytes)> {
+    let mut pairs = hash
+        .iter()
+        .map(|(field, value)| (field.clone(), value.clone()))
+        .collect::<Vec<_>>();
+    pairs.sort_unstable_by(|a, b| a.0.cmp(&b.0));
+    pairs
+}
+impl CommandExecutor for CommandHKeys {
+    fn execute(self, backend: &Backend) -> RespFrame {
+        match backend.read(&self.key, sorted_pairs) {
+            Ok(pairs) => bulk_array(pairs.unwrap_or_default().into_iter().map(|(k, _)| k)),
+            Err(e) => e.into(),
+        }
+    }
+}
+impl CommandExecutor for CommandHVals {
+    fn execute(self, backend: &Backend) -> RespFrame {
+        match backend.read(&self.key, sorted_pairs) {
+            Ok(pairs) => bulk_array(pairs.unwrap_or_default().into_iter().map(|(_, v)| v)),
+            Err(e) => e.into(),
+        }
+    }
+}
+impl CommandExecutor for CommandHIncrBy {
+    fn execute(self, backend: &Backend) -> RespFrame {
+        let ret = backend.upsert(&self.key, |hash: &mut Hash| -> Result<_, SimpleError> {
+            let current = match hash.get(&self.field) {
+                Some(value) => parse_strict_int(value)
+                    .ok_or_else(|| SimpleError::new("ERR hash value is not an integer"))?,
+                None => 0,
+            };
+            let value = current
+                .checked_add(self.increment)
+                .ok_or_else(|| SimpleError::new("ERR increment or decrement would overflow"))?;
+            hash.insert(self.field, value.to_string().into());
+            Ok((value, 1))
+        });
+        match ret {
+            Ok(Ok(value)) => RespInteger::new(value).into(),
+            Ok(Err(e)) => e.into(),
+            Err(e) => e.into(),
+        }
+    }
+}
+impl CommandExecutor for CommandHIncrByFloat {
+    fn execute(self, backend: &Backend) -> RespFrame {
+        let ret = backend.upsert(&self.key, |hash: &mut Hash| -> Result<_, SimpleError> {
+            let current = match hash.get(&self.field) {
+                Some(value) => str::from_utf8(value)
+                    .ok()
+                    .and_then(|value| value.parse::<f64>().ok())
+                    .filter(|value| value.is_finite())
+                    .ok_or_else(|| SimpleError::new("ERR hash value is not a float"))?,
+                None => 0.0,
+            };
+            let value = current + self.increment;
+            if !value.is_finite() {
+                return Err(SimpleError::new(
+                    "ERR increment would produce NaN or Infinity",
+                ));
+            }
+            let value = Bytes::from(value.to_string());
+            hash.insert(self.field, value.clone());
+            Ok((value, 1))
+        });
+        // 与 Redis 相同，结果以 bulk string 回复
+        match ret {
+            Ok(Ok(value)) => BulkString::new(value).into(),
+            Ok(Err(e)) => e.into(),
+            Err(e) => e.into(),
+        }
+    }
+}
+impl CommandExecutor for CommandHStrLen {
+    fn execute(self, backend: &Backend) -> RespFrame {
+        let len = backend.read(&self.key, |hash: &Hash| {
+            hash.get(&self.field).map_or(0, Bytes::len)
+        });
+        match len {
+            Ok(len) => RespInteger::new(len.unwrap_or_default() as i64).into(),
+            Err(e) => e.into(),
+        }
+    }
+}
+impl CommandExecutor for CommandHRandField {
+    fn execute(self, backend: &Backend) -> RespFrame {
+        let with_values = self.with_values;
+        let Some(count) = self.count else {
+            let field = backend.read(&self.key, |hash: &Hash| random_fields(hash, 1, false));
+            return match field {
+                Ok(fields) => fields
+                    .and_then(|fields| fields.into_iter().next())
+                    .map(RespFrame::from)
+                    .unwrap_or(RespNull.into()),
+                Err(e) => e.into(),
+            };
+        };
+        match backend.read(&self.key, |hash: &Hash| {
+            random_fields(hash, count, with_values)
+        }) {
+            Ok(items) => bulk_array(items.unwrap_or_default()),
+            Err(e) => e.into(),
+        }
+    }
+}
+
+/// 随机选取字段，WITHVALUES 时每个字段后紧跟它的值
+/// count 为正数时不重复且最多取全部，负数时可以重复，回复逐个追加而不是按 |count| 预分配
+fn random_fields(hash: &Hash, count: i64, with_values: bool) -> Vec<Bytes> {
+    let mut pairs = hash.iter().collect::<Vec<_>>();
+    if pairs.is_empty() {
+        return Vec::new();
+    }
+    let mut items = Vec::new();
+    let mut push = |(field, value): (&Bytes, &Bytes)| {
+        items.push(field.clone());
+        if with_values {
+            items.push(value.clone());
+        }
+    };
+    if count < 0 {
+        for _ in 0..count.unsigned_abs() {
+            push(pairs[random_index(pairs.len())]);
+        }
+    } else {
+        // 部分 Fisher-Yates 洗牌，只打乱前 count 个
+        for i in 0..(count as usize).min(pairs.len()) {
+            let j = i + random_index(pairs.len() - i);
+            pairs.swap(i, j);
+            push(pairs[i]);
+        }
+    }
+    items
+}
+
 impl TryFrom<RespArray> for CommandHGet {
     type Error = CommandError;
     fn try_from(value: RespArray) -> Result<Self, Self::Error> {
-        let args = valid_command(&value, &["HGET"], 2)?;
-        match (args[0], args[1]) {
-            (
-                RespFrame::BulkString(BulkString { content: Some(key) }),
-                RespFrame::BulkString(BulkString {
-                    content: Some(field),
-                }),
-            ) => Ok(CommandHGet {
-                key: String::from_utf8(key.to_vec())?,
-                field: String::from_utf8(field.to_vec())?,
-            }),
-            _ => Err(CommandError::InvalidArguments(
-                "HGET command expect bulk string".to_string(),
-            )),
-        }
+        let args = command_args(&value)?;
+        Ok(CommandHGet {
+            key: extract_bytes(args[0], "HGET")?,
+            field: extract_bytes(args[1], "HGET")?,
+        })
     }
 }
 
 impl TryFrom<RespArray> for CommandHGetAll {
     type Error = CommandError;
     fn try_from(value: RespArray) -> Result<Self, Self::Error> {
-        let args = valid_command(&value, &["HGETALL"], 1)?;
-        match args[0] {
-            RespFrame::BulkString(BulkString { content: Some(key) }) => Ok(CommandHGetAll {
-                key: String::from_utf8(key.to_vec())?,
-            }),
-            _ => Err(CommandError::InvalidArguments(
-                "HGETALL command expect bulk string".to_string(),
-            )),
-        }
+        let args = command_args(&value)?;
+        Ok(CommandHGetAll {
+            key: extract_bytes(args[0], "HGETALL")?,
+        })
     }
 }
 
 impl TryFrom<RespArray> for CommandHSet {
     type Error = CommandError;
     fn try_from(value: RespArray) -> Result<Self, Self::Error> {
-        let args = valid_command(&value, &["HSET"], 3)?;
-        match (args[0], args[1], args[2]) {
-            (
-                RespFrame::BulkString(BulkString { content: Some(key) }),
-                RespFrame::BulkString(BulkString {
-                    content: Some(field),
-                }),
-                resp_frame,
-            ) => Ok(CommandHSet {
-                key: String::from_utf8(key.to_vec())?,
-                field: String::from_utf8(field.to_vec())?,
-                value: resp_frame.clone(),
-            }),
-            _ => Err(CommandError::InvalidArguments(
-                "HSET command expect bulk string".to_string(),
-            )),
+        let args = command_args(&value)?;
+        // key 之后必须是成对的 field value
+        if args.len() % 2 == 0 {
+            return Err(CommandError::InvalidNumberOfArguments(
+                "wrong number of arguments for 'hset' command".to_string(),
+            ));
         }
+        let pairs = args[1..]
+            .chunks(2)
+            .map(|pair| {
+                Ok((
+                    extract_bytes(pair[0], "HSET")?,
+                    extract_bytes(pair[1], "HSET")?,
+                ))
+            })
+            .collect::<Result<Vec<_>, CommandError>>()?;
+        Ok(CommandHSet {
+            key: extract_bytes(args[0], "HSET")?,
+            pairs,
+        })
+    }
+}
+
+impl TryFrom<RespArray> for CommandHSetNx {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        let args = command_args(&value)?;
+        Ok(CommandHSetNx {
+            key: extract_bytes(args[0], "HSETNX")?,
+            field: extract_bytes(args[1], "HSETNX")?,
+            value: extract_bytes(args[2], "HSETNX")?,
+        })
+    }
+}
+
+/// 解析 key field [field ...]
+fn parse_fields(
+    value: &RespArray,
+    name: &'static str,
+) -> Result<(Bytes, Vec<Bytes>), CommandError> {
+    let args = command_args(value)?;
+    let key = extract_bytes(args[0], name)?;
+    let fields = args[1..]
+        .iter()
+        .map(|arg| extract_bytes(arg, name))
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok((key, fields))
+}
+
+impl TryFrom<RespArray> for CommandHMGet {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        let (key, fields) = parse_fields(&value, "HMGET")?;
+        Ok(CommandHMGet { key, fields })
+    }
+}
+
+impl TryFrom<RespArray> for CommandHDel {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        let (key, fields) = parse_fields(&value, "HDEL")?;
+        Ok(CommandHDel { key, fields })
+    }
+}
+
+impl TryFrom<RespArray> for CommandHExists {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        let args = command_args(&value)?;
+        Ok(CommandHExists {
+            key: extract_bytes(args[0], "HEXISTS")?,
+            field: extract_bytes(args[1], "HEXISTS")?,
+        })
+    }
+}
+
+impl TryFrom<RespArray> for CommandHLen {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        let args = command_args(&value)?;
+        Ok(CommandHLen {
+            key: extract_bytes(args[0], "HLEN")?,
+        })
+    }
+}
+
+impl TryFrom<RespArray> for CommandHKeys {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        let args = command_args(&value)?;
+        Ok(CommandHKeys {
+            key: extract_bytes(args[0], "HKEYS")?,
+        })
+    }
+}
+
+impl TryFrom<RespArray> for CommandHVals {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        let args = command_args(&value)?;
+        Ok(CommandHVals {
+            key: extract_bytes(args[0], "HVALS")?,
+        })
+    }
+}
+
+impl TryFrom<RespArray> for CommandHIncrBy {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        let args = command_args(&value)?;
+        Ok(CommandHIncrBy {
+            key: extract_bytes(args[0], "HINCRBY")?,
+            field: extract_bytes(args[1], "HINCRBY")?,
+            increment: extract_strict_int(args[2], "HINCRBY")?,
+        })
+    }
+}
+
+impl TryFrom<RespArray> for CommandHIncrByFloat {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        let args = command_args(&value)?;
+        let increment = extract_string(args[2], "HINCRBYFLOAT")?
+            .parse::<f64>()
+            .ok()
+            .filter(|increment| increment.is_finite())
+            .ok_or_else(|| CommandError::InvalidArguments("value is not a valid float".into()))?;
+        Ok(CommandHIncrByFloat {
+            key: extract_bytes(args[0], "HINCRBYFLOAT")?,
+            field: extract_bytes(args[1], "HINCRBYFLOAT")?,
+            increment,
+        })
+    }
+}
+
+impl TryFrom<RespArray> for CommandHStrLen {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        let args = command_args(&value)?;
+        Ok(CommandHStrLen {
+            key: extract_bytes(args[0], "HSTRLEN")?,
+            field: extract_bytes(args[1], "HSTRLEN")?,
+        })
+    }
+}
+
+impl TryFrom<RespArray> for CommandHRandField {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        let args = command_args(&value)?;
+        let key = extract_bytes(args[0], "HRANDFIELD")?;
+        let count = |frame: &RespFrame| {
+            let count = extract_int(frame, "HRANDFIELD")?;
+            if count < -MAX_REPEATED_COUNT {
+                return Err(CommandError::InvalidArguments(
+                    "value is out of range".into(),
+                ));
+            }
+            Ok(Some(count))
+        };
+        let (count, with_values) = match args[1..] {
+            [] => (None, false),
+            [frame] => (count(frame)?, false),
+            [frame, option]
+                if extract_string(option, "HRANDFIELD")?.eq_ignore_ascii_case("withvalues") =>
+            {
+                (count(frame)?, true)
+            }
+            _ => return Err(CommandError::InvalidArguments("syntax error".into())),
+        };
+        Ok(CommandHRandField {
+            key,
+            count,
+            with_values,
+        })
     }
 }
 
@@ -115,7 +539,7 @@ mod tests {
     use bytes::BytesMut;
 
     use super::*;
-    use crate::resp::RespDecode;
+    use crate::resp::{RespDecode, RespEncode};
 
     #[test]
     fn test_hget_command() {
@@ -154,58 +578,55 @@ mod tests {
         let array = RespArray::decode(&mut byte_get)?;
         let cmd = CommandHSet::try_from(array)?;
         assert_eq!(cmd.key, "map".to_string());
-        assert_eq!(cmd.field, "hello".to_string());
-        assert_eq!(
-            cmd.value,
-            RespFrame::BulkString(BulkString {
-                content: Some("world".into())
-            })
-        );
+        assert_eq!(cmd.pairs, [("hello".into(), "world".into())]);
+
+        let cmd: RespArray = vec![
+            "hset".into(),
+            "map".into(),
+            "a".into(),
+            "1".into(),
+            "b".into(),
+        ]
+        .into();
+        assert!(CommandHSet::try_from(cmd).is_err());
         Ok(())
     }
     #[test]
-    /// hset value是array的
+    /// hset value是array的，只接受 bulk string
     fn test_command_hset_try_from_array() -> Result<(), CommandError> {
         let mut byte_get =
             BytesMut::from("*4\r\n$4\r\nhset\r\n$3\r\nmap\r\n$5\r\nhello\r\n*1\r\n$5\r\nworld\r\n");
         let array = RespArray::decode(&mut byte_get)?;
-        let cmd = CommandHSet::try_from(array)?;
-        assert_eq!(cmd.key, "map".to_string());
-        assert_eq!(cmd.field, "hello".to_string());
-        assert_eq!(
-            cmd.value,
-            RespFrame::Array(RespArray::from(vec!["world".into()]))
-        );
+        assert!(CommandHSet::try_from(array).is_err());
         Ok(())
     }
     #[test]
     fn test_hset_hget_hgetall_commands() -> Result<(), CommandError> {
         let backend = Backend::new();
         let cmd = CommandHSet {
-            key: "map".to_string(),
-            field: "hello".to_string(),
-            value: "world".into(),
+            key: "map".into(),
+            pairs: vec![("hello".into(), "world".into())],
         };
         let result = cmd.execute(&backend);
-        assert_eq!(result, RESP_OK.clone());
+        assert_eq!(result, RespInteger::new(1).into());
 
         let cmd = CommandHSet {
-            key: "map".to_string(),
-            field: "hello1".to_string(),
-            value: "world1".into(),
+            key: "map".into(),
+            pairs: vec![
+                ("hello".into(), "world".into()),
+                ("hello1".into(), "world1".into()),
+            ],
         };
-        cmd.execute(&backend);
+        assert_eq!(cmd.execute(&backend), RespInteger::new(1).into());
 
         let cmd = CommandHGet {
-            key: "map".to_string(),
-            field: "hello".to_string(),
+            key: "map".into(),
+            field: "hello".into(),
         };
         let result = cmd.execute(&backend);
         assert_eq!(result, "world".into());
 
-        let cmd = CommandHGetAll {
-            key: "map".to_string(),
-        };
+        let cmd = CommandHGetAll { key: "map".into() };
         let result: RespFrame = cmd.execute(&backend);
 
         let expected: RespArray = vec![
@@ -216,6 +637,167 @@ mod tests {
         ]
         .into();
         assert_eq!(result, expected.into());
+
+        let cmd = CommandHGetAll {
+            key: "missing".into(),
+        };
+        let result = cmd.execute(&backend);
+        assert_eq!(result, bulk_array([]));
+        assert_eq!(result.encode(), b"*0\r\n");
         Ok(())
     }
+
+    fn run(backend: &Backend, args: &[&str]) -> RespFrame {
+        let frames = args.iter().map(|arg| (*arg).into()).collect::<Vec<_>>();
+        match crate::cmd::Command::try_from(RespArray::from(frames)) {
+            Ok(cmd) => cmd.execute(backend),
+            Err(e) => SimpleError::new(format!("ERR {}", e)).into(),
+        }
+    }
+
+    fn int(n: i64) -> RespFrame {
+        RespInteger::new(n).into()
+    }
+
+    #[test]
+    fn test_hash_field_commands() {
+        let backend = Backend::new();
+        assert_eq!(run(&backend, &["HSET", "h", "a", "1", "b", "2"]), int(2));
+        assert_eq!(run(&backend, &["hsetnx", "h", "a", "9"]), int(0));
+        assert_eq!(run(&backend, &["hsetnx", "h", "c", "hello"]), int(1));
+        assert_eq!(
+            run(&backend, &["hmget", "h", "a", "x", "c"]),
+            RespArray::from_vec(vec!["1".into(), RespNull.into(), "hello".into()]).into()
+        );
+        assert_eq!(
+            run(&backend, &["hmget", "nope", "a"]),
+            RespArray::from_vec(vec![RespNull.into()]).into()
+        );
+        assert_eq!(run(&backend, &["hexists", "h", "a"]), int(1));
+        assert_eq!(run(&backend, &["hexists", "h", "x"]), int(0));
+        assert_eq!(run(&backend, &["hlen", "h"]), int(3));
+        assert_eq!(run(&backend, &["hstrlen", "h", "c"]), int(5));
+        assert_eq!(run(&backend, &["hstrlen", "h", "x"]), int(0));
+        assert_eq!(
+            run(&backend, &["hkeys", "h"]),
+            bulk_array(["a".into(), "b".into(), "c".into()])
+        );
+        assert_eq!(
+            run(&backend, &["hvals", "h"]),
+            bulk_array(["1".into(), "2".into(), "hello".into()])
+        );
+        assert_eq!(run(&backend, &["hkeys", "nope"]), bulk_array([]));
+
+        assert_eq!(run(&backend, &["hdel", "h", "a", "b", "x"]), int(2));
+        assert_eq!(run(&backend, &["hdel", "h", "c"]), int(1));
+        assert!(!backend.contains_key(b"h"));
+
+        backend.set("s".into(), "v".into());
+        assert_eq!(
+            run(&backend, &["hlen", "s"]),
+            crate::backend::WrongType.into()
+        );
+    }
+
+    #[test]
+    fn test_hash_incr_commands() {
+        let backend = Backend::new();
+        assert_eq!(run(&backend, &["hincrby", "h", "n", "5"]), int(5));
+        assert_eq!(run(&backend, &["hincrby", "h", "n", "-7"]), int(-2));
+        run(
+            &backend,
+            &["hset", "h", "s", "abc", "max", &i64::MAX.to_string()],
+        );
+        assert_eq!(
+            run(&backend, &["hincrby", "h", "s", "1"]),
+            SimpleError::new("ERR hash value is not an integer").into()
+        );
+        // 增量和字段值都按严格整数解析，与 INCRBY 一致
+        for increment in ["+5", "05", "-0", " 1", ""] {
+            assert_eq!(
+                run(&backend, &["hincrby", "h", "n", increment]),
+                SimpleError::new("ERR value is not an integer or out of range").into()
+            );
+        }
+        run(&backend, &["hset", "h", "plus", "+1"]);
+        assert_eq!(
+            run(&backend, &["hincrby", "h", "plus", "1"]),
+            SimpleError::new("ERR hash value is not an integer").into()
+        );
+        assert_eq!(run(&backend, &["hget", "h", "n"]), "-2".into());
+        assert_eq!(
+            run(&backend, &["hincrby", "h", "max", "1"]),
+            SimpleError::new("ERR increment or decrement would overflow").into()
+        );
+        assert_eq!(
+            run(&backend, &["hincrbyfloat", "h", "f", "10.5"]),
+            "10.5".into()
+        );
+        assert_eq!(
+            run(&backend, &["hincrbyfloat", "h", "f", "0.1"]),
+            "10.6".into()
+        );
+        assert_eq!(run(&backend, &["hincrbyfloat", "h", "n", "2"]), "0".into());
+        assert_eq!(
+            run(&backend, &["hincrbyfloat", "h", "s", "1"]),
+            SimpleError::new("ERR hash value is not a float").into()
+        );
+        assert!(matches!(
+            run(&backend, &["hincrbyfloat", "h", "f", "inf"]),
+            RespFrame::SimpleError(_)
+        ));
+        assert_eq!(run(&backend, &["hget", "h", "f"]), "10.6".into());
+    }
+
+    #[test]
+    fn test_hrandfield() {
+        let backend = Backend::new();
+        run(&backend, &["hset", "h", "a", "1", "b", "2", "c", "3"]);
+        let len = |frame: RespFrame| match frame {
+            RespFrame::Array(array) => array.elements.map_or(0, |elements| elements.len()),
+            _ => panic!("expect array"),
+        };
+        assert!(matches!(
+            run(&backend, &["hrandfield", "h"]),
+            RespFrame::BulkString(_)
+        ));
+        assert_eq!(run(&backend, &["hrandfield", "nope"]), RespNull.into());
+        assert_eq!(len(run(&backend, &["hrandfield", "h", "2"])), 2);
+        assert_eq!(len(run(&backend, &["hrandfield", "h", "10"])), 3);
+        assert_eq!(len(run(&backend, &["hrandfield", "h", "-10"])), 10);
+        assert_eq!(len(run(&backend, &["hrandfield", "h", "0"])), 0);
+        assert_eq!(
+            len(run(&backend, &["hrandfield", "h", "3", "WITHVALUES"])),
+            6
+        );
+        assert_eq!(len(run(&backend, &["hrandfield", "nope", "3"])), 0);
+        assert_eq!(
+            len(run(&backend, &["hrandfield", "h", "-4", "withvalues"])),
+            8
+        );
+
+        // 过大的负数 count 直接拒绝，不会尝试分配回复
+        for count in ["-9223372036854775808", "-10000000000000"] {
+            assert_eq!(
+                run(&backend, &["hrandfield", "h", count]),
+                SimpleError::new("ERR value is out of range").into()
+            );
+            assert_eq!(
+                run(&backend, &["hrandfield", "h", count, "withvalues"]),
+                SimpleError::new("ERR value is out of range").into()
+            );
+        }
+        assert_eq!(
+            len(run(&backend, &["hrandfield", "h", &i64::MAX.to_string()])),
+            3
+        );
+
+        // 不重复取样覆盖全部字段
+        let RespFrame::Array(array) = run(&backend, &["hrandfield", "h", "3"]) else {
+            panic!("expect array");
+        };
+        let mut fields = array.elements.unwrap();
+        fields.sort_by_key(|field| format!("{:?}", field));
+        assert_eq!(fields, vec!["a".into(), "b".into(), "c".into()]);
+    }
 }
@@ -0,0 +1,828 @@
+use std::time::Duration;
+
+use bytes::Bytes;
+
+use crate::{
+    backend::{Backend, ListTarget, WrongType},
+    cmd::{
+        CommandError, CommandExecutor, RESP_OK, bulk_array, command_args, extract_bytes,
+        extract_int, extract_string, normalize_range,
+    },
+    resp::{RespArray, RespFrame, RespInteger, RespNull, RespNullArray, SimpleError},
+};
+
+// Redis命令与RESP协议格式对应表
+// | 命令    | 参数              | 对应格式                                                            |
+// |---------|-------------------|---------------------------------------------------------------------|
+// | LPUSH   | key elem [elem..] | "*3\r\n$5\r\nlpush\r\n$4\r\nlist\r\n$5\r\nhello\r\n"                |
+// | RPUSH   | key elem [elem..] | "*3\r\n$5\r\nrpush\r\n$4\r\nlist\r\n$5\r\nhello\r\n"                |
+// | LPOP    | key [count]       | "*2\r\n$4\r\nlpop\r\n$4\r\nlist\r\n"                                |
+// | RPOP    | key [count]       | "*2\r\n$4\r\nrpop\r\n$4\r\nlist\r\n"                                |
+// | LRANGE  | key start stop    | "*4\r\n$6\r\nlrange\r\n$4\r\nlist\r\n$1\r\n0\r\n$2\r\n-1\r\n"       |
+// | LLEN    | key               | "*2\r\n$4\r\nllen\r\n$4\r\nlist\r\n"                                |
+// | LINDEX  | key index         | "*3\r\n$6\r\nlindex\r\n$4\r\nlist\r\n$1\r\n0\r\n"                   |
+// | LSET    | key index elem    | "*4\r\n$4\r\nlset\r\n$4\r\nlist\r\n$1\r\n0\r\n$5\r\nhello\r\n"      |
+// | LREM    | key count elem    | "*4\r\n$4\r\nlrem\r\n$4\r\nlist\r\n$1\r\n0\r\n$5\r\nhello\r\n"      |
+// | LTRIM   | key start stop    | "*4\r\n$5\r\nltrim\r\n$4\r\nlist\r\n$1\r\n0\r\n$2\r\n-1\r\n"        |
+// | BLPOP   | key [key..] tmout | "*3\r\n$5\r\nblpop\r\n$4\r\nlist\r\n$1\r\n0\r\n"                    |
+// | BRPOP   | key [key..] tmout | "*3\r\n$5\r\nbrpop\r\n$4\r\nlist\r\n$3\r\n0.5\r\n"                  |
+// | LMOVE   | src dst LEFT RIGHT| "*5\r\n$5\r\nlmove\r\n$3\r\nsrc\r\n$3\r\ndst\r\n$4\r\nLEFT\r\n$5\r\nRIGHT\r\n" |
+// | BLMOVE  | src dst L R tmout | "*6\r\n$6\r\nblmove\r\n$3\r\nsrc\r\n$3\r\ndst\r\n$4\r\nLEFT\r\n$5\r\nRIGHT\r\n$1\r\n0\r\n" |
+
+#[derive(Debug)]
+pub struct CommandLPush {
+    key: Bytes,
+    values: Vec<Bytes>,
+}
+#[derive(Debug)]
+pub struct CommandRPush {
+    key: Bytes,
+    values: Vec<Bytes>,
+}
+#[derive(Debug)]
+pub struct CommandLPop {
+    key: Bytes,
+    count: Option<usize>,
+}
+#[derive(Debug)]
+pub struct CommandRPop {
+    key: Bytes,
+    count: Option<usize>,
+}
+#[derive(Debug)]
+pub struct CommandLRange {
+    key: Bytes,
+    start: i64,
+    stop: i64,
+}
+#[derive(Debug)]
+pub struct CommandLLen {
+    key: Bytes,
+}
+#[derive(Debug)]
+pub struct CommandLIndex {
+    key: Bytes,
+    index: i64,
+}
+#[derive(Debug)]
+pub struct CommandLSet {
+    key: Bytes,
+    index: i64,
+    value: Bytes,
+}
+#[derive(Debug)]
+pub struct CommandLRem {
+    key: Bytes,
+    count: i64,
+    value: Bytes,
+}
+#[derive(Debug)]
+pub struct CommandLTrim {
+    key: Bytes,
+    start: i64,
+    stop: i64,
+}
+#[derive(Debug)]
+pub struct CommandBLPop {
+    keys: Vec<Bytes>,
+    timeout: Option<Duration>,
+}
+#[derive(Debug)]
+pub struct CommandBRPop {
+    keys: Vec<Bytes>,
+    timeout: Option<Duration>,
+}
+#[derive(Debug)]
+pub struct CommandLMove {
+    source: Bytes,
+    destination: Bytes,
+    from_front: bool,
+    to_front: bool,
+}
+#[derive(Debug)]
+pub struct CommandBLMove {
+    source: Bytes,
+    destination: Bytes,
+    from_front: bool,
+    to_front: bool,
+    timeout: Option<Duration>,
+}
+
+/// 弹出元素：不带 count 时返回单个元素，带 count 时返回数组，key 不存在返回 null
+fn list_pop(backend: &Backend, key: &[u8], count: Option<usize>, front: bool) -> RespFrame {
+    let popped = backend.list_update(key, |list| {
+        let n = count.unwrap_or(1).min(list.len());
+        let popped = (0..n)
+            .filter_map(|_| {
+                if front {
+                    list.pop_front()
+                } else {
+                    list.pop_back()
+                }
+            })
+            .collect::<Vec<_>>();
+        (popped, n)
+    });
+    match (popped, count) {
+        (Err(e), _) => e.into(),
+        (Ok(None), _) => RespFrame::RespNull(RespNull),
+        (Ok(Some(values)), Some(_)) => bulk_array(values),
+        (Ok(Some(mut values)), None) => values
+            .pop()
+            .map(RespFrame::from)
+            .unwrap_or(RespFrame::RespNull(RespNull)),
+    }
+}
+
+/// 将下标（支持负数）转换为合法下标
+fn normalize_index(index: i64, len: usize) -> Option<usize> {
+    let index = if index < 0 { len as i64 + index } else { index };
+    (0..len as i64).contains(&index).then_some(index as usize)
+}
+
+impl CommandExecutor for CommandLPush {
+    fn execute(self, backend: &Backend) -> RespFrame {
+        match backend.list_push(self.key, self.values, true) {
+            Ok(len) => RespInteger::new(len as i64).into(),
+            Err(e) => e.into(),
+        }
+    }
+}
+impl CommandExecutor for CommandRPush {
+    fn execute(self, backend: &Backend) -> RespFrame {
+        match backend.list_push(self.key, self.values, false) {
+            Ok(len) => RespInteger::new(len as i64).into(),
+            Err(e) => e.into(),
+        }
+    }
+}
+impl CommandExecutor for CommandLPop {
+    fn execute(self, backend: &Backend) -> RespFrame {
+        list_pop(backend, &self.key, self.count, true)
+    }
+}
+impl CommandExecutor for CommandRPop {
+    fn execute(self, backend: &Backend) -> RespFrame {
+        list_pop(backend, &self.key, self.count, false)
+    }
+}
+impl CommandExecutor for CommandLRange {
+    fn execute(self, backend: &Backend) -> RespFrame {
+        let values = backend.list_read(&self.key, |list| {
+            match normalize_range(self.start, self.stop, list.len()) {
+                Some((start, stop)) => list.range(start..=stop).cloned().collect(),
+                None => Vec::new(),
+            }
+        });
+        match values {
+            Ok(values) => bulk_array(values.unwrap_or_default()),
+            Err(e) => e.into(),
+        }
+    }
+}
+impl CommandExecutor for CommandLLen {
+    fn execute(self, backend: &Backend) -> RespFrame {
+        match backend.list_read(&self.key, |list| list.len()) {
+            Ok(len) => RespInteger::new(len.unwrap_or(0) as i64).into(),
+            Err(e) => e.into(),
+        }
+    }
+}
+impl CommandExecutor for CommandLIndex {
+    fn execute(self, backend: &Backend) -> RespFrame {
+        let value = backend.list_read(&self.key, |list| {
+            normalize_index(self.index, list.len()).map(|index| list[index].clone())
+        });
+        match value {
+            Ok(value) => value
+                .flatten()
+                .map(RespFrame::from)
+                .unwrap_or(RespFrame::RespNull(RespNull)),
+            Err(e) => e.into(),
+        }
+    }
+}
+impl CommandExecutor for CommandLSet {
+    fn execute(self, backend: &Backend) -> RespFrame {
+        let ret = backend.list_update(&self.key, |list| {
+            let set = normalize_index(self.index, list.len()).map(|index| list[index] = self.value);
+            let changes = set.is_some() as usize;
+            (set, changes)
+        });
+        match ret {
+            Err(e) => e.into(),
+            Ok(None) => SimpleError::new("ERR no such key").into(),
+            Ok(Some(None)) => SimpleError::new("ERR index out of range").into(),
+            Ok(Some(Some(()))) => RESP_OK.clone(),
+        }
+    }
+}
+impl CommandExecutor for CommandLRem {
+    fn execute(self, backend: &Backend) -> RespFrame {
+        let removed = backend.list_update(&self.key, |list| {
+            let limit = match self.count {
+                0 => usize::MAX,
+                count => count.unsigned_abs() as usize,
+            };
+            let mut positions = list
+                .iter()
+                .enumerate()
+                .filter(|(_, v)| **v == self.value)
+                .map(|(i, _)| i)
+                .collect::<Vec<_>>();
+            // count 为负数时从尾部开始删除
+            if self.count < 0 {
+                positions.reverse();
+            }
+            positions.truncate(limit);
+            positions.sort_unstable();
+            for index in positions.iter().rev() {
+                list.remove(*index);
+            }
+            (positions.len(), positions.len())
+        });
+        match removed {
+            Ok(removed) => RespInteger::new(removed.unwrap_or(0) as i64).into(),
+            Err(e) => e.into(),
+        }
+    }
+}
+impl CommandExecutor for CommandLTrim {
+    fn execute(self, backend: &Backend) -> RespFrame {
+        let ret = backend.list_update(&self.key, |list| {
+            let len = list.len();
+            match normalize_range(self.start, self.stop, len) {
+                Some((start, stop)) => {
+                    list.truncate(stop + 1);
+                    list.drain(..start);
+                }
+                None => list.clear(),
+            }
+            ((), len - list.len())
+        });
+        match ret {
+            Ok(_) => RESP_OK.clone(),
+            Err(e) => e.into(),
+        }
+    }
+}
+
+/// 阻塞弹出的回复：[key, 元素]，超时返回 null array
+fn blocking_pop_reply(popped: Result<Option<(Bytes, Bytes)>, WrongType>) -> RespFrame {
+    match popped {
+        Ok(Some((key, value))) => bulk_array([key, value]),
+        Ok(None) => RespFrame::RespNullArray(RespNullArray),
+        Err(e) => e.into(),
+    }
+}
+
+/// 非阻塞地从第一个非空列表弹出，用于不能阻塞的场景
+fn try_pop_first(
+    backend: &Backend,
+    keys: &[Bytes],
+    front: bool,
+) -> Result<Option<(Bytes, Bytes)>, WrongType> {
+    for key in keys {
+        if let Some(value) = backend.list_pop_one(key, front)? {
+            return Ok(Some((key.clone(), value)));
+        }
+    }
+    Ok(None)
+}
+
+/// 从 source 弹出元素并插入 destination，返回被移动的元素
+fn list_move(
+    backend: &Backend,
+    source: &Bytes,
+    from_front: bool,
+    target: &ListTarget,
+) -> RespFrame {
+    match backend.list_move(source, from_front, target) {
+        Ok(Some(value)) => value.into(),
+        Ok(None) => RespFrame::RespNull(RespNull),
+        Err(e) => e.into(),
+    }
+}
+
+impl CommandExecutor for CommandBLPop {
+    fn execute(self, backend: &Backend) -> RespFrame {
+        blocking_pop_reply(try_pop_first(backend, &self.keys, true))
+    }
+}
+impl CommandExecutor for CommandBRPop {
+    fn execute(self, backend: &Backend) -> RespFrame {
+        blocking_pop_reply(try_pop_first(backend, &self.keys, false))
+    }
+}
+impl CommandExecutor for CommandLMove {
+    fn execute(self, backend: &Backend) -> RespFrame {
+        list_move(backend, &self.source, self.from_front, &self.target())
+    }
+}
+impl CommandExecutor for CommandBLMove {
+    fn execute(self, backend: &Backend) -> RespFrame {
+        list_move(backend, &self.source, self.from_front, &self.target())
+    }
+}
+impl CommandLMove {
+    fn target(&self) -> ListTarget {
+        ListTarget {
+            destination: self.destination.clone(),
+            front: self.to_front,
+        }
+    }
+}
+
+impl CommandBLPop {
+    /// 阻塞直到任意一个列表有数据或超时
+    pub async fn execute_blocking(self, backend: &Backend) -> RespFrame {
+        blocking_pop_reply(
+            backend
+                .list_blocking_pop(&self.keys, true, None, self.timeout)
+                .await,
+        )
+    }
+}
+impl CommandBRPop {
+    /// 阻塞直到任意一个列表有数据或超时
+    pub async fn execute_blocking(self, backend: &Backend) -> RespFrame {
+        blocking_pop_reply(
+            backend
+                .list_blocking_pop(&self.keys, false, None, self.timeout)
+                .await,
+        )
+    }
+}
+impl CommandBLMove {
+    fn target(&self) -> ListTarget {
+        ListTarget {
+            destination: self.destination.clone(),
+            front: self.to_front,
+        }
+    }
+    /// 阻塞直到 source 有数据或超时，被服务时弹出和插入一起完成
+    pub async fn execute_blocking(self, backend: &Backend) -> RespFrame {
+        if let Err(e) = backend.list_read(&self.destination, |_| ()) {
+            return e.into();
+        }
+        let target = self.target();
+        match backend
+            .list_blocking_pop(&[self.source], self.from_front, Some(&target), self.timeout)
+            .await
+        {
+            Ok(Some((_, value))) => value.into(),
+            Ok(None) => RespFrame::RespNull(RespNull),
+            Err(e) => e.into(),
+        }
+    }
+}
+
+/// 解析阻塞超时时间（秒，可以是小数），0 表示一直阻塞
+fn extract_timeout(frame: &RespFrame, name: &str) -> Result<Option<Duration>, CommandError> {
+    let timeout = extract_string(frame, name)?
+        .parse::<f64>()
+        .ok()
+        .filter(|timeout| timeout.is_finite())
+        .ok_or_else(|| {
+            CommandError::InvalidArguments("timeout is not a float or out of range".to_string())
+        })?;
+    if timeout < 0.0 {
+        return Err(CommandError::InvalidArguments(
+            "timeout is negative".to_string(),
+        ));
+    }
+    if timeout == 0.0 {
+        return Ok(None);
+    }
+    // 超出 Duration 范围的超时时间无法表示
+    Duration::try_from_secs_f64(timeout)
+        .map(Some)
+        .map_err(|_| CommandError::InvalidArguments("timeout is out of range".to_string()))
+}
+
+/// 解析 LEFT/RIGHT，返回是否为头部
+fn extract_direction(frame: &RespFrame, name: &str) -> Result<bool, CommandError> {
+    match extract_string(frame, name)?.to_ascii_uppercase().as_str() {
+        "LEFT" => Ok(true),
+        "RIGHT" => Ok(false),
+        _ => Err(CommandError::InvalidArguments("syntax error".to_string())),
+    }
+}
+
+/// 解析 BLPOP/BRPOP 的参数：key [key ...] timeout
+fn parse_blocking_pop(
+    value: &RespArray,
+    name: &'static str,
+) -> Result<(Vec<Bytes>, Option<Duration>), CommandError> {
+    let args = command_args(value)?;
+    let (timeout, keys) = args.split_last().ok_or_else(|| {
+        CommandError::InvalidNumberOfArguments(format!("{} command expects keys", name))
+    })?;
+    let keys = keys
+        .iter()
+        .map(|key| extract_bytes(key, name))
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok((keys, extract_timeout(timeout, name)?))
+}
+
+/// 解析 LPUSH/RPUSH 的参数：key element [element ...]
+fn parse_push(value: &RespArray, name: &'static str) -> Result<(Bytes, Vec<Bytes>), CommandError> {
+    let args = command_args(value)?;
+    let key = extract_bytes(args[0], name)?;
+    let values = args[1..]
+        .iter()
+        .map(|arg| extract_bytes(arg, name))
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok((key, values))
+}
+
+/// 解析 LPOP/RPOP 的参数：key [count]
+fn parse_pop(
+    value: &RespArray,
+    name: &'static str,
+) -> Result<(Bytes, Option<usize>), CommandError> {
+    let args = command_args(value)?;
+    let key = extract_bytes(args[0], name)?;
+    let count = match args[1..] {
+        [] => None,
+        [count] => {
+            let count = extract_int(count, name)?;
+            if count < 0 {
+                return Err(CommandError::InvalidArguments(
+                    "value is out of range, must be positive".to_string(),
+                ));
+            }
+            Some(count as usize)
+        }
+        _ => {
+            return Err(CommandError::InvalidNumberOfArguments(format!(
+                "{} command expects at most 2 arguments, got {}",
+                name,
+                args.len()
+            )));
+        }
+    };
+    Ok((key, count))
+}
+
+/// 解析 LRANGE/LTRIM 的参数：key start stop
+fn parse_range(value: &RespArray, name: &'static str) -> Result<(Bytes, i64, i64), CommandError> {
+    let args = command_args(value)?;
+    Ok((
+        extract_bytes(args[0], name)?,
+        extract_int(args[1], name)?,
+        extract_int(args[2], name)?,
+    ))
+}
+
+impl TryFrom<RespArray> for CommandLPush {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        let (key, values) = parse_push(&value, "LPUSH")?;
+        Ok(CommandLPush { key, values })
+    }
+}
+impl TryFrom<RespArray> for CommandRPush {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        let (key, values) = parse_push(&value, "RPUSH")?;
+        Ok(CommandRPush { key, values })
+    }
+}
+impl TryFrom<RespArray> for CommandLPop {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        let (key, count) = parse_pop(&value, "LPOP")?;
+        Ok(CommandLPop { key, count })
+    }
+}
+impl TryFrom<RespArray> for CommandRPop {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        let (key, count) = parse_pop(&value, "RPOP")?;
+        Ok(CommandRPop { key, count })
+    }
+}
+impl TryFrom<RespArray> for CommandLRange {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        let (key, start, stop) = parse_range(&value, "LRANGE")?;
+        Ok(CommandLRange { key, start, stop })
+    }
+}
+impl TryFrom<RespArray> for CommandLTrim {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        let (key, start, stop) = parse_range(&value, "LTRIM")?;
+        Ok(CommandLTrim { key, start, stop })
+    }
+}
+impl TryFrom<RespArray> for CommandLLen {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        let args = command_args(&value)?;
+        Ok(CommandLLen {
+            key: extract_bytes(args[0], "LLEN")?,
+        })
+    }
+}
+impl TryFrom<RespArray> for CommandLIndex {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        let args = command_args(&value)?;
+        Ok(CommandLIndex {
+            key: extract_bytes(args[0], "LINDEX")?,
+            index: extract_int(args[1], "LINDEX")?,
+        })
+    }
+}
+impl TryFrom<RespArray> for CommandLSet {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        let args = command_args(&value)?;
+        Ok(CommandLSet {
+            key: extract_bytes(args[0], "LSET")?,
+            index: extract_int(args[1], "LSET")?,
+            value: extract_bytes(args[2], "LSET")?,
+        })
+    }
+}
+impl TryFrom<RespArray> for CommandLRem {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        let args = command_args(&value)?;
+        Ok(CommandLRem {
+            key: extract_bytes(args[0], "LREM")?,
+            count: extract_int(args[1], "LREM")?,
+            value: extract_bytes(args[2], "LREM")?,
+        })
+    }
+}
+
+impl TryFrom<RespArray> for CommandBLPop {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        let (keys, timeout) = parse_blocking_pop(&value, "BLPOP")?;
+        Ok(CommandBLPop { keys, timeout })
+    }
+}
+impl TryFrom<RespArray> for CommandBRPop {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        let (keys, timeout) = parse_blocking_pop(&value, "BRPOP")?;
+        Ok(CommandBRPop { keys, timeout })
+    }
+}
+impl TryFrom<RespArray> for CommandLMove {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        let args = command_args(&value)?;
+        Ok(CommandLMove {
+            source: extract_bytes(args[0], "LMOVE")?,
+            destination: extract_bytes(args[1], "LMOVE")?,
+            from_front: extract_direction(args[2], "LMOVE")?,
+            to_front: extract_direction(args[3], "LMOVE")?,
+        })
+    }
+}
+impl TryFrom<RespArray> for CommandBLMove {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        let args = command_args(&value)?;
+        Ok(CommandBLMove {
+            source: extract_bytes(args[0], "BLMOVE")?,
+            destination: extract_bytes(args[1], "BLMOVE")?,
+            from_front: extract_direction(args[2], "BLMOVE")?,
+            to_front: extract_direction(args[3], "BLMOVE")?,
+            timeout: extract_timeout(args[4], "BLMOVE")?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::BytesMut;
+
+    use super::*;
+    use crate::resp::RespDecode;
+
+    fn lrange(backend: &Backend, key: &'static str) -> RespFrame {
+        CommandLRange {
+            key: Bytes::from(key),
+            start: 0,
+            stop: -1,
+        }
+        .execute(backend)
+    }
+
+    #[test]
+    fn test_command_list_try_from() -> Result<(), CommandError> {
+        let mut buf =
+            BytesMut::from("*4\r\n$5\r\nlpush\r\n$4\r\nlist\r\n$5\r\nhello\r\n$5\r\nworld\r\n");
+        let cmd = CommandLPush::try_from(RespArray::decode(&mut buf)?)?;
+        assert_eq!(cmd.key, "list");
+        assert_eq!(cmd.values, vec!["hello", "world"]);
+
+        let mut buf = BytesMut::from("*4\r\n$6\r\nlrange\r\n$4\r\nlist\r\n$1\r\n0\r\n$2\r\n-1\r\n");
+        let cmd = CommandLRange::try_from(RespArray::decode(&mut buf)?)?;
+        assert_eq!((cmd.start, cmd.stop), (0, -1));
+
+        let mut buf = BytesMut::from("*3\r\n$4\r\nrpop\r\n$4\r\nlist\r\n$1\r\n2\r\n");
+        let cmd = CommandRPop::try_from(RespArray::decode(&mut buf)?)?;
+        assert_eq!(cmd.count, Some(2));
+
+        let cmd: RespArray = vec!["LPOP".into(), "list".into(), "-1".into()].into();
+        assert!(CommandLPop::try_from(cmd).is_err());
+        Ok(())
+    }
+    #[test]
+    fn test_push_pop_commands() {
+        let backend = Backend::new();
+        let cmd = CommandRPush {
+            key: "list".into(),
+            values: vec!["a".into(), "b".into(), "c".into()],
+        };
+        assert_eq!(cmd.execute(&backend), RespInteger::new(3).into());
+        let cmd = CommandLPush {
+            key: "list".into(),
+            values: vec!["x".into(), "y".into()],
+        };
+        assert_eq!(cmd.execute(&backend), RespInteger::new(5).into());
+        let expected: RespArray =
+            vec!["y".into(), "x".into(), "a".into(), "b".into(), "c".into()].into();
+        assert_eq!(lrange(&backend, "list"), expected.into());
+
+        let cmd = CommandLPop {
+            key: "list".into(),
+            count: None,
+        };
+        assert_eq!(cmd.execute(&backend), "y".into());
+        let cmd = CommandRPop {
+            key: "list".into(),
+            count: Some(2),
+        };
+        let expected: RespArray = vec!["c".into(), "b".into()].into();
+        assert_eq!(cmd.execute(&backend), expected.into());
+        let cmd = CommandRPop {
+            key: "list".into(),
+            count: Some(10),
+        };
+        let expected: RespArray = vec!["a".into(), "x".into()].into();
+        assert_eq!(cmd.execute(&backend), expected.into());
+
+        // 列表为空后 key 被删除
+        let cmd = CommandLPop {
+            key: "list".into(),
+            count: None,
+        };
+        assert_eq!(cmd.execute(&backend), RespNull.into());
+        let cmd = CommandLLen { key: "list".into() };
+        assert_eq!(cmd.execute(&backend), RespInteger::new(0).into());
+    }
+    #[test]
+    fn test_index_set_rem_trim_commands() {
+        let backend = Backend::new();
+        backend
+            .list_push(
+                "list".into(),
+                vec!["a".into(), "b".into(), "a".into(), "c".into(), "a".into()],
+                false,
+            )
+            .unwrap();
+        let cmd = CommandLIndex {
+            key: "list".into(),
+            index: -2,
+        };
+        assert_eq!(cmd.execute(&backend), "c".into());
+        let cmd = CommandLIndex {
+            key: "list".into(),
+            index: 5,
+        };
+        assert_eq!(cmd.execute(&backend), RespNull.into());
+
+        let cmd = CommandLSet {
+            key: "list".into(),
+            index: 1,
+            value: "B".into(),
+        };
+        assert_eq!(cmd.execute(&backend), RESP_OK.clone());
+        let cmd = CommandLSet {
+            key: "list".into(),
+            index: 10,
+            value: "B".into(),
+        };
+        assert_eq!(
+            cmd.execute(&backend),
+            SimpleError::new("ERR index out of range").into()
+        );
+        let cmd = CommandLSet {
+            key: "missing".into(),
+            index: 0,
+            value: "B".into(),
+        };
+        assert_eq!(
+            cmd.execute(&backend),
+            SimpleError::new("ERR no such key").into()
+        );
+
+        // 从尾部删除一个 a
+        let cmd = CommandLRem {
+            key: "list".into(),
+            count: -1,
+            value: "a".into(),
+        };
+        assert_eq!(cmd.execute(&backend), RespInteger::new(1).into());
+        let expected: RespArray = vec!["a".into(), "B".into(), "a".into(), "c".into()].into();
+        assert_eq!(lrange(&backend, "list"), expected.into());
+        let cmd = CommandLRem {
+            key: "list".into(),
+            count: 0,
+            value: "a".into(),
+        };
+        assert_eq!(cmd.execute(&backend), RespInteger::new(2).into());
+
+        let cmd = CommandLTrim {
+            key: "list".into(),
+            start: 1,
+            stop: -1,
+        };
+        assert_eq!(cmd.execute(&backend), RESP_OK.clone());
+        let expected: RespArray = vec!["c".into()].into();
+        assert_eq!(lrange(&backend, "list"), expected.into());
+        let cmd = CommandLTrim {
+            key: "list".into(),
+            start: 5,
+            stop: 10,
+        };
+        cmd.execute(&backend);
+        assert!(!backend.contains_key(b"list"));
+    }
+    #[test]
+    fn test_command_blocking_try_from() -> Result<(), CommandError> {
+        let mut buf = BytesMut::from("*4\r\n$5\r\nblpop\r\n$2\r\nl1\r\n$2\r\nl2\r\n$3\r\n0.5\r\n");
+        let cmd = CommandBLPop::try_from(RespArray::decode(&mut buf)?)?;
+        assert_eq!(cmd.keys, vec!["l1", "l2"]);
+        assert_eq!(cmd.timeout, Some(Duration::from_millis(500)));
+
+        let mut buf = BytesMut::from(
+            "*6\r\n$6\r\nblmove\r\n$3\r\nsrc\r\n$3\r\ndst\r\n$4\r\nLEFT\r\n$5\r\nright\r\n$1\r\n0\r\n",
+        );
+        let cmd = CommandBLMove::try_from(RespArray::decode(&mut buf)?)?;
+        assert!(cmd.from_front);
+        assert!(!cmd.to_front);
+        assert_eq!(cmd.timeout, None);
+
+        let cmd: RespArray = vec!["BRPOP".into(), "list".into(), "-1".into()].into();
+        assert!(CommandBRPop::try_from(cmd).is_err());
+        // 有限但超出 Duration 范围的超时时间返回错误，而不是 panic
+        let cmd: RespArray = vec!["BLPOP".into(), "list".into(), "1e20".into()].into();
+        let ret = CommandBLPop::try_from(cmd);
+        assert!(matches!(ret, Err(e) if e.to_string().ends_with("timeout is out of range")));
+        Ok(())
+    }
+    #[tokio::test]
+    async fn test_blocking_commands() {
+        let backend = Backend::new();
+        let cmd = CommandBLPop {
+            keys: vec!["l1".into(), "l2".into()],
+            timeout: Some(Duration::from_millis(10)),
+        };
+        assert_eq!(cmd.execute_blocking(&backend).await, RespNullArray.into());
+
+        let waiting = {
+            let backend = backend.clone();
+            tokio::spawn(async move {
+                let cmd = CommandBLMove {
+                    source: "l2".into(),
+                    destination: "l3".into(),
+                    from_front: false,
+                    to_front: true,
+                    timeout: None,
+                };
+                cmd.execute_blocking(&backend).await
+            })
+        };
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        let cmd = CommandRPush {
+            key: "l2".into(),
+            values: vec!["a".into(), "b".into()],
+        };
+        cmd.execute(&backend);
+        assert_eq!(waiting.await.unwrap(), "b".into());
+
+        let cmd = CommandBRPop {
+            keys: vec!["l1".into(), "l3".into()],
+            timeout: None,
+        };
+        let expected: RespArray = vec!["l3".into(), "b".into()].into();
+        assert_eq!(cmd.execute_blocking(&backend).await, expected.into());
+
+        let cmd = CommandLMove {
+            source: "l2".into(),
+            destination: "l2".into(),
+            from_front: true,
+            to_front: false,
+        };
+        assert_eq!(cmd.execute(&backend), "a".into());
+    }
+}
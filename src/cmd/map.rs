@@ -1,67 +1,751 @@
+use std::mem;
+
+use bytes::{Bytes, BytesMut};
+
 use crate::{
-    backend::Backend,
-    cmd::{CommandError, CommandExecutor, CommandGet, CommandSet, RESP_OK, valid_command},
-    resp::{BulkString, RespArray, RespFrame, RespNull},
+    backend::{Backend, MAX_STRING_LEN, SetCondition, SetTtl, now_ms},
+    cmd::{
+        CommandError, CommandExecutor, CommandGet, CommandSet, RESP_OK, SetExpire, bulk_array,
+        command_args, extract_bytes, extract_int, extract_string, parse_strict_int,
+    },
+    resp::{RespArray, RespDouble, RespFrame, RespInteger, RespNull, SimpleError},
 };
 
 // Redis命令与RESP协议格式对应表
 // | 命令    | 参数         | 对应格式                                                                 |
 // |---------|--------------|--------------------------------------------------------------------------|
 // | SET     | key val      | "*3\r\n$3\r\nset\r\n$5\r\nhello\r\n$5\r\nworld\r\n"                      |
+// | SET     | key val EX s | "*5\r\n$3\r\nset\r\n$5\r\nhello\r\n$5\r\nworld\r\n$2\r\nEX\r\n$2\r\n10\r\n"   |
 // | GET     | key          | "*2\r\n$3\r\nget\r\n$5\r\nhello\r\n"                                     |
+// | APPEND  | key val      | "*3\r\n$6\r\nappend\r\n$5\r\nhello\r\n$5\r\nworld\r\n"                   |
+// | STRLEN  | key          | "*2\r\n$6\r\nstrlen\r\n$5\r\nhello\r\n"                                  |
+// | GETRANGE| key start end| "*4\r\n$8\r\ngetrange\r\n$5\r\nhello\r\n$1\r\n0\r\n$2\r\n-1\r\n"         |
+// | SETRANGE| key off val  | "*4\r\n$8\r\nsetrange\r\n$5\r\nhello\r\n$1\r\n6\r\n$5\r\nworld\r\n"      |
+// | GETSET  | key val      | "*3\r\n$6\r\ngetset\r\n$5\r\nhello\r\n$5\r\nworld\r\n"                   |
+// | GETDEL  | key          | "*2\r\n$6\r\ngetdel\r\n$5\r\nhello\r\n"                                  |
+// | GETEX   | key PERSIST  | "*3\r\n$5\r\ngetex\r\n$5\r\nhello\r\n$7\r\nPERSIST\r\n"                 |
+// | MSET    | key val ...  | "*5\r\n$4\r\nmset\r\n$1\r\na\r\n$1\r\n1\r\n$1\r\nb\r\n$1\r\n2\r\n"        |
+// | MGET    | key ...      | "*3\r\n$4\r\nmget\r\n$1\r\na\r\n$1\r\nb\r\n"                            |
+// | MSETNX  | key val ...  | "*5\r\n$6\r\nmsetnx\r\n$1\r\na\r\n$1\r\n1\r\n$1\r\nb\r\n$1\r\n2\r\n"      |
+// | SETNX   | key val      | "*3\r\n$5\r\nsetnx\r\n$5\r\nhello\r\n$5\r\nworld\r\n"                    |
+// | INCR    | key          | "*2\r\n$4\r\nincr\r\n$7\r\ncounter\r\n"                                  |
+// | DECR    | key          | "*2\r\n$4\r\ndecr\r\n$7\r\ncounter\r\n"                                  |
+// | INCRBY  | key n        | "*3\r\n$6\r\nincrby\r\n$7\r\ncounter\r\n$2\r\n10\r\n"                     |
+// | DECRBY  | key n        | "*3\r\n$6\r\ndecrby\r\n$7\r\ncounter\r\n$2\r\n10\r\n"                     |
+// | INCRBYFLOAT | key n    | "*3\r\n$11\r\nincrbyfloat\r\n$7\r\ncounter\r\n$3\r\n0.5\r\n"               |
 // | HSET    | key field val| "*4\r\n$4\r\nhset\r\n$3\r\nmap\r\n$5\r\nhello\r\n$5\r\nworld\r\n"         |
 // | HGET    | key field    | "*3\r\n$4\r\nhget\r\n$3\r\nmap\r\n$5\r\nhello\r\n"                       |
 // | HGETALL | key          | "*2\r\n$7\r\nhgetall\r\n$3\r\nmap\r\n"                                   |
 
+#[derive(Debug)]
+pub struct CommandAppend {
+    key: Bytes,
+    value: Bytes,
+}
+#[derive(Debug)]
+pub struct CommandStrLen {
+    key: Bytes,
+}
+/// 闭区间 [start, end]，负数从末尾算起
+#[derive(Debug)]
+pub struct CommandGetRange {
+    key: Bytes,
+    start: i64,
+    end: i64,
+}
+/// 从 offset 开始覆盖写入，原字符串不够长时用 0 字节补齐
+#[derive(Debug)]
+pub struct CommandSetRange {
+    key: Bytes,
+    offset: usize,
+    value: Bytes,
+}
+#[derive(Debug)]
+pub struct CommandGetSet {
+    key: Bytes,
+    value: Bytes,
+}
+#[derive(Debug)]
+pub struct CommandGetDel {
+    key: Bytes,
+}
+#[derive(Debug)]
+pub struct CommandGetEx {
+    key: Bytes,
+    ttl: Option<GetExTtl>,
+}
+/// GETEX 的过期选项
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GetExTtl {
+    /// EX / PX / EXAT / PXAT
+    Expire(SetExpire),
+    Persist,
+}
+#[derive(Debug)]
+pub struct CommandMSet {
+    pairs: Vec<(Bytes, Bytes)>,
+}
+#[derive(Debug)]
+pub struct CommandMGet {
+    keys: Vec<Bytes>,
+}
+#[derive(Debug)]
+pub struct CommandMSetNx {
+    pairs: Vec<(Bytes, Bytes)>,
+}
+#[derive(Debug)]
+pub struct CommandSetNx {
+    key: Bytes,
+    value: Bytes,
+}
+#[derive(Debug)]
+pub struct CommandIncr {
+    key: Bytes,
+}
+#[derive(Debug)]
+pub struct CommandDecr {
+    key: Bytes,
+}
+#[derive(Debug)]
+pub struct CommandIncrBy {
+    key: Bytes,
+    increment: i64,
+}
+#[derive(Debug)]
+pub struct CommandDecrBy {
+    key: Bytes,
+    decrement: i64,
+}
+#[derive(Debug)]
+pub struct CommandIncrByFloat {
+    key: Bytes,
+    increment: f64,
+}
+
 impl CommandExecutor for CommandGet {
     fn execute(self, backend: &Backend) -> RespFrame {
-        backend
-            .get(&self.key)
-            .unwrap_or(RespFrame::RespNull(RespNull))
+        match backend.get(&self.key) {
+            Ok(value) => value
+                .map(RespFrame::from)
+                .unwrap_or(RespFrame::RespNull(RespNull)),
+            Err(e) => e.into(),
+        }
     }
 }
 
 impl CommandExecutor for CommandSet {
     fn execute(self, backend: &Backend) -> RespFrame {
-        backend.set(self.key, self.value);
+        let ttl = match self.expire {
+            None => SetTtl::Clear,
+            Some(SetExpire::KeepTtl) => SetTtl::Keep,
+            Some(expire) => SetTtl::At(expire_deadline(expire)),
+        };
+        let (applied, old) =
+            match backend.set_with(self.key, self.value, self.condition, ttl, self.get) {
+                Ok(ret) => ret,
+                Err(e) => return e.into(),
+            };
+        match (self.get, applied) {
+            (true, _) => old
+                .map(RespFrame::from)
+                .unwrap_or(RespFrame::RespNull(RespNull)),
+            (false, true) => RESP_OK.clone(),
+            (false, false) => RespFrame::RespNull(RespNull),
+        }
+    }
+}
+
+impl CommandExecutor for CommandAppend {
+    fn execute(self, backend: &Backend) -> RespFrame {
+        let ret = backend.upsert(&self.key, |value: &mut Bytes| -> Result<_, SimpleError> {
+            check_string_len(value.len(), self.value.len())?;
+            let mut buf = BytesMut::from(mem::take(value));
+            buf.extend_from_slice(&self.value);
+            *value = buf.freeze();
+            Ok((value.len(), 1))
+        });
+        match ret {
+            Ok(Ok(len)) => RespInteger::new(len as i64).into(),
+            Ok(Err(e)) => e.into(),
+            Err(e) => e.into(),
+        }
+    }
+}
+impl CommandExecutor for CommandStrLen {
+    fn execute(self, backend: &Backend) -> RespFrame {
+        match backend.read(&self.key, Bytes::len) {
+            Ok(len) => RespInteger::new(len.unwrap_or_default() as i64).into(),
+            Err(e) => e.into(),
+        }
+    }
+}
+impl CommandExecutor for CommandGetRange {
+    fn execute(self, backend: &Backend) -> RespFrame {
+        let value = backend.read(&self.key, |value: &Bytes| {
+            match string_range(self.start, self.end, value.len()) {
+                Some((start, end)) => value.slice(start..=end),
+                None => Bytes::new(),
+            }
+        });
+        match value {
+            Ok(value) => value.unwrap_or_default().into(),
+            Err(e) => e.into(),
+        }
+    }
+}
+impl CommandExecutor for CommandSetRange {
+    fn execute(self, backend: &Backend) -> RespFrame {
+        // 写入空串不修改字符串，也不创建 key
+        if self.value.is_empty() {
+            return match backend.read(&self.key, Bytes::len) {
+                Ok(len) => RespInteger::new(len.unwrap_or_default() as i64).into(),
+                Err(e) => e.into(),
+            };
+        }
+        let ret = backend.upsert(&self.key, |value: &mut Bytes| {
+            let end = self.offset + self.value.len();
+            let mut buf = BytesMut::from(mem::take(value));
+            if buf.len() < end {
+                buf.resize(end, 0);
+            }
+            buf[self.offset..end].copy_from_slice(&self.value);
+            *value = buf.freeze();
+            (value.len(), 1)
+        });
+        match ret {
+            Ok(len) => RespInteger::new(len as i64).into(),
+            Err(e) => e.into(),
+        }
+    }
+}
+impl CommandExecutor for CommandGetSet {
+    fn execute(self, backend: &Backend) -> RespFrame {
+        match backend.set_with(
+            self.key,
+            self.value,
+            SetCondition::Always,
+            SetTtl::Clear,
+            true,
+        ) {
+            Ok((_, old)) => old
+                .map(RespFrame::from)
+                .unwrap_or(RespFrame::RespNull(RespNull)),
+            Err(e) => e.into(),
+        }
+    }
+}
+impl CommandExecutor for CommandGetDel {
+    fn execute(self, backend: &Backend) -> RespFrame {
+        match backend.getdel(&self.key) {
+            Ok(value) => value
+                .map(RespFrame::from)
+                .unwrap_or(RespFrame::RespNull(RespNull)),
+            Err(e) => e.into(),
+        }
+    }
+}
+impl CommandExecutor for CommandGetEx {
+    fn execute(self, backend: &Backend) -> RespFrame {
+        let value = match backend.get(&self.key) {
+            Ok(Some(value)) => value,
+            Ok(None) => return RespFrame::RespNull(RespNull),
+            Err(e) => return e.into(),
+        };
+        match self.ttl {
+            Some(GetExTtl::Expire(expire)) => {
+                backend.expire_at(&self.key, expire_deadline(expire));
+            }
+            Some(GetExTtl::Persist) => {
+                backend.persist(&self.key);
+            }
+            None => {}
+        }
+        value.into()
+    }
+}
+impl CommandExecutor for CommandMSet {
+    fn execute(self, backend: &Backend) -> RespFrame {
+        backend.mset(self.pairs);
         RESP_OK.clone()
     }
 }
+impl CommandExecutor for CommandMGet {
+    fn execute(self, backend: &Backend) -> RespFrame {
+        // 不是字符串的 key 与不存在的 key 一样返回 nil
+        let values = self
+            .keys
+            .iter()
+            .map(|key| match backend.get(key) {
+                Ok(Some(value)) => value.into(),
+                _ => RespNull.into(),
+            })
+            .collect();
+        RespArray::from_vec(values).into()
+    }
+}
+impl CommandExecutor for CommandMSetNx {
+    fn execute(self, backend: &Backend) -> RespFrame {
+        RespInteger::new(backend.msetnx(self.pairs) as i64).into()
+    }
+}
+impl CommandExecutor for CommandSetNx {
+    fn execute(self, backend: &Backend) -> RespFrame {
+        match backend.set_with(
+            self.key,
+            self.value,
+            SetCondition::IfNotExists,
+            SetTtl::Clear,
+            false,
+        ) {
+            Ok((applied, _)) => RespInteger::new(applied as i64).into(),
+            Err(e) => e.into(),
+        }
+    }
+}
+impl CommandExecutor for CommandIncr {
+    fn execute(self, backend: &Backend) -> RespFrame {
+        incr_by(backend, &self.key, 1)
+    }
+}
+impl CommandExecutor for CommandDecr {
+    fn execute(self, backend: &Backend) -> RespFrame {
+        incr_by(backend, &self.key, -1)
+    }
+}
+impl CommandExecutor for CommandIncrBy {
+    fn execute(self, backend: &Backend) -> RespFrame {
+        incr_by(backend, &self.key, self.increment)
+    }
+}
+impl CommandExecutor for CommandDecrBy {
+    fn execute(self, backend: &Backend) -> RespFrame {
+        // 解析时已排除 i64::MIN，取反不会溢出
+        incr_by(backend, &self.key, -self.decrement)
+    }
+}
+impl CommandExecutor for CommandIncrByFloat {
+    fn execute(self, backend: &Backend) -> RespFrame {
+        let ret = backend.upsert_with(
+            &self.key,
+            |value: &mut Bytes, exists| -> Result<_, SimpleError> {
+                // 只有 key 不存在时从 0 开始，空字符串不是合法的浮点数
+                let current = if exists {
+                    str::from_utf8(value)
+                        .ok()
+                        .and_then(|value| value.parse::<f64>().ok())
+                        .filter(|value| value.is_finite())
+                        .ok_or_else(|| SimpleError::new("ERR value is not a valid float"))?
+                } else {
+                    0.0
+                };
+                let new = current + self.increment;
+                if !new.is_finite() {
+                    return Err(SimpleError::new(
+                        "ERR increment would produce NaN or Infinity",
+                    ));
+                }
+                *value = Bytes::from(new.to_string());
+                Ok((new, 1))
+            },
+        );
+        // RESP3 回复 double，RESP2 客户端由 into_resp2 转为与存储值相同的 bulk string
+        match ret {
+            Ok(Ok(value)) => RespDouble::new(value).into(),
+            Ok(Err(e)) => e.into(),
+            Err(e) => e.into(),
+        }
+    }
+}
+
+/// 在 key 的分片锁内把整数加上 delta，key 不存在时从 0 开始，保留原有的过期时间
+fn incr_by(backend: &Backend, key: &Bytes, delta: i64) -> RespFrame {
+    let ret = backend.upsert_with(key, |value: &mut Bytes, exists| -> Result<_, SimpleError> {
+        let current = if exists {
+            parse_strict_int(value)
+                .ok_or_else(|| SimpleError::new("ERR value is not an integer or out of range"))?
+        } else {
+            0
+        };
+        let new = current
+            .checked_add(delta)
+            .ok_or_else(|| SimpleError::new("ERR increment or decrement would overflow"))?;
+        *value = Bytes::from(new.to_string());
+        Ok((new, 1))
+    });
+    match ret {
+        Ok(Ok(value)) => RespInteger::new(value).into(),
+        Ok(Err(e)) => e.into(),
+        Err(e) => e.into(),
+    }
+}
+
+impl SetExpire {
+    /// EX/PX 换算为 PXAT，其他选项不变
+    pub fn absolute(self) -> Self {
+        match self {
+            SetExpire::Ex(seconds) => SetExpire::PxAt(now_ms().saturating_add(seconds * 1000)),
+            SetExpire::Px(millis) => SetExpire::PxAt(now_ms().saturating_add(millis)),
+            expire => expire,
+        }
+    }
+}
+
+/// 过期选项对应的绝对过期时间（unix 毫秒），KEEPTTL 没有过期时间
+fn expire_deadline(expire: SetExpire) -> u64 {
+    match expire.absolute() {
+        SetExpire::ExAt(seconds) => seconds * 1000,
+        SetExpire::PxAt(millis) => millis,
+        _ => unreachable!("KEEPTTL has no deadline"),
+    }
+}
+
+/// 追加后的长度不能超过字符串的最大长度
+fn check_string_len(len: usize, extra: usize) -> Result<(), SimpleError> {
+    if len.saturating_add(extra) > MAX_STRING_LEN {
+        return Err(SimpleError::new(
+            "ERR string exceeds maximum allowed size (proto-max-bulk-len)",
+        ));
+    }
+    Ok(())
+}
+
+/// GETRANGE 的闭区间转换为合法下标，越界部分截断，区间为空时返回 None
+/// 与 Redis 一致：两端都是负数且 start > end 时为空，end 换算后小于 0 时按 0 处理
+fn string_range(start: i64, end: i64, len: usize) -> Option<(usize, usize)> {
+    if len == 0 || (start < 0 && end < 0 && start > end) {
+        return None;
+    }
+    let len = len as i64;
+    let start = if start < 0 {
+        (len + start).max(0)
+    } else {
+        start
+    };
+    let end = if end < 0 {
+        (len + end).max(0)
+    } else {
+        end.min(len - 1)
+    };
+    (start <= end).then_some((start as usize, end as usize))
+}
+
+impl CommandSet {
+    /// 相对过期时间换算为绝对时间，执行和写入 AOF 使用同一个过期时间
+    pub fn absolute(mut self) -> Self {
+        self.expire = self.expire.map(SetExpire::absolute);
+        self
+    }
+    /// 经过 absolute 换算后以 PXAT 写入 AOF，其他情况原样写入
+    pub fn aof_frame(&self) -> Option<RespFrame> {
+        let Some(SetExpire::PxAt(at)) = self.expire else {
+            return None;
+        };
+        let mut args = vec![
+            Bytes::from_static(b"set"),
+            self.key.clone(),
+            self.value.clone(),
+        ];
+        match self.condition {
+            SetCondition::Always => {}
+            SetCondition::IfNotExists => args.push(Bytes::from_static(b"NX")),
+            SetCondition::IfExists => args.push(Bytes::from_static(b"XX")),
+        }
+        if self.get {
+            args.push(Bytes::from_static(b"GET"));
+        }
+        args.extend([Bytes::from_static(b"PXAT"), Bytes::from(at.to_string())]);
+        Some(bulk_array(args))
+    }
+}
+
+impl CommandGetEx {
+    /// 相对过期时间换算为绝对时间，同 CommandSet::absolute
+    pub fn absolute(mut self) -> Self {
+        if let Some(GetExTtl::Expire(expire)) = self.ttl {
+            self.ttl = Some(GetExTtl::Expire(expire.absolute()));
+        }
+        self
+    }
+    /// 过期选项写入 AOF 时转换为 PEXPIREAT 或 PERSIST，没有选项时原样写入
+    pub fn aof_frame(&self) -> Option<RespFrame> {
+        let args = match self.ttl? {
+            GetExTtl::Expire(expire) => vec![
+                Bytes::from_static(b"pexpireat"),
+                self.key.clone(),
+                Bytes::from(expire_deadline(expire).to_string()),
+            ],
+            GetExTtl::Persist => vec![Bytes::from_static(b"persist"), self.key.clone()],
+        };
+        Some(bulk_array(args))
+    }
+}
 
 impl TryFrom<RespArray> for CommandGet {
     type Error = CommandError;
     fn try_from(value: RespArray) -> Result<Self, Self::Error> {
-        let args = valid_command(&value, &["GET"], 1)?;
-        match args[0] {
-            RespFrame::BulkString(BulkString { content: Some(key) }) => Ok(CommandGet {
-                key: String::from_utf8(key.to_vec())?,
-            }),
-            _ => Err(CommandError::InvalidArguments(
-                "GET command expect bulk string".to_string(),
-            )),
-        }
+        let args = command_args(&value)?;
+        Ok(CommandGet {
+            key: extract_bytes(args[0], "GET")?,
+        })
     }
 }
 impl TryFrom<RespArray> for CommandSet {
     type Error = CommandError;
     fn try_from(value: RespArray) -> Result<Self, Self::Error> {
-        let args = valid_command(&value, &["SET"], 2)?;
-        match (args[0], args[1]) {
-            (
-                RespFrame::BulkString(BulkString { content: Some(key) }),
-                RespFrame::BulkString(BulkString {
-                    content: Some(value),
-                }),
-            ) => Ok(CommandSet {
-                key: String::from_utf8(key.to_vec())?,
-                value: RespFrame::BulkString(BulkString {
-                    content: Some(value.to_vec()),
-                }),
-            }),
-            _ => Err(CommandError::InvalidArguments(
-                "SET command expect bulk string".to_string(),
-            )),
+        let args = command_args(&value)?;
+        let key = extract_bytes(args[0], "SET")?;
+        let value = extract_bytes(args[1], "SET")?;
+        let syntax_error = || CommandError::InvalidArguments("syntax error".to_string());
+        let mut condition = SetCondition::Always;
+        let mut expire = None;
+        let mut get = false;
+        let mut options = args[2..].iter();
+        while let Some(option) = options.next() {
+            let option = extract_string(option, "SET")?.to_ascii_uppercase();
+            match option.as_str() {
+                "NX" if condition == SetCondition::Always => condition = SetCondition::IfNotExists,
+                "XX" if condition == SetCondition::Always => condition = SetCondition::IfExists,
+                "GET" => get = true,
+                "KEEPTTL" if expire.is_none() => expire = Some(SetExpire::KeepTtl),
+                "EX" | "PX" | "EXAT" | "PXAT" if expire.is_none() => {
+                    let n = options.next().ok_or_else(syntax_error)?;
+                    expire = Some(parse_expire(&option, n, "set")?);
+                }
+                _ => return Err(syntax_error()),
+            }
         }
+        Ok(CommandSet {
+            key,
+            value,
+            condition,
+            expire,
+            get,
+        })
+    }
+}
+/// 解析 EX / PX / EXAT / PXAT 的参数，时间必须为正数
+fn parse_expire(option: &str, n: &RespFrame, name: &str) -> Result<SetExpire, CommandError> {
+    let n = extract_int(n, name)?;
+    // 秒需要换算成毫秒，提前排除溢出
+    let limit = if option.starts_with("EX") {
+        i64::MAX / 1000
+    } else {
+        i64::MAX
+    };
+    if n <= 0 || n > limit {
+        return Err(CommandError::InvalidArguments(format!(
+            "invalid expire time in '{}' command",
+            name
+        )));
+    }
+    let n = n as u64;
+    Ok(match option {
+        "EX" => SetExpire::Ex(n),
+        "PX" => SetExpire::Px(n),
+        "EXAT" => SetExpire::ExAt(n),
+        _ => SetExpire::PxAt(n),
+    })
+}
+
+/// MSET / MSETNX 的参数必须是成对的 key value
+fn parse_pairs(args: &[&RespFrame], name: &str) -> Result<Vec<(Bytes, Bytes)>, CommandError> {
+    if !args.len().is_multiple_of(2) {
+        return Err(CommandError::InvalidNumberOfArguments(format!(
+            "wrong number of arguments for '{}' command",
+            name.to_ascii_lowercase()
+        )));
+    }
+    args.chunks(2)
+        .map(|pair| Ok((extract_bytes(pair[0], name)?, extract_bytes(pair[1], name)?)))
+        .collect()
+}
+
+impl TryFrom<RespArray> for CommandAppend {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        let args = command_args(&value)?;
+        Ok(CommandAppend {
+            key: extract_bytes(args[0], "APPEND")?,
+            value: extract_bytes(args[1], "APPEND")?,
+        })
+    }
+}
+impl TryFrom<RespArray> for CommandStrLen {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        let args = command_args(&value)?;
+        Ok(CommandStrLen {
+            key: extract_bytes(args[0], "STRLEN")?,
+        })
+    }
+}
+impl TryFrom<RespArray> for CommandGetRange {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        let args = command_args(&value)?;
+        Ok(CommandGetRange {
+            key: extract_bytes(args[0], "GETRANGE")?,
+            start: extract_int(args[1], "GETRANGE")?,
+            end: extract_int(args[2], "GETRANGE")?,
+        })
+    }
+}
+impl TryFrom<RespArray> for CommandSetRange {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        let args = command_args(&value)?;
+        let key = extract_bytes(args[0], "SETRANGE")?;
+        let offset = extract_int(args[1], "SETRANGE")?;
+        let value = extract_bytes(args[2], "SETRANGE")?;
+        let offset = usize::try_from(offset)
+            .map_err(|_| CommandError::InvalidArguments("offset is out of range".to_string()))?;
+        // 写入空串时不会扩展字符串，不需要检查长度
+        if !value.is_empty() && offset.saturating_add(value.len()) > MAX_STRING_LEN {
+            return Err(CommandError::InvalidArguments(
+                "string exceeds maximum allowed size (proto-max-bulk-len)".to_string(),
+            ));
+        }
+        Ok(CommandSetRange { key, offset, value })
+    }
+}
+impl TryFrom<RespArray> for CommandGetSet {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        let args = command_args(&value)?;
+        Ok(CommandGetSet {
+            key: extract_bytes(args[0], "GETSET")?,
+            value: extract_bytes(args[1], "GETSET")?,
+        })
+    }
+}
+impl TryFrom<RespArray> for CommandGetDel {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        let args = command_args(&value)?;
+        Ok(CommandGetDel {
+            key: extract_bytes(args[0], "GETDEL")?,
+        })
+    }
+}
+impl TryFrom<RespArray> for CommandGetEx {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        let args = command_args(&value)?;
+        let key = extract_bytes(args[0], "GETEX")?;
+        let syntax_error = || CommandError::InvalidArguments("syntax error".to_string());
+        let mut ttl = None;
+        let mut options = args[1..].iter();
+        while let Some(option) = options.next() {
+            let option = extract_string(option, "GETEX")?.to_ascii_uppercase();
+            match option.as_str() {
+                "PERSIST" if ttl.is_none() => ttl = Some(GetExTtl::Persist),
+                "EX" | "PX" | "EXAT" | "PXAT" if ttl.is_none() => {
+                    let n = options.next().ok_or_else(syntax_error)?;
+                    ttl = Some(GetExTtl::Expire(parse_expire(&option, n, "getex")?));
+                }
+                _ => return Err(syntax_error()),
+            }
+        }
+        Ok(CommandGetEx { key, ttl })
+    }
+}
+impl TryFrom<RespArray> for CommandMSet {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        let args = command_args(&value)?;
+        Ok(CommandMSet {
+            pairs: parse_pairs(&args, "MSET")?,
+        })
+    }
+}
+impl TryFrom<RespArray> for CommandMGet {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        let args = command_args(&value)?;
+        Ok(CommandMGet {
+            keys: args
+                .iter()
+                .map(|arg| extract_bytes(arg, "MGET"))
+                .collect::<Result<_, _>>()?,
+        })
+    }
+}
+impl TryFrom<RespArray> for CommandMSetNx {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        let args = command_args(&value)?;
+        Ok(CommandMSetNx {
+            pairs: parse_pairs(&args, "MSETNX")?,
+        })
+    }
+}
+impl TryFrom<RespArray> for CommandSetNx {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        let args = command_args(&value)?;
+        Ok(CommandSetNx {
+            key: extract_bytes(args[0], "SETNX")?,
+            value: extract_bytes(args[1], "SETNX")?,
+        })
+    }
+}
+impl TryFrom<RespArray> for CommandIncr {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        let args = command_args(&value)?;
+        Ok(CommandIncr {
+            key: extract_bytes(args[0], "INCR")?,
+        })
+    }
+}
+impl TryFrom<RespArray> for CommandDecr {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        let args = command_args(&value)?;
+        Ok(CommandDecr {
+            key: extract_bytes(args[0], "DECR")?,
+        })
+    }
+}
+impl TryFrom<RespArray> for CommandIncrBy {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        let args = command_args(&value)?;
+        Ok(CommandIncrBy {
+            key: extract_bytes(args[0], "INCRBY")?,
+            increment: extract_int(args[1], "INCRBY")?,
+        })
+    }
+}
+impl TryFrom<RespArray> for CommandDecrBy {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        let args = command_args(&value)?;
+        let decrement = extract_int(args[1], "DECRBY")?;
+        if decrement == i64::MIN {
+            return Err(CommandError::InvalidArguments(
+                "decrement would overflow".to_string(),
+            ));
+        }
+        Ok(CommandDecrBy {
+            key: extract_bytes(args[0], "DECRBY")?,
+            decrement,
+        })
+    }
+}
+impl TryFrom<RespArray> for CommandIncrByFloat {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        let args = command_args(&value)?;
+        let increment = extract_string(args[1], "INCRBYFLOAT")?
+            .parse::<f64>()
+            .ok()
+            .filter(|increment| increment.is_finite())
+            .ok_or_else(|| CommandError::InvalidArguments("value is not a valid float".into()))?;
+        Ok(CommandIncrByFloat {
+            key: extract_bytes(args[0], "INCRBYFLOAT")?,
+            increment,
+        })
     }
 }
 #[cfg(test)]
@@ -69,13 +753,13 @@ mod tests {
     use bytes::BytesMut;
 
     use super::*;
-    use crate::resp::RespDecode;
+    use crate::resp::{BulkString, RespDecode};
     #[test]
     fn test_command_get_try_from() -> Result<(), CommandError> {
         let mut byte_get = BytesMut::from("*2\r\n$3\r\nget\r\n$5\r\nhello\r\n");
         let array = RespArray::decode(&mut byte_get)?;
         let cmd = CommandGet::try_from(array)?;
-        assert_eq!(cmd.key, "hello".to_string());
+        assert_eq!(cmd.key, "hello");
         Ok(())
     }
     #[test]
@@ -83,8 +767,8 @@ mod tests {
         let mut byte_set = BytesMut::from("*3\r\n$3\r\nset\r\n$5\r\nhello\r\n$5\r\nworld\r\n");
         let array = RespArray::decode(&mut byte_set)?;
         let cmd = CommandSet::try_from(array)?;
-        assert_eq!(cmd.key, "hello".to_string());
-        assert_eq!(cmd.value, "world".into());
+        assert_eq!(cmd.key, "hello");
+        assert_eq!(cmd.value, "world");
         Ok(())
     }
     #[test]
@@ -102,4 +786,307 @@ mod tests {
         assert_eq!(resp_get, "world".into());
         Ok(())
     }
+    #[test]
+    fn test_command_set_options_try_from() -> Result<(), CommandError> {
+        let cmd = CommandSet::try_from(RespArray::decode(&mut BytesMut::from(
+            "*6\r\n$3\r\nset\r\n$5\r\nhello\r\n$5\r\nworld\r\n$2\r\nnx\r\n$2\r\nPX\r\n$3\r\n100\r\n",
+        ))?)?;
+        assert_eq!(cmd.condition, SetCondition::IfNotExists);
+        assert_eq!(cmd.expire, Some(SetExpire::Px(100)));
+        assert!(!cmd.get);
+
+        // NX 与 XX 互斥
+        let ret = CommandSet::try_from(RespArray::from(vec![
+            "SET".into(),
+            "hello".into(),
+            "world".into(),
+            "NX".into(),
+            "XX".into(),
+        ]));
+        assert!(ret.is_err());
+        // 过期时间必须为正数
+        let ret = CommandSet::try_from(RespArray::from(vec![
+            "SET".into(),
+            "hello".into(),
+            "world".into(),
+            "EX".into(),
+            "0".into(),
+        ]));
+        assert!(ret.is_err());
+        Ok(())
+    }
+    #[test]
+    fn test_set_nx_xx_get_command() -> Result<(), CommandError> {
+        let backend = Backend::new();
+        let set = |args: &[&str]| -> Result<RespFrame, CommandError> {
+            let frames = args.iter().map(|arg| (*arg).into()).collect::<Vec<_>>();
+            Ok(CommandSet::try_from(RespArray::from(frames))?.execute(&backend))
+        };
+        assert_eq!(set(&["SET", "k", "v1", "XX"])?, RespNull.into());
+        assert_eq!(
+            set(&["SET", "k", "v1", "NX", "EX", "100"])?,
+            RESP_OK.clone()
+        );
+        assert_eq!(set(&["SET", "k", "v2", "NX"])?, RespNull.into());
+        assert_eq!(
+            set(&["SET", "k", "v2", "XX", "KEEPTTL", "GET"])?,
+            "v1".into()
+        );
+        assert!(backend.pttl(b"k") > 0);
+        assert_eq!(set(&["SET", "k", "v3", "GET"])?, "v2".into());
+        assert_eq!(backend.pttl(b"k"), -1);
+        Ok(())
+    }
+    #[test]
+    fn test_set_get_binary_safe() -> Result<(), CommandError> {
+        let backend = Backend::new();
+        let mut buf =
+            BytesMut::from(&b"*3\r\n$3\r\nset\r\n$3\r\n\xff\x00k\r\n$4\r\n\x80\r\n\x00\r\n"[..]);
+        let resp_set = CommandSet::try_from(RespArray::decode(&mut buf)?)?.execute(&backend);
+        assert_eq!(resp_set, RESP_OK.clone());
+        let mut buf = BytesMut::from(&b"*2\r\n$3\r\nget\r\n$3\r\n\xff\x00k\r\n"[..]);
+        let resp_get = CommandGet::try_from(RespArray::decode(&mut buf)?)?.execute(&backend);
+        assert_eq!(resp_get, BulkString::new(b"\x80\r\n\x00".to_vec()).into());
+        Ok(())
+    }
+    #[test]
+    fn test_binary_safe_edge_cases() -> Result<(), CommandError> {
+        let backend = Backend::new();
+        // 空 key 和空值都是合法的二进制串
+        assert_eq!(
+            run(&backend, b"*3\r\n$3\r\nset\r\n$0\r\n\r\n$0\r\n\r\n")?,
+            RESP_OK.clone()
+        );
+        assert_eq!(
+            run(&backend, b"*2\r\n$3\r\nget\r\n$0\r\n\r\n")?,
+            BulkString::new(b"".to_vec()).into()
+        );
+        // 非 UTF-8 的 field 和值在其他类型里同样原样保存
+        let hset = b"*4\r\n$4\r\nhset\r\n$2\r\nh\xfe\r\n$2\r\n\xff\x00\r\n$2\r\n\r\n\r\n";
+        assert_eq!(run(&backend, hset)?, RespInteger::new(1).into());
+        let hget = b"*3\r\n$4\r\nhget\r\n$2\r\nh\xfe\r\n$2\r\n\xff\x00\r\n";
+        assert_eq!(
+            run(&backend, hget)?,
+            BulkString::new(b"\r\n".to_vec()).into()
+        );
+        let rpush = b"*3\r\n$5\r\nrpush\r\n$1\r\n\x80\r\n$3\r\n\x00\x01\x02\r\n";
+        assert_eq!(run(&backend, rpush)?, RespInteger::new(1).into());
+        let lpop = b"*2\r\n$4\r\nlpop\r\n$1\r\n\x80\r\n";
+        assert_eq!(
+            run(&backend, lpop)?,
+            BulkString::new(b"\x00\x01\x02".to_vec()).into()
+        );
+
+        // key 和值必须是非 null 的 bulk string
+        assert!(run(&backend, b"*3\r\n$3\r\nset\r\n$-1\r\n$1\r\nv\r\n").is_err());
+        assert!(run(&backend, b"*3\r\n$3\r\nset\r\n$1\r\nk\r\n:1\r\n").is_err());
+        assert!(run(&backend, b"*2\r\n$3\r\nget\r\n+k\r\n").is_err());
+        assert_eq!(backend.get(b"k"), Ok(None));
+        Ok(())
+    }
+
+    /// 解码 RESP 请求并通过命令表执行
+    fn run(backend: &Backend, fixture: &[u8]) -> Result<RespFrame, CommandError> {
+        let array = RespArray::decode(&mut BytesMut::from(fixture))?;
+        Ok(crate::cmd::Command::try_from(array)?.execute(backend))
+    }
+    #[test]
+    fn test_append_strlen_command() -> Result<(), CommandError> {
+        let backend = Backend::new();
+        let append = b"*3\r\n$6\r\nappend\r\n$5\r\nhello\r\n$5\r\nworld\r\n";
+        assert_eq!(run(&backend, append)?, RespInteger::new(5).into());
+        assert_eq!(run(&backend, append)?, RespInteger::new(10).into());
+        let strlen = b"*2\r\n$6\r\nstrlen\r\n$5\r\nhello\r\n";
+        assert_eq!(run(&backend, strlen)?, RespInteger::new(10).into());
+        let strlen = b"*2\r\n$6\r\nstrlen\r\n$7\r\nmissing\r\n";
+        assert_eq!(run(&backend, strlen)?, RespInteger::new(0).into());
+        assert_eq!(backend.get(b"hello"), Ok(Some("worldworld".into())));
+        Ok(())
+    }
+    #[test]
+    fn test_getrange_command() -> Result<(), CommandError> {
+        let backend = Backend::new();
+        backend.set("hello".into(), "This is a string".into());
+        let getrange = |start: &str, end: &str| {
+            let fixture = format!(
+                "*4\r\n$8\r\ngetrange\r\n$5\r\nhello\r\n${}\r\n{}\r\n${}\r\n{}\r\n",
+                start.len(),
+                start,
+                end.len(),
+                end
+            );
+            run(&backend, fixture.as_bytes())
+        };
+        assert_eq!(getrange("0", "3")?, "This".into());
+        assert_eq!(getrange("-3", "-1")?, "ing".into());
+        assert_eq!(getrange("0", "-1")?, "This is a string".into());
+        assert_eq!(getrange("10", "100")?, "string".into());
+        // 换算后 end 小于 0 时按 0 处理
+        assert_eq!(getrange("0", "-100")?, "T".into());
+        assert_eq!(getrange("-1", "-5")?, "".into());
+        assert_eq!(getrange("5", "3")?, "".into());
+        assert_eq!(getrange("100", "200")?, "".into());
+        let missing = b"*4\r\n$8\r\ngetrange\r\n$7\r\nmissing\r\n$1\r\n0\r\n$2\r\n-1\r\n";
+        assert_eq!(run(&backend, missing)?, "".into());
+        Ok(())
+    }
+    #[test]
+    fn test_setrange_command() -> Result<(), CommandError> {
+        let backend = Backend::new();
+        backend.set("hello".into(), "Hello World".into());
+        let setrange = b"*4\r\n$8\r\nsetrange\r\n$5\r\nhello\r\n$1\r\n6\r\n$5\r\nRedis\r\n";
+        assert_eq!(run(&backend, setrange)?, RespInteger::new(11).into());
+        assert_eq!(backend.get(b"hello"), Ok(Some("Hello Redis".into())));
+        // 不存在的 key 用 0 字节补齐
+        let setrange = b"*4\r\n$8\r\nsetrange\r\n$3\r\npad\r\n$1\r\n3\r\n$2\r\nab\r\n";
+        assert_eq!(run(&backend, setrange)?, RespInteger::new(5).into());
+        assert_eq!(backend.get(b"pad"), Ok(Some(b"\0\0\0ab".as_slice().into())));
+        // 写入空串不创建 key
+        let setrange = b"*4\r\n$8\r\nsetrange\r\n$5\r\nempty\r\n$2\r\n10\r\n$0\r\n\r\n";
+        assert_eq!(run(&backend, setrange)?, RespInteger::new(0).into());
+        assert!(!backend.contains_key(b"empty"));
+        let setrange = b"*4\r\n$8\r\nsetrange\r\n$5\r\nhello\r\n$2\r\n-1\r\n$1\r\nx\r\n";
+        assert!(run(&backend, setrange).is_err());
+        let setrange = b"*4\r\n$8\r\nsetrange\r\n$5\r\nhello\r\n$9\r\n536870912\r\n$1\r\nx\r\n";
+        assert!(run(&backend, setrange).is_err());
+        Ok(())
+    }
+    #[test]
+    fn test_getset_getdel_getex_command() -> Result<(), CommandError> {
+        let backend = Backend::new();
+        let getset = b"*3\r\n$6\r\ngetset\r\n$5\r\nhello\r\n$5\r\nworld\r\n";
+        assert_eq!(run(&backend, getset)?, RespNull.into());
+        backend.expire_at(&"hello".into(), now_ms() + 100_000);
+        let getset = b"*3\r\n$6\r\ngetset\r\n$5\r\nhello\r\n$3\r\nnew\r\n";
+        assert_eq!(run(&backend, getset)?, "world".into());
+        assert_eq!(backend.pttl(b"hello"), -1);
+
+        let getex = b"*4\r\n$5\r\ngetex\r\n$5\r\nhello\r\n$2\r\nEX\r\n$3\r\n100\r\n";
+        assert_eq!(run(&backend, getex)?, "new".into());
+        assert!(backend.pttl(b"hello") > 0);
+        let getex = b"*3\r\n$5\r\ngetex\r\n$5\r\nhello\r\n$7\r\nPERSIST\r\n";
+        assert_eq!(run(&backend, getex)?, "new".into());
+        assert_eq!(backend.pttl(b"hello"), -1);
+        let getex = b"*5\r\n$5\r\ngetex\r\n$5\r\nhello\r\n$7\r\nPERSIST\r\n$2\r\nEX\r\n$1\r\n1\r\n";
+        assert!(run(&backend, getex).is_err());
+
+        let getdel = b"*2\r\n$6\r\ngetdel\r\n$5\r\nhello\r\n";
+        assert_eq!(run(&backend, getdel)?, "new".into());
+        assert_eq!(run(&backend, getdel)?, RespNull.into());
+        Ok(())
+    }
+    #[test]
+    fn test_mset_mget_msetnx_setnx_command() -> Result<(), CommandError> {
+        let backend = Backend::new();
+        let mset = b"*5\r\n$4\r\nmset\r\n$1\r\na\r\n$1\r\n1\r\n$1\r\nb\r\n$1\r\n2\r\n";
+        assert_eq!(run(&backend, mset)?, RESP_OK.clone());
+        backend
+            .hset("h".into(), vec![("f".into(), "v".into())])
+            .unwrap();
+        let mget = b"*5\r\n$4\r\nmget\r\n$1\r\na\r\n$1\r\nh\r\n$1\r\nc\r\n$1\r\nb\r\n";
+        assert_eq!(
+            run(&backend, mget)?,
+            RespArray::from_vec(vec![
+                "1".into(),
+                RespNull.into(),
+                RespNull.into(),
+                "2".into()
+            ])
+            .into()
+        );
+        // 任意一个 key 已存在时都不写入
+        let msetnx = b"*5\r\n$6\r\nmsetnx\r\n$1\r\nc\r\n$1\r\n3\r\n$1\r\na\r\n$1\r\n4\r\n";
+        assert_eq!(run(&backend, msetnx)?, RespInteger::new(0).into());
+        assert!(!backend.contains_key(b"c"));
+        let msetnx = b"*5\r\n$6\r\nmsetnx\r\n$1\r\nc\r\n$1\r\n3\r\n$1\r\nd\r\n$1\r\n4\r\n";
+        assert_eq!(run(&backend, msetnx)?, RespInteger::new(1).into());
+        let mset = b"*4\r\n$4\r\nmset\r\n$1\r\na\r\n$1\r\n1\r\n$1\r\nb\r\n";
+        assert!(run(&backend, mset).is_err());
+
+        let setnx = b"*3\r\n$5\r\nsetnx\r\n$1\r\na\r\n$1\r\n5\r\n";
+        assert_eq!(run(&backend, setnx)?, RespInteger::new(0).into());
+        let setnx = b"*3\r\n$5\r\nsetnx\r\n$1\r\ne\r\n$1\r\n5\r\n";
+        assert_eq!(run(&backend, setnx)?, RespInteger::new(1).into());
+        assert_eq!(backend.get(b"e"), Ok(Some("5".into())));
+        Ok(())
+    }
+    #[test]
+    fn test_incr_decr_command() -> Result<(), CommandError> {
+        let backend = Backend::new();
+        let incr = b"*2\r\n$4\r\nincr\r\n$7\r\ncounter\r\n";
+        assert_eq!(run(&backend, incr)?, RespInteger::new(1).into());
+        let incrby = b"*3\r\n$6\r\nincrby\r\n$7\r\ncounter\r\n$2\r\n10\r\n";
+        assert_eq!(run(&backend, incrby)?, RespInteger::new(11).into());
+        let decrby = b"*3\r\n$6\r\ndecrby\r\n$7\r\ncounter\r\n$2\r\n20\r\n";
+        assert_eq!(run(&backend, decrby)?, RespInteger::new(-9).into());
+        let decr = b"*2\r\n$4\r\ndecr\r\n$7\r\ncounter\r\n";
+        assert_eq!(run(&backend, decr)?, RespInteger::new(-10).into());
+        assert_eq!(backend.get(b"counter"), Ok(Some("-10".into())));
+        // 自增保留原有的过期时间
+        backend.expire_at(&"counter".into(), now_ms() + 100_000);
+        run(&backend, incr)?;
+        assert!(backend.pttl(b"counter") > 0);
+
+        backend.set("text".into(), "abc".into());
+        let incr = b"*2\r\n$4\r\nincr\r\n$4\r\ntext\r\n";
+        assert_eq!(
+            run(&backend, incr)?,
+            SimpleError::new("ERR value is not an integer or out of range").into()
+        );
+        backend.set("max".into(), i64::MAX.to_string().into());
+        let incr = b"*2\r\n$4\r\nincr\r\n$3\r\nmax\r\n";
+        assert_eq!(
+            run(&backend, incr)?,
+            SimpleError::new("ERR increment or decrement would overflow").into()
+        );
+        let decrby = b"*3\r\n$6\r\ndecrby\r\n$7\r\ncounter\r\n$20\r\n-9223372036854775808\r\n";
+        let ret = run(&backend, decrby);
+        assert!(matches!(ret, Err(e) if e.to_string() == "decrement would overflow"));
+        // 已存在的空字符串、'+' 和前导 0 都不是合法整数
+        for value in ["", "+5", "007", "-0", " 1"] {
+            backend.set("text".into(), Bytes::copy_from_slice(value.as_bytes()));
+            let incr = b"*2\r\n$4\r\nincr\r\n$4\r\ntext\r\n";
+            assert_eq!(
+                run(&backend, incr)?,
+                SimpleError::new("ERR value is not an integer or out of range").into()
+            );
+        }
+        let incrby = b"*3\r\n$6\r\nincrby\r\n$7\r\ncounter\r\n$3\r\n1.5\r\n";
+        assert!(run(&backend, incrby).is_err());
+        Ok(())
+    }
+    #[test]
+    fn test_incrbyfloat_command() -> Result<(), CommandError> {
+        let backend = Backend::new();
+        backend.set("f".into(), "10.50".into());
+        let incrbyfloat = b"*3\r\n$11\r\nincrbyfloat\r\n$1\r\nf\r\n$3\r\n0.1\r\n";
+        let reply = run(&backend, incrbyfloat)?;
+        assert_eq!(reply, RespDouble::new(10.6).into());
+        assert_eq!(reply.into_resp2(), "10.6".into());
+        assert_eq!(backend.get(b"f"), Ok(Some("10.6".into())));
+        let incrbyfloat = b"*3\r\n$11\r\nincrbyfloat\r\n$7\r\nmissing\r\n$4\r\n-2.5\r\n";
+        assert_eq!(run(&backend, incrbyfloat)?, RespDouble::new(-2.5).into());
+
+        backend.set("text".into(), "abc".into());
+        let incrbyfloat = b"*3\r\n$11\r\nincrbyfloat\r\n$4\r\ntext\r\n$1\r\n1\r\n";
+        assert_eq!(
+            run(&backend, incrbyfloat)?,
+            SimpleError::new("ERR value is not a valid float").into()
+        );
+        backend.set("big".into(), f64::MAX.to_string().into());
+        let incrbyfloat = b"*3\r\n$11\r\nincrbyfloat\r\n$3\r\nbig\r\n$5\r\n1e308\r\n";
+        assert_eq!(
+            run(&backend, incrbyfloat)?,
+            SimpleError::new("ERR increment would produce NaN or Infinity").into()
+        );
+        backend.set("empty".into(), "".into());
+        let incrbyfloat = b"*3\r\n$11\r\nincrbyfloat\r\n$5\r\nempty\r\n$1\r\n1\r\n";
+        assert_eq!(
+            run(&backend, incrbyfloat)?,
+            SimpleError::new("ERR value is not a valid float").into()
+        );
+        let incrbyfloat = b"*3\r\n$11\r\nincrbyfloat\r\n$1\r\nf\r\n$3\r\nnan\r\n";
+        assert!(run(&backend, incrbyfloat).is_err());
+        Ok(())
+    }
 }
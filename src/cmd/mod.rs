@@ -1,11 +1,68 @@
+mod bitmap;
+mod connection;
+mod expire;
+mod generic;
 mod hmap;
+mod list;
 mod map;
-use std::{convert::TryFrom, sync::LazyLock};
+mod scan;
+mod server;
+mod set;
+mod table;
+mod zset;
+use std::{
+    collections::hash_map::RandomState,
+    convert::TryFrom,
+    hash::{BuildHasher, Hasher},
+    path::Path,
+    sync::LazyLock,
+};
 
+pub use bitmap::{
+    BitFieldOp, BitFieldOverflow, BitFieldType, BitOperation, BitRange, BitUnit, CommandBitCount,
+    CommandBitField, CommandBitOp, CommandBitPos, CommandGetBit, CommandSetBit,
+};
+use bytes::Bytes;
+pub use connection::CommandHello;
+pub use expire::{
+    CommandExpire, CommandExpireAt, CommandPExpire, CommandPExpireAt, CommandPTtl, CommandPersist,
+    CommandTtl,
+};
+pub use generic::{
+    CommandDbSize, CommandDel, CommandExists, CommandKeys, CommandRandomKey, CommandRename,
+    CommandType, CommandUnlink,
+};
+pub use hmap::{
+    CommandHDel, CommandHExists, CommandHIncrBy, CommandHIncrByFloat, CommandHKeys, CommandHLen,
+    CommandHMGet, CommandHRandField, CommandHSetNx, CommandHStrLen, CommandHVals,
+};
+pub use list::{
+    CommandBLMove, CommandBLPop, CommandBRPop, CommandLIndex, CommandLLen, CommandLMove,
+    CommandLPop, CommandLPush, CommandLRange, CommandLRem, CommandLSet, CommandLTrim, CommandRPop,
+    CommandRPush,
+};
+pub use map::{
+    CommandAppend, CommandDecr, CommandDecrBy, CommandGetDel, CommandGetEx, CommandGetRange,
+    CommandGetSet, CommandIncr, CommandIncrBy, CommandIncrByFloat, CommandMGet, CommandMSet,
+    CommandMSetNx, CommandSetNx, CommandSetRange, CommandStrLen, GetExTtl,
+};
+pub use scan::{CommandHScan, CommandSScan, CommandScan, CommandZScan, ScanOptions};
+pub use server::{CommandBgRewriteAof, CommandBgSave, CommandCommand, CommandSave};
+pub use set::{
+    CommandSAdd, CommandSCard, CommandSIsMember, CommandSMembers, CommandSRem, CommandSetOperation,
+    CommandSetOperationStore, SetOperation,
+};
+pub use table::{
+    COMMAND_TABLE, CommandFlag, CommandGroup, CommandSpec, lookup_command, resolve_command,
+};
 use thiserror::Error;
+pub use zset::{
+    CommandZAdd, CommandZCard, CommandZIncrBy, CommandZRange, CommandZRank, CommandZRem,
+    CommandZScore, ZAddFlags, ZRangeBy,
+};
 
 use crate::{
-    backend::Backend,
+    backend::{AofError, Backend, ClientHandle, SetCondition},
     resp::{BulkString, RespArray, RespError, RespFrame, SimpleError, SimpleString},
 };
 
@@ -14,13 +71,13 @@ pub static RESP_OK: LazyLock<RespFrame> =
 
 #[derive(Debug, Error)]
 pub enum CommandError {
-    #[error("invalid command: {0}")]
+    #[error("{0}")]
     InvalidCommand(String),
-    #[error("invalid arguments: {0}")]
+    #[error("{0}")]
     InvalidArguments(String),
     #[error("resp error: {0}")]
     RespErr(#[from] RespError),
-    #[error("invalid number of arguments: {0}")]
+    #[error("{0}")]
     InvalidNumberOfArguments(String),
     #[error("invalid utf8: {0}")]
     InvalidUtf8(#[from] std::string::FromUtf8Error),
@@ -34,19 +91,94 @@ pub enum Command {
     Ping,
     Set(CommandSet),
     Get(CommandGet),
+    Append(CommandAppend),
+    StrLen(CommandStrLen),
+    GetRange(CommandGetRange),
+    SetRange(CommandSetRange),
+    GetSet(CommandGetSet),
+    GetDel(CommandGetDel),
+    GetEx(CommandGetEx),
+    MSet(CommandMSet),
+    MGet(CommandMGet),
+    MSetNx(CommandMSetNx),
+    SetNx(CommandSetNx),
+    Incr(CommandIncr),
+    Decr(CommandDecr),
+    IncrBy(CommandIncrBy),
+    DecrBy(CommandDecrBy),
+    IncrByFloat(CommandIncrByFloat),
+    SetBit(CommandSetBit),
+    GetBit(CommandGetBit),
+    BitCount(CommandBitCount),
+    BitPos(CommandBitPos),
+    BitOp(CommandBitOp),
+    BitField(CommandBitField),
     HGet(CommandHGet),
     HSet(CommandHSet),
     HGetAll(CommandHGetAll),
-    Unrecognized(Unrecognized),
-}
-#[derive(Debug)]
-pub struct Unrecognized {
-    command: String,
-}
-impl CommandExecutor for Unrecognized {
-    fn execute(self, _backend: &Backend) -> RespFrame {
-        SimpleError::new(format!("unrecognized command: {}", self.command)).into()
-    }
+    HSetNx(CommandHSetNx),
+    HMGet(CommandHMGet),
+    HDel(CommandHDel),
+    HExists(CommandHExists),
+    HLen(CommandHLen),
+    HKeys(CommandHKeys),
+    HVals(CommandHVals),
+    HIncrBy(CommandHIncrBy),
+    HIncrByFloat(CommandHIncrByFloat),
+    HStrLen(CommandHStrLen),
+    HRandField(CommandHRandField),
+    Expire(CommandExpire),
+    PExpire(CommandPExpire),
+    ExpireAt(CommandExpireAt),
+    PExpireAt(CommandPExpireAt),
+    Ttl(CommandTtl),
+    PTtl(CommandPTtl),
+    Persist(CommandPersist),
+    LPush(CommandLPush),
+    RPush(CommandRPush),
+    LPop(CommandLPop),
+    RPop(CommandRPop),
+    LRange(CommandLRange),
+    LLen(CommandLLen),
+    LIndex(CommandLIndex),
+    LSet(CommandLSet),
+    LRem(CommandLRem),
+    LTrim(CommandLTrim),
+    LMove(CommandLMove),
+    BLPop(CommandBLPop),
+    BRPop(CommandBRPop),
+    BLMove(CommandBLMove),
+    SAdd(CommandSAdd),
+    SRem(CommandSRem),
+    SMembers(CommandSMembers),
+    SIsMember(CommandSIsMember),
+    SCard(CommandSCard),
+    SetOperation(CommandSetOperation),
+    SetOperationStore(CommandSetOperationStore),
+    ZAdd(CommandZAdd),
+    ZRange(CommandZRange),
+    ZRank(CommandZRank),
+    ZIncrBy(CommandZIncrBy),
+    ZRem(CommandZRem),
+    ZScore(CommandZScore),
+    ZCard(CommandZCard),
+    Type(CommandType),
+    Del(CommandDel),
+    Unlink(CommandUnlink),
+    Exists(CommandExists),
+    Rename(CommandRename),
+    Keys(CommandKeys),
+    RandomKey(CommandRandomKey),
+    DbSize(CommandDbSize),
+    Scan(CommandScan),
+    HScan(CommandHScan),
+    SScan(CommandSScan),
+    ZScan(CommandZScan),
+    Save(CommandSave),
+    BgSave(CommandBgSave),
+    BgRewriteAof(CommandBgRewriteAof),
+    Hello(CommandHello),
+    Command(CommandCommand),
 }
 impl CommandExecutor for Command {
     fn execute(self, backend: &Backend) -> RespFrame {
@@ -54,134 +186,336 @@ impl CommandExecutor for Command {
             Command::Ping => RespFrame::SimpleString(SimpleString::from("PONG")),
             Command::Set(cmd) => cmd.execute(backend),
             Command::Get(cmd) => cmd.execute(backend),
+            Command::Append(cmd) => cmd.execute(backend),
+            Command::StrLen(cmd) => cmd.execute(backend),
+            Command::GetRange(cmd) => cmd.execute(backend),
+            Command::SetRange(cmd) => cmd.execute(backend),
+            Command::GetSet(cmd) => cmd.execute(backend),
+            Command::GetDel(cmd) => cmd.execute(backend),
+            Command::GetEx(cmd) => cmd.execute(backend),
+            Command::MSet(cmd) => cmd.execute(backend),
+            Command::MGet(cmd) => cmd.execute(backend),
+            Command::MSetNx(cmd) => cmd.execute(backend),
+            Command::SetNx(cmd) => cmd.execute(backend),
+            Command::Incr(cmd) => cmd.execute(backend),
+            Command::Decr(cmd) => cmd.execute(backend),
+            Command::IncrBy(cmd) => cmd.execute(backend),
+            Command::DecrBy(cmd) => cmd.execute(backend),
+            Command::IncrByFloat(cmd) => cmd.execute(backend),
+            Command::SetBit(cmd) => cmd.execute(backend),
+            Command::GetBit(cmd) => cmd.execute(backend),
+            Command::BitCount(cmd) => cmd.execute(backend),
+            Command::BitPos(cmd) => cmd.execute(backend),
+            Command::BitOp(cmd) => cmd.execute(backend),
+            Command::BitField(cmd) => cmd.execute(backend),
             Command::HGet(cmd) => cmd.execute(backend),
             Command::HSet(cmd) => cmd.execute(backend),
             Command::HGetAll(cmd) => cmd.execute(backend),
-            Command::Unrecognized(unrecognized) => unrecognized.execute(backend),
+            Command::HSetNx(cmd) => cmd.execute(backend),
+            Command::HMGet(cmd) => cmd.execute(backend),
+            Command::HDel(cmd) => cmd.execute(backend),
+            Command::HExists(cmd) => cmd.execute(backend),
+            Command::HLen(cmd) => cmd.execute(backend),
+            Command::HKeys(cmd) => cmd.execute(backend),
+            Command::HVals(cmd) => cmd.execute(backend),
+            Command::HIncrBy(cmd) => cmd.execute(backend),
+            Command::HIncrByFloat(cmd) => cmd.execute(backend),
+            Command::HStrLen(cmd) => cmd.execute(backend),
+            Command::HRandField(cmd) => cmd.execute(backend),
+            Command::Expire(cmd) => cmd.execute(backend),
+            Command::PExpire(cmd) => cmd.execute(backend),
+            Command::ExpireAt(cmd) => cmd.execute(backend),
+            Command::PExpireAt(cmd) => cmd.execute(backend),
+            Command::Ttl(cmd) => cmd.execute(backend),
+            Command::PTtl(cmd) => cmd.execute(backend),
+            Command::Persist(cmd) => cmd.execute(backend),
+            Command::LPush(cmd) => cmd.execute(backend),
+            Command::RPush(cmd) => cmd.execute(backend),
+            Command::LPop(cmd) => cmd.execute(backend),
+            Command::RPop(cmd) => cmd.execute(backend),
+            Command::LRange(cmd) => cmd.execute(backend),
+            Command::LLen(cmd) => cmd.execute(backend),
+            Command::LIndex(cmd) => cmd.execute(backend),
+            Command::LSet(cmd) => cmd.execute(backend),
+            Command::LRem(cmd) => cmd.execute(backend),
+            Command::LTrim(cmd) => cmd.execute(backend),
+            Command::LMove(cmd) => cmd.execute(backend),
+            Command::BLPop(cmd) => cmd.execute(backend),
+            Command::BRPop(cmd) => cmd.execute(backend),
+            Command::BLMove(cmd) => cmd.execute(backend),
+            Command::SAdd(cmd) => cmd.execute(backend),
+            Command::SRem(cmd) => cmd.execute(backend),
+            Command::SMembers(cmd) => cmd.execute(backend),
+            Command::SIsMember(cmd) => cmd.execute(backend),
+            Command::SCard(cmd) => cmd.execute(backend),
+            Command::SetOperation(cmd) => cmd.execute(backend),
+            Command::SetOperationStore(cmd) => cmd.execute(backend),
+            Command::ZAdd(cmd) => cmd.execute(backend),
+            Command::ZRange(cmd) => cmd.execute(backend),
+            Command::ZRank(cmd) => cmd.execute(backend),
+            Command::ZIncrBy(cmd) => cmd.execute(backend),
+            Command::ZRem(cmd) => cmd.execute(backend),
+            Command::ZScore(cmd) => cmd.execute(backend),
+            Command::ZCard(cmd) => cmd.execute(backend),
+            Command::Type(cmd) => cmd.execute(backend),
+            Command::Del(cmd) => cmd.execute(backend),
+            Command::Unlink(cmd) => cmd.execute(backend),
+            Command::Exists(cmd) => cmd.execute(backend),
+            Command::Rename(cmd) => cmd.execute(backend),
+            Command::Keys(cmd) => cmd.execute(backend),
+            Command::RandomKey(cmd) => cmd.execute(backend),
+            Command::DbSize(cmd) => cmd.execute(backend),
+            Command::Scan(cmd) => cmd.execute(backend),
+            Command::HScan(cmd) => cmd.execute(backend),
+            Command::SScan(cmd) => cmd.execute(backend),
+            Command::ZScan(cmd) => cmd.execute(backend),
+            Command::Save(cmd) => cmd.execute(backend),
+            Command::BgSave(cmd) => cmd.execute(backend),
+            Command::BgRewriteAof(cmd) => cmd.execute(backend),
+            Command::Command(cmd) => cmd.execute(backend),
+            // 没有连接（如 AOF 重放）时无法协商协议
+            Command::Hello(_) => SimpleError::new("ERR HELLO requires a client connection").into(),
+        }
+    }
+}
+impl Command {
+    /// 解析并执行一个请求；开启 AOF 时写命令执行后按顺序追加到 AOF，失败或没有修改的不追加
+    /// 阻塞命令会挂起当前连接直到有数据或超时，它们实际执行的弹出和写入由 backend 追加
+    pub async fn execute_request(
+        frame: RespFrame,
+        backend: &Backend,
+        client: &ClientHandle,
+    ) -> Result<RespFrame, CommandError> {
+        let request = backend.aof_enabled().then(|| frame.clone());
+        let (spec, command) = parse_request(frame)?;
+        let request = request.filter(|_| spec.is_write());
+        Ok(match command {
+            Command::BLPop(cmd) => cmd.execute_blocking(backend).await,
+            Command::BRPop(cmd) => cmd.execute_blocking(backend).await,
+            Command::BLMove(cmd) => cmd.execute_blocking(backend).await,
+            Command::Hello(cmd) => cmd.execute_for_client(client),
+            // 快照需要独占键空间
+            cmd @ (Command::Save(_) | Command::BgSave(_) | Command::BgRewriteAof(_)) => {
+                cmd.execute(backend)
+            }
+            cmd => {
+                // 跨多个 key 的写入需要独占键空间，其他命令看不到只写了一部分的状态
+                let (_shared, _exclusive) = match cmd {
+                    Command::MSet(_)
+                    | Command::MSetNx(_)
+                    | Command::LMove(_)
+                    | Command::BitOp(_)
+                    | Command::SetOperationStore(_)
+                    | Command::Rename(_) => (None, Some(backend.lock_exclusive())),
+                    _ => (Some(backend.lock_shared()), None),
+                };
+                let Some(request) = request else {
+                    return Ok(cmd.execute(backend));
+                };
+                let _order = backend.aof_order();
+                let cmd = cmd.absolute();
+                let aof = cmd.aof_frame().unwrap_or(request);
+                // 执行期间修改次数不变说明命令没有修改键空间；唤醒阻塞客户端产生的弹出暂存到命令之后
+                let changes = backend.changes();
+                backend.stage_propagation();
+                let ret = cmd.execute(backend);
+                let changed =
+                    backend.changes() != changes && !matches!(ret, RespFrame::SimpleError(_));
+                backend.commit_propagation(changed.then_some(&aof));
+                ret
+            }
+        })
+    }
+    /// 相对过期时间换算为绝对时间，执行和写入 AOF 使用同一个过期时间
+    fn absolute(self) -> Self {
+        match self {
+            Command::Set(cmd) => Command::Set(cmd.absolute()),
+            Command::GetEx(cmd) => Command::GetEx(cmd.absolute()),
+            Command::Expire(cmd) => Command::PExpireAt(cmd.absolute()),
+            Command::PExpire(cmd) => Command::PExpireAt(cmd.absolute()),
+            cmd => cmd,
+        }
+    }
+    /// 写入 AOF 时需要改写的命令，需要先经过 absolute 换算；None 表示原样写入
+    fn aof_frame(&self) -> Option<RespFrame> {
+        match self {
+            Command::Set(cmd) => cmd.aof_frame(),
+            Command::GetEx(cmd) => cmd.aof_frame(),
+            Command::PExpireAt(cmd) => Some(cmd.aof_frame()),
+            _ => None,
         }
     }
 }
 #[derive(Debug)]
 #[allow(dead_code)]
 pub struct CommandGet {
-    key: String,
+    key: Bytes,
 }
 #[derive(Debug)]
 #[allow(dead_code)]
 pub struct CommandSet {
-    key: String,
-    value: RespFrame,
+    key: Bytes,
+    value: Bytes,
+    condition: SetCondition,
+    expire: Option<SetExpire>,
+    get: bool,
+}
+/// SET 的过期选项
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SetExpire {
+    /// EX seconds
+    Ex(u64),
+    /// PX milliseconds
+    Px(u64),
+    /// EXAT unix-time-seconds
+    ExAt(u64),
+    /// PXAT unix-time-milliseconds
+    PxAt(u64),
+    KeepTtl,
 }
 #[allow(dead_code)]
 #[derive(Debug)]
 pub struct CommandHGet {
-    key: String,
-    field: String,
+    key: Bytes,
+    field: Bytes,
 }
 #[derive(Debug)]
 pub struct CommandHGetAll {
     #[allow(dead_code)]
-    key: String,
+    key: Bytes,
 }
 
 #[derive(Debug)]
 #[allow(dead_code)]
 pub struct CommandHSet {
-    key: String,
-    field: String,
-    value: RespFrame,
-}
-
-pub fn valid_command<'a>(
-    value: &'a RespArray,
-    name_slice: &[&'static str],
-    n_args: usize,
-) -> Result<Vec<&'a RespFrame>, CommandError> {
-    if let Some(ref elements) = value.elements {
-        if elements.len() != n_args + 1 {
-            return Err(CommandError::InvalidNumberOfArguments(format!(
-                "{} command expects {} arguments, got {}",
-                name_slice.join(" "),
-                n_args,
-                elements.len()
-            )));
-        }
-        for (i, name) in name_slice.iter().enumerate() {
-            match elements[i] {
-                RespFrame::BulkString(BulkString {
-                    content: Some(ref bytes),
-                }) => {
-                    if bytes.to_ascii_lowercase() != name.to_ascii_lowercase().as_bytes() {
-                        return Err(CommandError::InvalidArguments(format!(
-                            "{} command must have the {} argument: expect {:?}, but got {:?}",
-                            name_slice.join(" "),
-                            i,
-                            name,
-                            bytes
-                        )));
-                    }
-                }
-                _ => {
-                    return Err(CommandError::InvalidArguments(format!(
-                        "{} command must have a bulk string as the {} argument: expect {:?} find, but got {:?}",
-                        name_slice.join(" "),
-                        i,
-                        name,
-                        elements[i]
-                    )));
-                }
-            }
+    key: Bytes,
+    pairs: Vec<(Bytes, Bytes)>,
+}
+
+/// 按命令表校验请求，返回命令名之后的参数
+pub fn command_args(value: &RespArray) -> Result<Vec<&RespFrame>, CommandError> {
+    Ok(resolve_command(value)?.1)
+}
+
+/// 解析请求，同时返回命令表中的对应项
+fn parse_request(frame: RespFrame) -> Result<(&'static CommandSpec, Command), CommandError> {
+    match frame {
+        RespFrame::Array(array) => {
+            let (spec, _) = resolve_command(&array)?;
+            Ok((spec, spec.parse(array)?))
         }
-        Ok(elements.iter().skip(name_slice.len()).collect())
+        _ => Err(CommandError::InvalidCommand(
+            "invalid command array".to_string(),
+        )),
+    }
+}
+
+/// 启动时重放 AOF，文件不存在时返回 None，否则返回重放的命令条数
+pub fn load_aof(backend: &Backend, path: &Path, repair: bool) -> Result<Option<usize>, AofError> {
+    backend.load_aof(path, repair, |array| {
+        Command::try_from(array)
+            .map(|command| {
+                command.execute(backend);
+            })
+            .map_err(|e| e.to_string())
+    })
+}
+
+/// 提取 bulk string 参数
+pub fn extract_string(frame: &RespFrame, name: &str) -> Result<String, CommandError> {
+    match frame {
+        RespFrame::BulkString(BulkString {
+            content: Some(bytes),
+        }) => Ok(String::from_utf8(bytes.to_vec())?),
+        _ => Err(CommandError::InvalidArguments(format!(
+            "{} command expect bulk string",
+            name
+        ))),
+    }
+}
+
+/// 提取 bulk string 参数的原始字节，key 和 value 都按二进制保存
+/// 不拷贝；写命令的参数在 CommandSpec::parse 中已经与读缓冲区分离
+pub fn extract_bytes(frame: &RespFrame, name: &str) -> Result<Bytes, CommandError> {
+    match frame {
+        RespFrame::BulkString(BulkString {
+            content: Some(bytes),
+        }) => Ok(bytes.clone()),
+        _ => Err(CommandError::InvalidArguments(format!(
+            "{} command expect bulk string",
+            name
+        ))),
+    }
+}
+
+/// 把多个值转换为 bulk string 数组回复
+pub fn bulk_array(values: impl IntoIterator<Item = Bytes>) -> RespFrame {
+    RespArray::new(Some(values.into_iter().map(RespFrame::from).collect())).into()
+}
+
+/// [0, len) 内的随机下标，len 必须大于 0
+/// 每个 RandomState 的种子都不同，足够用于随机取样
+pub fn random_index(len: usize) -> usize {
+    (RandomState::new().build_hasher().finish() % len as u64) as usize
+}
+
+/// 提取整数参数
+pub fn extract_int(frame: &RespFrame, name: &str) -> Result<i64, CommandError> {
+    extract_string(frame, name)?.parse().map_err(|_| {
+        CommandError::InvalidArguments("value is not an integer or out of range".into())
+    })
+}
+
+/// 按 parse_strict_int 的规则提取整数参数，用于 INCRBY 一类的增量
+pub fn extract_strict_int(frame: &RespFrame, name: &str) -> Result<i64, CommandError> {
+    parse_strict_int(&extract_bytes(frame, name)?).ok_or_else(|| {
+        CommandError::InvalidArguments("value is not an integer or out of range".into())
+    })
+}
+
+/// 与 Redis 的 string2ll 一致的严格整数解析：不允许 '+'、前导 0、"-0" 和空白
+pub fn parse_strict_int(value: &[u8]) -> Option<i64> {
+    let digits = value.strip_prefix(b"-").unwrap_or(value);
+    let valid = match digits {
+        [b'0'] => digits.len() == value.len(),
+        [b'1'..=b'9', rest @ ..] => rest.iter().all(u8::is_ascii_digit),
+        _ => false,
+    };
+    valid
+        .then(|| str::from_utf8(value).ok()?.parse().ok())
+        .flatten()
+}
+
+/// 将 [start, stop] 闭区间（支持负数下标）转换为合法下标，区间为空时返回 None
+pub fn normalize_range(start: i64, stop: i64, len: usize) -> Option<(usize, usize)> {
+    let len = len as i64;
+    let start = if start < 0 {
+        (len + start).max(0)
     } else {
-        Err(CommandError::InvalidArguments(format!(
-            "{} command expects {} arguments, got 0",
-            name_slice.join(" "),
-            n_args
-        )))
+        start
+    };
+    let stop = if stop < 0 {
+        len + stop
+    } else {
+        stop.min(len - 1)
+    };
+    if start > stop || start >= len {
+        return None;
     }
+    Some((start as usize, stop as usize))
 }
 
 impl TryFrom<RespFrame> for Command {
     type Error = CommandError;
     fn try_from(v: RespFrame) -> Result<Self, Self::Error> {
-        match v {
-            RespFrame::Array(resp_array) => resp_array.try_into(),
-            _ => Err(CommandError::InvalidCommand(
-                "invalid command array".to_string(),
-            )),
-        }
+        parse_request(v).map(|(_, command)| command)
     }
 }
 
 impl TryFrom<RespArray> for Command {
     type Error = CommandError;
     fn try_from(v: RespArray) -> Result<Self, Self::Error> {
-        match v {
-            RespArray {
-                elements: Some(ref elements),
-            } => match elements.first() {
-                Some(RespFrame::BulkString(BulkString {
-                    content: Some(bytes),
-                })) => match bytes.as_slice() {
-                    b"ping" => Ok(Command::Ping),
-                    b"set" => CommandSet::try_from(v).map(Command::Set),
-                    b"get" => CommandGet::try_from(v).map(Command::Get),
-                    b"hget" => CommandHGet::try_from(v).map(Command::HGet),
-                    b"hset" => CommandHSet::try_from(v).map(Command::HSet),
-                    b"hgetall" => CommandHGetAll::try_from(v).map(Command::HGetAll),
-                    _ => Ok(Command::Unrecognized(Unrecognized {
-                        command: String::from_utf8_lossy(bytes).to_string(),
-                    })),
-                },
-                _ => Err(CommandError::InvalidCommand(format!(
-                    "unknown command: {:?}",
-                    elements.first()
-                ))),
-            },
-            _ => Err(CommandError::InvalidCommand(
-                "empty command array".to_string(),
-            )),
-        }
+        let (spec, _) = resolve_command(&v)?;
+        spec.parse(v)
     }
 }
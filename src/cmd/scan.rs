@@ -0,0 +1,398 @@
+use std::collections::{HashMap, HashSet};
+
+use bytes::Bytes;
+
+use crate::{
+    backend::{Backend, SortedSet, WrongType, scan_by_hash},
+    cmd::{CommandError, CommandExecutor, bulk_array, command_args, extract_bytes, extract_string},
+    glob::glob_match,
+    resp::{RespArray, RespFrame},
+};
+
+// Redis命令与RESP协议格式对应表
+// | 命令  | 参数                                          | 对应格式                                                   |
+// |-------|-----------------------------------------------|------------------------------------------------------------|
+// | SCAN  | cursor [MATCH pat] [COUNT n] [TYPE type]      | "*4\r\n$4\r\nscan\r\n$1\r\n0\r\n$5\r\nmatch\r\n$2\r\nu*\r\n" |
+// | HSCAN | key cursor [MATCH pat] [COUNT n]              | "*3\r\n$5\r\nhscan\r\n$3\r\nmap\r\n$1\r\n0\r\n"            |
+// | SSCAN | key cursor [MATCH pat] [COUNT n]              | "*3\r\n$5\r\nsscan\r\n$3\r\nset\r\n$1\r\n0\r\n"            |
+// | ZSCAN | key cursor [MATCH pat] [COUNT n]              | "*3\r\n$5\r\nzscan\r\n$4\r\nzset\r\n$1\r\n0\r\n"           |
+
+/// COUNT 的默认值，与 Redis 相同
+const DEFAULT_COUNT: usize = 10;
+
+/// 游标之后的可选参数；COUNT 只是每轮遍历数量的提示，MATCH 和 TYPE 在遍历之后过滤
+#[derive(Debug, PartialEq)]
+pub struct ScanOptions {
+    pattern: Option<Bytes>,
+    count: usize,
+    /// 只有 SCAN 支持 TYPE
+    kind: Option<String>,
+}
+
+#[derive(Debug)]
+pub struct CommandScan {
+    cursor: u64,
+    options: ScanOptions,
+}
+#[derive(Debug)]
+pub struct CommandHScan {
+    key: Bytes,
+    cursor: u64,
+    options: ScanOptions,
+}
+#[derive(Debug)]
+pub struct CommandSScan {
+    key: Bytes,
+    cursor: u64,
+    options: ScanOptions,
+}
+#[derive(Debug)]
+pub struct CommandZScan {
+    key: Bytes,
+    cursor: u64,
+    options: ScanOptions,
+}
+
+impl ScanOptions {
+    fn matches(&self, item: &[u8]) -> bool {
+        self.pattern
+            .as_ref()
+            .is_none_or(|pattern| glob_match(pattern, item))
+    }
+}
+
+/// 回复格式：[下一个游标, [元素 ...]]
+fn scan_reply(cursor: u64, items: impl IntoIterator<Item = Bytes>) -> RespFrame {
+    RespArray::from_vec(vec![cursor.to_string().as_str().into(), bulk_array(items)]).into()
+}
+
+impl CommandExecutor for CommandScan {
+    fn execute(self, backend: &Backend) -> RespFrame {
+        let (next, keys) = backend.scan_keys(self.cursor, self.options.count);
+        let keys = keys.into_iter().filter(|key| {
+            self.options.matches(key)
+                && self.options.kind.as_ref().is_none_or(|kind| {
+                    backend
+                        .type_of(key)
+                        .is_some_and(|name| name.eq_ignore_ascii_case(kind))
+                })
+        });
+        scan_reply(next, keys)
+    }
+}
+impl CommandExecutor for CommandHScan {
+    fn execute(self, backend: &Backend) -> RespFrame {
+        let ret = backend.read(&self.key, |hash: &HashMap<Bytes, Bytes>| {
+            let (next, pairs) = scan_by_hash(
+                hash.iter(),
+                |(field, _)| field,
+                self.cursor,
+                self.options.count,
+            );
+            let pairs = pairs
+                .into_iter()
+                .filter(|(field, _)| self.options.matches(field))
+                .flat_map(|(field, value)| [field.clone(), value.clone()])
+                .collect::<Vec<_>>();
+            (next, pairs)
+        });
+        reply_or_error(ret)
+    }
+}
+impl CommandExecutor for CommandSScan {
+    fn execute(self, backend: &Backend) -> RespFrame {
+        let ret = backend.read(&self.key, |set: &HashSet<Bytes>| {
+            let (next, members) =
+                scan_by_hash(set.iter(), |member| member, self.cursor, self.options.count);
+            let members = members
+                .into_iter()
+                .filter(|member| self.options.matches(member))
+                .cloned()
+                .collect::<Vec<_>>();
+            (next, members)
+        });
+        reply_or_error(ret)
+    }
+}
+impl CommandExecutor for CommandZScan {
+    fn execute(self, backend: &Backend) -> RespFrame {
+        let ret = backend.read(&self.key, |zset: &SortedSet| {
+            let (next, members) = scan_by_hash(
+                zset.iter(),
+                |(member, _)| member,
+                self.cursor,
+                self.options.count,
+            );
+            // 与 Redis 相同，score 以 bulk string 返回
+            let members = members
+                .into_iter()
+                .filter(|(member, _)| self.options.matches(member))
+                .flat_map(|(member, score)| [member.clone(), score.to_string().into()])
+                .collect::<Vec<_>>();
+            (next, members)
+        });
+        reply_or_error(ret)
+    }
+}
+
+/// key 不存在时相当于空集合，直接结束遍历
+fn reply_or_error(ret: Result<Option<(u64, Vec<Bytes>)>, WrongType>) -> RespFrame {
+    match ret {
+        Ok(Some((next, items))) => scan_reply(next, items),
+        Ok(None) => scan_reply(0, []),
+        Err(e) => e.into(),
+    }
+}
+
+/// 解析 cursor [MATCH pattern] [COUNT count] [TYPE type]
+fn parse_scan(
+    args: &[&RespFrame],
+    name: &'static str,
+    allow_type: bool,
+) -> Result<(u64, ScanOptions), CommandError> {
+    let cursor = extract_string(args[0], name)?
+        .parse()
+        .map_err(|_| CommandError::InvalidArguments("invalid cursor".to_string()))?;
+    let mut options = ScanOptions {
+        pattern: None,
+        count: DEFAULT_COUNT,
+        kind: None,
+    };
+    let syntax_error = || CommandError::InvalidArguments("syntax error".to_string());
+    let mut rest = args[1..].iter();
+    while let Some(option) = rest.next() {
+        let value = rest.next().ok_or_else(syntax_error)?;
+        match extract_string(option, name)?.to_ascii_lowercase().as_str() {
+            "match" => options.pattern = Some(extract_bytes(value, name)?),
+            "count" => {
+                options.count = extract_string(value, name)?
+                    .parse::<i64>()
+                    .map_err(|_| {
+                        CommandError::InvalidArguments(
+                            "value is not an integer or out of range".to_string(),
+                        )
+                    })?
+                    .try_into()
+                    .ok()
+                    .filter(|count| *count > 0)
+                    .ok_or_else(syntax_error)?;
+            }
+            "type" if allow_type => options.kind = Some(extract_string(value, name)?),
+            _ => return Err(syntax_error()),
+        }
+    }
+    Ok((cursor, options))
+}
+
+impl TryFrom<RespArray> for CommandScan {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        let args = command_args(&value)?;
+        let (cursor, options) = parse_scan(&args, "SCAN", true)?;
+        Ok(CommandScan { cursor, options })
+    }
+}
+
+/// 解析 key cursor [MATCH pattern] [COUNT count]
+fn parse_key_scan(
+    value: &RespArray,
+    name: &'static str,
+) -> Result<(Bytes, u64, ScanOptions), CommandError> {
+    let args = command_args(value)?;
+    let key = extract_bytes(args[0], name)?;
+    let (cursor, options) = parse_scan(&args[1..], name, false)?;
+    Ok((key, cursor, options))
+}
+
+impl TryFrom<RespArray> for CommandHScan {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        let (key, cursor, options) = parse_key_scan(&value, "HSCAN")?;
+        Ok(CommandHScan {
+            key,
+            cursor,
+            options,
+        })
+    }
+}
+impl TryFrom<RespArray> for CommandSScan {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        let (key, cursor, options) = parse_key_scan(&value, "SSCAN")?;
+        Ok(CommandSScan {
+            key,
+            cursor,
+            options,
+        })
+    }
+}
+impl TryFrom<RespArray> for CommandZScan {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        let (key, cursor, options) = parse_key_scan(&value, "ZSCAN")?;
+        Ok(CommandZScan {
+            key,
+            cursor,
+            options,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{cmd::Command, resp::SimpleError};
+
+    fn run(backend: &Backend, args: &[&str]) -> RespFrame {
+        let frames = args.iter().map(|arg| (*arg).into()).collect::<Vec<_>>();
+        match Command::try_from(RespArray::from(frames)) {
+            Ok(cmd) => cmd.execute(backend),
+            Err(e) => SimpleError::new(format!("ERR {}", e)).into(),
+        }
+    }
+
+    /// 把一次回复拆成 (游标, 元素)
+    fn split(frame: RespFrame) -> (String, Vec<String>) {
+        let RespFrame::Array(RespArray {
+            elements: Some(mut reply),
+        }) = frame
+        else {
+            panic!("expect array, got {:?}", frame);
+        };
+        let RespFrame::Array(RespArray {
+            elements: Some(items),
+        }) = reply.pop().unwrap()
+        else {
+            panic!("expect array");
+        };
+        let text = |frame: RespFrame| match frame {
+            RespFrame::BulkString(bulk) => {
+                String::from_utf8(bulk.content.unwrap().to_vec()).unwrap()
+            }
+            other => panic!("expect bulk string, got {:?}", other),
+        };
+        (
+            text(reply.pop().unwrap()),
+            items.into_iter().map(text).collect(),
+        )
+    }
+
+    /// 从 0 开始遍历到游标回到 0
+    fn scan_all(backend: &Backend, args: &[&str]) -> Vec<String> {
+        let mut cursor = "0".to_string();
+        let mut all = Vec::new();
+        loop {
+            let mut request = args.to_vec();
+            let position = if request[0] == "scan" { 1 } else { 2 };
+            request.insert(position, &cursor);
+            let (next, items) = split(run(backend, &request));
+            all.extend(items);
+            if next == "0" {
+                break;
+            }
+            cursor = next;
+        }
+        all.sort();
+        all
+    }
+
+    #[test]
+    fn test_scan() {
+        let backend = Backend::new();
+        for i in 0..30 {
+            backend.set(format!("user:{}", i).into(), "v".into());
+        }
+        run(&backend, &["sadd", "tags", "a"]);
+        assert_eq!(scan_all(&backend, &["scan", "COUNT", "3"]).len(), 31);
+        let users = scan_all(&backend, &["scan", "match", "user:1*"]);
+        assert_eq!(users.len(), 11);
+        assert!(users.iter().all(|key| key.starts_with("user:1")));
+        assert_eq!(scan_all(&backend, &["scan", "TYPE", "set"]), ["tags"]);
+
+        let (next, items) = split(run(&backend, &["scan", "0", "count", "100"]));
+        assert_eq!((next.as_str(), items.len()), ("0", 31));
+        assert!(matches!(
+            run(&backend, &["scan", "x"]),
+            RespFrame::SimpleError(_)
+        ));
+        assert!(matches!(
+            run(&backend, &["scan", "0", "count", "0"]),
+            RespFrame::SimpleError(_)
+        ));
+        assert!(matches!(
+            run(&backend, &["scan", "0", "match"]),
+            RespFrame::SimpleError(_)
+        ));
+    }
+
+    #[test]
+    fn test_scan_match_and_type() {
+        let backend = Backend::new();
+        for i in 0..10 {
+            backend.set(format!("user:{}", i).into(), "v".into());
+            run(&backend, &["hset", &format!("user:h{}", i), "f", "v"]);
+            run(&backend, &["hset", &format!("order:{}", i), "f", "v"]);
+        }
+        run(&backend, &["sadd", "user:set", "a"]);
+
+        // MATCH 和 TYPE 同时生效，小 COUNT 下也不漏不重
+        let hashes = scan_all(
+            &backend,
+            &["scan", "MATCH", "user:*", "TYPE", "hash", "COUNT", "2"],
+        );
+        assert_eq!(hashes.len(), 10);
+        assert!(hashes.iter().all(|key| key.starts_with("user:h")));
+        assert_eq!(
+            scan_all(&backend, &["scan", "type", "STRING", "match", "user:[0-4]"]),
+            ["user:0", "user:1", "user:2", "user:3", "user:4"]
+        );
+        assert_eq!(
+            scan_all(&backend, &["scan", "match", "order:*", "type", "set"]),
+            Vec::<String>::new()
+        );
+        assert_eq!(
+            scan_all(&backend, &["scan", "match", "user:*", "type", "nosuchtype"]),
+            Vec::<String>::new()
+        );
+        // 后出现的同名选项覆盖前一个
+        assert_eq!(
+            scan_all(&backend, &["scan", "match", "order:*", "match", "user:s*"]),
+            ["user:set"]
+        );
+        assert!(matches!(
+            run(&backend, &["scan", "0", "type"]),
+            RespFrame::SimpleError(_)
+        ));
+        assert!(matches!(
+            run(&backend, &["scan", "0", "match", "*", "bogus", "1"]),
+            RespFrame::SimpleError(_)
+        ));
+    }
+
+    #[test]
+    fn test_key_scan() {
+        let backend = Backend::new();
+        run(&backend, &["hset", "h", "a", "1", "b", "2", "c", "3"]);
+        assert_eq!(
+            scan_all(&backend, &["hscan", "h", "count", "1"]),
+            ["1", "2", "3", "a", "b", "c"]
+        );
+        assert_eq!(
+            scan_all(&backend, &["hscan", "h", "match", "[ab]"]),
+            ["1", "2", "a", "b"]
+        );
+        run(&backend, &["sadd", "s", "x", "y", "z"]);
+        assert_eq!(
+            scan_all(&backend, &["sscan", "s", "count", "2"]),
+            ["x", "y", "z"]
+        );
+        run(&backend, &["zadd", "z", "1.5", "m", "2", "n"]);
+        assert_eq!(scan_all(&backend, &["zscan", "z"]), ["1.5", "2", "m", "n"]);
+        assert_eq!(scan_all(&backend, &["sscan", "nope"]), Vec::<String>::new());
+        assert!(matches!(
+            run(&backend, &["sscan", "s", "0", "type", "set"]),
+            RespFrame::SimpleError(_)
+        ));
+        assert_eq!(run(&backend, &["hscan", "s", "0"]), WrongType.into());
+    }
+}
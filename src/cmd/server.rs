@@ -0,0 +1,449 @@
+use crate::{
+    backend::Backend,
+    cmd::{
+        COMMAND_TABLE, CommandError, CommandExecutor, RESP_OK, command_args, extract_string,
+        lookup_command,
+    },
+    resp::{RespArray, RespFrame, RespInteger, RespMap, RespNull, SimpleError, SimpleString},
+};
+
+// Redis命令与RESP协议格式对应表
+// | 命令    | 参数         | 对应格式                                                                 |
+// |---------|--------------|--------------------------------------------------------------------------|
+// | SAVE    |              | "*1\r\n$4\r\nsave\r\n"                                                   |
+// | BGSAVE  |              | "*1\r\n$6\r\nbgsave\r\n"                                                 |
+// | BGREWRITEAOF |         | "*1\r\n$12\r\nbgrewriteaof\r\n"                                          |
+// | COMMAND |              | "*1\r\n$7\r\ncommand\r\n"                                                 |
+// | COMMAND INFO | [name ...] | "*3\r\n$7\r\ncommand\r\n$4\r\ninfo\r\n$3\r\nget\r\n"                    |
+// | COMMAND COUNT |        | "*2\r\n$7\r\ncommand\r\n$5\r\ncount\r\n"                                  |
+// | COMMAND DOCS | [name ...] | "*3\r\n$7\r\ncommand\r\n$4\r\ndocs\r\n$3\r\nget\r\n"                    |
+
+/// 拍摄快照时需要独占键空间，不能在持有 lock_shared 时执行
+#[derive(Debug)]
+pub struct CommandSave;
+#[derive(Debug)]
+pub struct CommandBgSave;
+#[derive(Debug)]
+pub struct CommandBgRewriteAof;
+/// 查询命令表，名字为空时返回全部命令
+#[derive(Debug, PartialEq)]
+pub enum CommandCommand {
+    Info(Vec<String>),
+    Count,
+    Docs(Vec<String>),
+}
+
+impl CommandExecutor for CommandSave {
+    fn execute(self, backend: &Backend) -> RespFrame {
+        match backend.save() {
+            Ok(()) => RESP_OK.clone(),
+            Err(e) => SimpleError::new(format!("ERR {}", e)).into(),
+        }
+    }
+}
+impl CommandExecutor for CommandBgSave {
+    fn execute(self, backend: &Backend) -> RespFrame {
+        match backend.bgsave() {
+            Ok(()) => SimpleString::new("Background saving started").into(),
+            Err(e) => SimpleError::new(format!("ERR {}", e)).into(),
+        }
+    }
+}
+
+impl CommandExecutor for CommandBgRewriteAof {
+    fn execute(self, backend: &Backend) -> RespFrame {
+        match backend.bgrewriteaof() {
+            Ok(()) => SimpleString::new("Background append only file rewriting started").into(),
+            Err(e) => SimpleError::new(format!("ERR {}", e)).into(),
+        }
+    }
+}
+
+impl CommandExecutor for CommandCommand {
+    fn execute(self, _backend: &Backend) -> RespFrame {
+        match self {
+            CommandCommand::Info(names) if names.is_empty() => {
+                RespArray::from_vec(COMMAND_TABLE.iter().map(|spec| spec.info()).collect()).into()
+            }
+            // 未知命令对应位置回复 null
+            CommandCommand::Info(names) => RespArray::from_vec(
+                names
+                    .iter()
+                    .map(|name| match lookup_command(name.as_bytes()) {
+                        Some(spec) => spec.info(),
+                        None => RespNull.into(),
+                    })
+                    .collect(),
+            )
+            .into(),
+            CommandCommand::Count => RespInteger::new(COMMAND_TABLE.len() as i64).into(),
+            CommandCommand::Docs(names) => {
+                let mut docs = RespMap::default();
+                let specs: Vec<_> = if names.is_empty() {
+                    COMMAND_TABLE.iter().collect()
+                } else {
+                    names
+                        .iter()
+                        .filter_map(|name| lookup_command(name.as_bytes()))
+                        .collect()
+                };
+                for spec in specs {
+                    docs.insert(spec.name.to_string(), spec.docs());
+                }
+                docs.into()
+            }
+        }
+    }
+}
+
+impl TryFrom<RespArray> for CommandSave {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        command_args(&value)?;
+        Ok(CommandSave)
+    }
+}
+impl TryFrom<RespArray> for CommandBgSave {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        command_args(&value)?;
+        Ok(CommandBgSave)
+    }
+}
+impl TryFrom<RespArray> for CommandBgRewriteAof {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        command_args(&value)?;
+        Ok(CommandBgRewriteAof)
+    }
+}
+impl TryFrom<RespArray> for CommandCommand {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        let args = command_args(&value)?;
+        let Some((subcommand, rest)) = args.split_first() else {
+            return Ok(CommandCommand::Info(vec![]));
+        };
+        let names = || {
+            rest.iter()
+                .map(|name| extract_string(name, "COMMAND"))
+                .collect::<Result<Vec<_>, _>>()
+        };
+        let subcommand = extract_string(subcommand, "COMMAND")?;
+        match subcommand.to_ascii_lowercase().as_str() {
+            "info" => Ok(CommandCommand::Info(names()?)),
+            "docs" => Ok(CommandCommand::Docs(names()?)),
+            "count" if rest.is_empty() => Ok(CommandCommand::Count),
+            "count" => Err(CommandError::InvalidNumberOfArguments(
+                "wrong number of arguments for 'command|count' command".to_string(),
+            )),
+            _ => Err(CommandError::InvalidCommand(format!(
+                "unknown subcommand '{}'. Try COMMAND HELP.",
+                subcommand
+            ))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs, thread, time::Duration};
+
+    use anyhow::Result;
+    use bytes::BytesMut;
+
+    use super::*;
+    use crate::{cmd::Command, resp::RespDecode};
+
+    async fn execute(backend: &Backend, buf: &str) -> Result<RespFrame> {
+        let frame = RespArray::decode(&mut BytesMut::from(buf))?.into();
+        let client = backend.register_client("127.0.0.1:40000".parse()?);
+        Ok(Command::execute_request(frame, backend, &client).await?)
+    }
+
+    #[tokio::test]
+    async fn test_save_bgsave() -> Result<()> {
+        let dir = std::env::temp_dir().join(format!("dredis-{}-cmd-save", std::process::id()));
+        fs::create_dir_all(&dir)?;
+        let backend = Backend::with_rdb_path(dir.join("dump.rdb"));
+        execute(&backend, "*3\r\n$3\r\nset\r\n$1\r\nk\r\n$1\r\nv\r\n").await?;
+        assert_eq!(backend.dirty(), 1);
+        assert_eq!(
+            execute(&backend, "*1\r\n$4\r\nsave\r\n").await?,
+            RESP_OK.clone()
+        );
+        assert_eq!(backend.dirty(), 0);
+
+        backend.set("k2".into(), "v2".into());
+        assert_eq!(
+            execute(&backend, "*1\r\n$6\r\nbgsave\r\n").await?,
+            SimpleString::new("Background saving started").into()
+        );
+        while backend.is_saving() {
+            thread::sleep(Duration::from_millis(1));
+        }
+        let loaded = Backend::with_rdb_path(dir.join("dump.rdb"));
+        assert_eq!(loaded.load()?, 2);
+        assert!(CommandSave::try_from(RespArray::from(vec!["save".into(), "x".into()])).is_err());
+        fs::remove_dir_all(dir)?;
+        Ok(())
+    }
+
+    async fn execute_args(backend: &Backend, args: &[&str]) -> Result<RespFrame> {
+        let frames = args.iter().map(|arg| (*arg).into()).collect::<Vec<_>>();
+        let client = backend.register_client("127.0.0.1:40000".parse()?);
+        Ok(Command::execute_request(RespArray::from(frames).into(), backend, &client).await?)
+    }
+
+    /// 从 AOF 恢复到新的 backend
+    fn replay(path: &std::path::Path) -> Result<Backend> {
+        let backend = Backend::new();
+        assert!(crate::cmd::load_aof(&backend, path, false)?.is_some());
+        Ok(backend)
+    }
+
+    #[tokio::test]
+    async fn test_aof_replay_and_rewrite() -> Result<()> {
+        let dir = std::env::temp_dir().join(format!("dredis-{}-cmd-aof", std::process::id()));
+        fs::create_dir_all(&dir)?;
+        let path = dir.join("appendonly.aof");
+        let backend = Backend::new();
+        backend.open_aof(&path, crate::backend::FsyncPolicy::Always)?;
+        execute_args(&backend, &["set", "a", "1", "EX", "100"]).await?;
+        execute_args(&backend, &["rpush", "l", "x", "y"]).await?;
+        execute_args(&backend, &["lpop", "l"]).await?;
+        // 被 LPUSH 唤醒的 BLPOP 以 LPOP 的形式写入 AOF
+        let waiting = {
+            let backend = backend.clone();
+            tokio::spawn(async move { execute_args(&backend, &["blpop", "l2", "0"]).await })
+        };
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        execute_args(&backend, &["lpush", "l2", "1"]).await?;
+        waiting.await??;
+        execute_args(&backend, &["rpush", "l2", "2"]).await?;
+        // 被唤醒的 BLMOVE 以一条 LMOVE 写入 AOF
+        let waiting = {
+            let backend = backend.clone();
+            tokio::spawn(async move {
+                execute_args(&backend, &["blmove", "l3", "l4", "LEFT", "RIGHT", "0"]).await
+            })
+        };
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        execute_args(&backend, &["rpush", "l3", "z"]).await?;
+        assert_eq!(waiting.await??, "z".into());
+        assert!(fs::read_to_string(&path)?.ends_with(
+            "*5\r\n$5\r\nlmove\r\n$2\r\nl3\r\n$2\r\nl4\r\n$4\r\nLEFT\r\n$5\r\nRIGHT\r\n"
+        ));
+
+        let check = |loaded: &Backend| {
+            let ttl = loaded.pttl(b"a");
+            assert!(ttl > 90_000 && ttl <= 100_000, "ttl {}", ttl);
+            let list = |key: &[u8]| loaded.list_read(key, |list| list.clone());
+            assert_eq!(list(b"l"), Ok(Some(["y".into()].into())));
+            assert_eq!(list(b"l2"), Ok(Some(["2".into()].into())));
+            assert_eq!(list(b"l3"), Ok(None));
+            assert_eq!(list(b"l4"), Ok(Some(["z".into()].into())));
+        };
+        check(&replay(&path)?);
+
+        assert_eq!(
+            execute_args(&backend, &["bgrewriteaof"]).await?,
+            SimpleString::new("Background append only file rewriting started").into()
+        );
+        while backend.is_rewriting() {
+            thread::sleep(Duration::from_millis(1));
+        }
+        execute_args(&backend, &["del", "l"]).await?;
+        let loaded = replay(&path)?;
+        assert!(!loaded.contains_key(b"l"));
+        execute_args(&backend, &["rpush", "l", "y"]).await?;
+        check(&replay(&path)?);
+        fs::remove_dir_all(dir)?;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_aof_skips_failed_and_noop_writes() -> Result<()> {
+        let dir = std::env::temp_dir().join(format!("dredis-{}-cmd-aof-noop", std::process::id()));
+        fs::create_dir_all(&dir)?;
+        let path = dir.join("appendonly.aof");
+        let backend = Backend::new();
+        backend.open_aof(&path, crate::backend::FsyncPolicy::Always)?;
+        execute_args(&backend, &["set", "s", "x"]).await?;
+        // 失败、条件不满足和没有修改的写命令都不写入 AOF
+        execute_args(&backend, &["incr", "s"]).await?;
+        execute_args(&backend, &["set", "s", "y", "NX"]).await?;
+        execute_args(&backend, &["del", "missing"]).await?;
+        execute_args(&backend, &["expire", "missing", "10"]).await?;
+        execute_args(&backend, &["persist", "s"]).await?;
+        execute_args(&backend, &["get", "s"]).await?;
+        assert_eq!(
+            fs::read(&path)?,
+            b"*3\r\n$3\r\nset\r\n$1\r\ns\r\n$1\r\nx\r\n"
+        );
+
+        // EXPIRE 执行时的过期时间与写入 AOF 的一致
+        execute_args(&backend, &["expire", "s", "100"]).await?;
+        let at = backend.expire_time(b"s").unwrap_or_default();
+        let replayed = replay(&path)?;
+        assert_eq!(replayed.expire_time(b"s"), Some(at));
+        fs::remove_dir_all(dir)?;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_failed_and_noop_writes_not_dirty() -> Result<()> {
+        let backend = Backend::new();
+        execute_args(&backend, &["hset", "h", "f", "x", "g", "y"]).await?;
+        execute_args(&backend, &["sadd", "s", "a"]).await?;
+        execute_args(&backend, &["rpush", "l", "a", "b"]).await?;
+        assert_eq!(backend.dirty(), 5);
+        for args in [
+            &["hincrby", "h", "f", "1"][..],
+            &["hsetnx", "h", "f", "z"],
+            &["hdel", "h", "missing"],
+            &["srem", "s", "missing"],
+            &["sadd", "s", "a"],
+            &["lset", "l", "10", "x"],
+            &["lrem", "l", "0", "missing"],
+            &["ltrim", "l", "0", "-1"],
+            &["append", "h", "x"],
+            &["bitfield", "s", "GET", "u8", "0"],
+            &["zrem", "missing", "a"],
+        ] {
+            execute_args(&backend, args).await?;
+            assert_eq!(backend.dirty(), 5, "{:?}", args);
+        }
+        // 计数与实际修改的元素个数一致
+        execute_args(&backend, &["rpush", "l", "c", "d", "e"]).await?;
+        execute_args(&backend, &["srem", "s", "a", "missing"]).await?;
+        execute_args(&backend, &["ltrim", "l", "0", "1"]).await?;
+        assert_eq!(backend.dirty(), 5 + 3 + 1 + 3);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_command_introspection() -> Result<()> {
+        let backend = Backend::new();
+        assert_eq!(
+            execute_args(&backend, &["COMMAND", "COUNT"]).await?,
+            RespInteger::new(COMMAND_TABLE.len() as i64).into()
+        );
+        let RespFrame::Array(all) = execute_args(&backend, &["command"]).await? else {
+            panic!("expect array");
+        };
+        assert_eq!(all.as_ref().map(Vec::len), Some(COMMAND_TABLE.len()));
+
+        let simple = |items: &[&str]| -> RespFrame {
+            RespArray::from_vec(items.iter().map(|s| SimpleString::new(*s).into()).collect()).into()
+        };
+        let RespFrame::Array(info) =
+            execute_args(&backend, &["command", "INFO", "GET", "nope"]).await?
+        else {
+            panic!("expect array");
+        };
+        let info = info.elements.unwrap();
+        assert_eq!(info[1], RespNull.into());
+        let RespFrame::Array(RespArray {
+            elements: Some(get),
+        }) = &info[0]
+        else {
+            panic!("expect array");
+        };
+        assert_eq!(get[0], "get".into());
+        assert_eq!(get[1], RespInteger::new(2).into());
+        assert_eq!(get[2], simple(&["readonly", "fast"]));
+        assert_eq!(
+            get[3..6],
+            [
+                RespInteger::new(1).into(),
+                RespInteger::new(1).into(),
+                RespInteger::new(1).into()
+            ]
+        );
+        assert_eq!(get[6], simple(&["@read", "@string", "@fast"]));
+        let RespFrame::Array(RespArray {
+            elements: Some(key_specs),
+        }) = &get[8]
+        else {
+            panic!("expect array");
+        };
+        assert_eq!(key_specs.len(), 1);
+
+        let RespFrame::Map(docs) = execute_args(&backend, &["command", "docs", "set"]).await?
+        else {
+            panic!("expect map");
+        };
+        let RespFrame::Map(set) = &docs["set"] else {
+            panic!("expect map");
+        };
+        assert_eq!(set["group"], "string".into());
+
+        assert!(
+            execute_args(&backend, &["command", "count", "x"])
+                .await
+                .is_err()
+        );
+        assert!(execute_args(&backend, &["command", "nope"]).await.is_err());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_command_introspection_edges() -> Result<()> {
+        let backend = Backend::new();
+        let info = |frame: RespFrame| -> Vec<RespFrame> {
+            match frame {
+                RespFrame::Array(RespArray {
+                    elements: Some(items),
+                }) => items,
+                other => panic!("expect array, got {:?}", other),
+            }
+        };
+        // 不带命令名的 INFO 与 COMMAND 相同
+        assert_eq!(
+            execute_args(&backend, &["command", "info"]).await?,
+            execute_args(&backend, &["command"]).await?
+        );
+
+        // 命令名不区分大小写，可变参数命令的 arity 和步长
+        let mset = info(execute_args(&backend, &["command", "info", "MSet"]).await?);
+        let mset = info(mset.into_iter().next().unwrap());
+        assert_eq!(mset[0], "mset".into());
+        assert_eq!(mset[1], RespInteger::new(-3).into());
+        assert_eq!(
+            mset[3..6],
+            [
+                RespInteger::new(1).into(),
+                RespInteger::new(-1).into(),
+                RespInteger::new(2).into()
+            ]
+        );
+
+        // 没有 key 的命令 key 位置全为 0，key-specs 为空
+        let ping = info(execute_args(&backend, &["command", "info", "ping"]).await?);
+        let ping = info(ping.into_iter().next().unwrap());
+        assert_eq!(ping[3], RespInteger::new(0).into());
+        assert!(info(ping[8].clone()).is_empty());
+
+        // DOCS 忽略未知命令，不带命令名时返回全部
+        let RespFrame::Map(docs) = execute_args(&backend, &["command", "docs", "nope"]).await?
+        else {
+            panic!("expect map");
+        };
+        assert!(docs.is_empty());
+        let RespFrame::Map(docs) = execute_args(&backend, &["command", "docs"]).await? else {
+            panic!("expect map");
+        };
+        assert_eq!(docs.len(), COMMAND_TABLE.len());
+
+        let err = execute_args(&backend, &["command", "help"])
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("unknown subcommand 'help'"));
+        let err = execute_args(&backend, &["command", "count", "1", "2"])
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("command|count"));
+        Ok(())
+    }
+}
@@ -0,0 +1,389 @@
+use std::collections::HashSet;
+
+use bytes::Bytes;
+
+use crate::{
+    backend::{Backend, WrongType},
+    cmd::{CommandError, CommandExecutor, command_args, extract_bytes},
+    resp::{BulkString, RespArray, RespFrame, RespInteger, RespSet},
+};
+
+// Redis命令与RESP协议格式对应表
+// | 命令        | 参数                | 对应格式                                                      |
+// |-------------|---------------------|---------------------------------------------------------------|
+// | SADD        | key mem [mem ...]   | "*3\r\n$4\r\nsadd\r\n$3\r\nset\r\n$5\r\nhello\r\n"            |
+// | SREM        | key mem [mem ...]   | "*3\r\n$4\r\nsrem\r\n$3\r\nset\r\n$5\r\nhello\r\n"            |
+// | SMEMBERS    | key                 | "*2\r\n$8\r\nsmembers\r\n$3\r\nset\r\n"                       |
+// | SISMEMBER   | key mem             | "*3\r\n$9\r\nsismember\r\n$3\r\nset\r\n$5\r\nhello\r\n"       |
+// | SCARD       | key                 | "*2\r\n$5\r\nscard\r\n$3\r\nset\r\n"                          |
+// | SINTER      | key [key ...]       | "*3\r\n$6\r\nsinter\r\n$2\r\ns1\r\n$2\r\ns2\r\n"              |
+// | SUNION      | key [key ...]       | "*3\r\n$6\r\nsunion\r\n$2\r\ns1\r\n$2\r\ns2\r\n"              |
+// | SDIFF       | key [key ...]       | "*3\r\n$5\r\nsdiff\r\n$2\r\ns1\r\n$2\r\ns2\r\n"               |
+// | SINTERSTORE | dst key [key ...]   | "*4\r\n$11\r\nsinterstore\r\n$3\r\ndst\r\n$2\r\ns1\r\n$2\r\ns2\r\n" |
+// | SUNIONSTORE | dst key [key ...]   | "*4\r\n$11\r\nsunionstore\r\n$3\r\ndst\r\n$2\r\ns1\r\n$2\r\ns2\r\n" |
+// | SDIFFSTORE  | dst key [key ...]   | "*4\r\n$10\r\nsdiffstore\r\n$3\r\ndst\r\n$2\r\ns1\r\n$2\r\ns2\r\n"  |
+
+/// 多个集合之间的运算
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SetOperation {
+    Inter,
+    Union,
+    Diff,
+}
+
+#[derive(Debug)]
+pub struct CommandSAdd {
+    key: Bytes,
+    members: Vec<Bytes>,
+}
+#[derive(Debug)]
+pub struct CommandSRem {
+    key: Bytes,
+    members: Vec<Bytes>,
+}
+#[derive(Debug)]
+pub struct CommandSMembers {
+    key: Bytes,
+}
+#[derive(Debug)]
+pub struct CommandSIsMember {
+    key: Bytes,
+    member: Bytes,
+}
+#[derive(Debug)]
+pub struct CommandSCard {
+    key: Bytes,
+}
+/// SINTER/SUNION/SDIFF
+#[derive(Debug)]
+pub struct CommandSetOperation {
+    operation: SetOperation,
+    keys: Vec<Bytes>,
+}
+/// SINTERSTORE/SUNIONSTORE/SDIFFSTORE
+#[derive(Debug)]
+pub struct CommandSetOperationStore {
+    operation: SetOperation,
+    destination: Bytes,
+    keys: Vec<Bytes>,
+}
+
+/// 成员按字典序排列，保证回复稳定
+fn members_reply(members: impl IntoIterator<Item = Bytes>) -> RespFrame {
+    let mut members = members.into_iter().collect::<Vec<_>>();
+    members.sort_unstable();
+    RespSet::new(members.into_iter().map(RespFrame::from).collect()).into()
+}
+
+/// 计算多个集合的交集/并集/差集，不存在的 key 视为空集合
+fn set_operation(
+    backend: &Backend,
+    operation: SetOperation,
+    keys: &[Bytes],
+) -> Result<HashSet<Bytes>, WrongType> {
+    let sets = keys
+        .iter()
+        .map(|key| {
+            Ok(backend
+                .set_read(key, |set| set.clone())?
+                .unwrap_or_default())
+        })
+        .collect::<Result<Vec<_>, WrongType>>()?;
+    let mut sets = sets.into_iter();
+    let first = sets.next().unwrap_or_default();
+    Ok(sets.fold(first, |acc, set| match operation {
+        SetOperation::Inter => acc.intersection(&set).cloned().collect(),
+        SetOperation::Union => acc.union(&set).cloned().collect(),
+        SetOperation::Diff => acc.difference(&set).cloned().collect(),
+    }))
+}
+
+impl CommandExecutor for CommandSAdd {
+    fn execute(self, backend: &Backend) -> RespFrame {
+        match backend.set_add(self.key, self.members) {
+            Ok(added) => RespInteger::new(added as i64).into(),
+            Err(e) => e.into(),
+        }
+    }
+}
+impl CommandExecutor for CommandSRem {
+    fn execute(self, backend: &Backend) -> RespFrame {
+        match backend.set_remove(&self.key, &self.members) {
+            Ok(removed) => RespInteger::new(removed as i64).into(),
+            Err(e) => e.into(),
+        }
+    }
+}
+impl CommandExecutor for CommandSMembers {
+    fn execute(self, backend: &Backend) -> RespFrame {
+        match backend.set_read(&self.key, |set| set.iter().cloned().collect::<Vec<_>>()) {
+            Ok(members) => members_reply(members.unwrap_or_default()),
+            Err(e) => e.into(),
+        }
+    }
+}
+impl CommandExecutor for CommandSIsMember {
+    fn execute(self, backend: &Backend) -> RespFrame {
+        match backend.set_read(&self.key, |set| set.contains(&self.member)) {
+            Ok(found) => RespInteger::new(found.unwrap_or(false) as i64).into(),
+            Err(e) => e.into(),
+        }
+    }
+}
+impl CommandExecutor for CommandSCard {
+    fn execute(self, backend: &Backend) -> RespFrame {
+        match backend.set_read(&self.key, |set| set.len()) {
+            Ok(len) => RespInteger::new(len.unwrap_or(0) as i64).into(),
+            Err(e) => e.into(),
+        }
+    }
+}
+impl CommandExecutor for CommandSetOperation {
+    fn execute(self, backend: &Backend) -> RespFrame {
+        match set_operation(backend, self.operation, &self.keys) {
+            Ok(members) => members_reply(members),
+            Err(e) => e.into(),
+        }
+    }
+}
+impl CommandExecutor for CommandSetOperationStore {
+    /// 读取源集合到写入 destination 之间需要独占键空间，由 execute_request 持有 lock_exclusive
+    fn execute(self, backend: &Backend) -> RespFrame {
+        match set_operation(backend, self.operation, &self.keys) {
+            Ok(members) => {
+                RespInteger::new(backend.set_store(self.destination, members) as i64).into()
+            }
+            Err(e) => e.into(),
+        }
+    }
+}
+
+/// 解析 key member [member ...]
+fn parse_members(
+    value: &RespArray,
+    name: &'static str,
+) -> Result<(Bytes, Vec<Bytes>), CommandError> {
+    let args = command_args(value)?;
+    let key = extract_bytes(args[0], name)?;
+    let members = args[1..]
+        .iter()
+        .map(|arg| extract_bytes(arg, name))
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok((key, members))
+}
+
+impl TryFrom<RespArray> for CommandSAdd {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        let (key, members) = parse_members(&value, "SADD")?;
+        Ok(CommandSAdd { key, members })
+    }
+}
+impl TryFrom<RespArray> for CommandSRem {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        let (key, members) = parse_members(&value, "SREM")?;
+        Ok(CommandSRem { key, members })
+    }
+}
+impl TryFrom<RespArray> for CommandSMembers {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        let args = command_args(&value)?;
+        Ok(CommandSMembers {
+            key: extract_bytes(args[0], "SMEMBERS")?,
+        })
+    }
+}
+impl TryFrom<RespArray> for CommandSIsMember {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        let args = command_args(&value)?;
+        Ok(CommandSIsMember {
+            key: extract_bytes(args[0], "SISMEMBER")?,
+            member: extract_bytes(args[1], "SISMEMBER")?,
+        })
+    }
+}
+impl TryFrom<RespArray> for CommandSCard {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        let args = command_args(&value)?;
+        Ok(CommandSCard {
+            key: extract_bytes(args[0], "SCARD")?,
+        })
+    }
+}
+impl TryFrom<RespArray> for CommandSetOperation {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        let (name, operation) = match value.as_ref().and_then(|elements| elements.first()) {
+            Some(RespFrame::BulkString(BulkString {
+                content: Some(name),
+            })) => match name.to_ascii_lowercase().as_slice() {
+                b"sinter" => ("SINTER", SetOperation::Inter),
+                b"sunion" => ("SUNION", SetOperation::Union),
+                b"sdiff" => ("SDIFF", SetOperation::Diff),
+                _ => {
+                    return Err(CommandError::InvalidCommand(format!(
+                        "unknown set operation: {:?}",
+                        name
+                    )));
+                }
+            },
+            _ => {
+                return Err(CommandError::InvalidCommand(
+                    "set operation expect bulk string".to_string(),
+                ));
+            }
+        };
+        let args = command_args(&value)?;
+        let keys = args
+            .iter()
+            .map(|arg| extract_bytes(arg, name))
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(CommandSetOperation { operation, keys })
+    }
+}
+impl TryFrom<RespArray> for CommandSetOperationStore {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        let (name, operation) = match value.as_ref().and_then(|elements| elements.first()) {
+            Some(RespFrame::BulkString(BulkString {
+                content: Some(name),
+            })) => match name.to_ascii_lowercase().as_slice() {
+                b"sinterstore" => ("SINTERSTORE", SetOperation::Inter),
+                b"sunionstore" => ("SUNIONSTORE", SetOperation::Union),
+                b"sdiffstore" => ("SDIFFSTORE", SetOperation::Diff),
+                _ => {
+                    return Err(CommandError::InvalidCommand(format!(
+                        "unknown set operation: {:?}",
+                        name
+                    )));
+                }
+            },
+            _ => {
+                return Err(CommandError::InvalidCommand(
+                    "set operation expect bulk string".to_string(),
+                ));
+            }
+        };
+        let args = command_args(&value)?;
+        let destination = extract_bytes(args[0], name)?;
+        let keys = args[1..]
+            .iter()
+            .map(|arg| extract_bytes(arg, name))
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(CommandSetOperationStore {
+            operation,
+            destination,
+            keys,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::BytesMut;
+
+    use super::*;
+    use crate::resp::RespDecode;
+
+    fn smembers(backend: &Backend, key: &'static str) -> RespFrame {
+        CommandSMembers {
+            key: Bytes::from(key),
+        }
+        .execute(backend)
+    }
+    fn set_of(members: &[&str]) -> RespFrame {
+        RespSet::new(members.iter().map(|member| (*member).into()).collect()).into()
+    }
+
+    #[test]
+    fn test_command_set_try_from() -> Result<(), CommandError> {
+        let mut buf =
+            BytesMut::from("*4\r\n$4\r\nsadd\r\n$3\r\nset\r\n$5\r\nhello\r\n$5\r\nworld\r\n");
+        let cmd = CommandSAdd::try_from(RespArray::decode(&mut buf)?)?;
+        assert_eq!(cmd.key, "set");
+        assert_eq!(cmd.members, vec!["hello", "world"]);
+
+        let mut buf = BytesMut::from("*3\r\n$6\r\nSUNION\r\n$2\r\ns1\r\n$2\r\ns2\r\n");
+        let cmd = CommandSetOperation::try_from(RespArray::decode(&mut buf)?)?;
+        assert_eq!(cmd.operation, SetOperation::Union);
+        assert_eq!(cmd.keys, vec!["s1", "s2"]);
+
+        let mut buf =
+            BytesMut::from("*4\r\n$10\r\nsdiffstore\r\n$3\r\ndst\r\n$2\r\ns1\r\n$2\r\ns2\r\n");
+        let cmd = CommandSetOperationStore::try_from(RespArray::decode(&mut buf)?)?;
+        assert_eq!(cmd.operation, SetOperation::Diff);
+        assert_eq!(cmd.destination, "dst");
+        Ok(())
+    }
+    #[test]
+    fn test_set_commands() {
+        let backend = Backend::new();
+        let cmd = CommandSAdd {
+            key: "set".into(),
+            members: vec!["b".into(), "a".into(), "b".into()],
+        };
+        assert_eq!(cmd.execute(&backend), RespInteger::new(2).into());
+        assert_eq!(smembers(&backend, "set"), set_of(&["a", "b"]));
+
+        let cmd = CommandSIsMember {
+            key: "set".into(),
+            member: "a".into(),
+        };
+        assert_eq!(cmd.execute(&backend), RespInteger::new(1).into());
+        let cmd = CommandSRem {
+            key: "set".into(),
+            members: vec!["a".into(), "c".into()],
+        };
+        assert_eq!(cmd.execute(&backend), RespInteger::new(1).into());
+        let cmd = CommandSCard { key: "set".into() };
+        assert_eq!(cmd.execute(&backend), RespInteger::new(1).into());
+        assert_eq!(smembers(&backend, "missing"), set_of(&[]));
+    }
+    #[test]
+    fn test_set_operation_commands() {
+        let backend = Backend::new();
+        backend
+            .set_add("s1".into(), vec!["a".into(), "b".into(), "c".into()])
+            .unwrap();
+        backend
+            .set_add("s2".into(), vec!["b".into(), "d".into()])
+            .unwrap();
+        let keys = vec!["s1".into(), "s2".into()];
+        let cmd = CommandSetOperation {
+            operation: SetOperation::Inter,
+            keys: keys.clone(),
+        };
+        assert_eq!(cmd.execute(&backend), set_of(&["b"]));
+        let cmd = CommandSetOperation {
+            operation: SetOperation::Union,
+            keys: keys.clone(),
+        };
+        assert_eq!(cmd.execute(&backend), set_of(&["a", "b", "c", "d"]));
+        let cmd = CommandSetOperation {
+            operation: SetOperation::Diff,
+            keys: keys.clone(),
+        };
+        assert_eq!(cmd.execute(&backend), set_of(&["a", "c"]));
+
+        let cmd = CommandSetOperationStore {
+            operation: SetOperation::Diff,
+            destination: "dst".into(),
+            keys: keys.clone(),
+        };
+        assert_eq!(cmd.execute(&backend), RespInteger::new(2).into());
+        assert_eq!(smembers(&backend, "dst"), set_of(&["a", "c"]));
+
+        // 结果为空时删除 destination
+        let cmd = CommandSetOperationStore {
+            operation: SetOperation::Inter,
+            destination: "dst".into(),
+            keys: vec!["s1".into(), "missing".into()],
+        };
+        assert_eq!(cmd.execute(&backend), RespInteger::new(0).into());
+        assert!(!backend.contains_key(b"dst"));
+    }
+}
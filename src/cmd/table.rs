@@ -0,0 +1,1005 @@
+use std::{collections::HashMap, sync::LazyLock};
+
+use super::*;
+use crate::resp::{RespInteger, RespMap};
+
+/// 写命令中不短于该长度的 bulk 参数不拷贝，直接共享读缓冲区，与 Redis 的 PROTO_MBULK_BIG_ARG 相同
+const SHARED_ARG_MIN_LEN: usize = 32 * 1024;
+
+/// 命令标志，含义与 Redis COMMAND 输出的 flags 相同
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CommandFlag {
+    /// 会修改键空间
+    Write,
+    /// 只读取数据
+    ReadOnly,
+    /// 可能增加内存占用
+    DenyOom,
+    /// 时间复杂度 O(1) 或 O(log(N))
+    Fast,
+    /// 可能阻塞连接
+    Blocking,
+    /// 管理命令
+    Admin,
+    NoScript,
+    /// 加载数据期间也可以执行
+    Loading,
+    /// 数据过期时也可以执行
+    Stale,
+    /// 认证前也可以执行
+    NoAuth,
+}
+
+impl CommandFlag {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CommandFlag::Write => "write",
+            CommandFlag::ReadOnly => "readonly",
+            CommandFlag::DenyOom => "denyoom",
+            CommandFlag::Fast => "fast",
+            CommandFlag::Blocking => "blocking",
+            CommandFlag::Admin => "admin",
+            CommandFlag::NoScript => "noscript",
+            CommandFlag::Loading => "loading",
+            CommandFlag::Stale => "stale",
+            CommandFlag::NoAuth => "no_auth",
+        }
+    }
+}
+
+/// 命令所属的分组，对应 COMMAND DOCS 中的 group
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CommandGroup {
+    Generic,
+    String,
+    Bitmap,
+    List,
+    Set,
+    SortedSet,
+    Hash,
+    Connection,
+    Server,
+}
+
+impl CommandGroup {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CommandGroup::Generic => "generic",
+            CommandGroup::String => "string",
+            CommandGroup::Bitmap => "bitmap",
+            CommandGroup::List => "list",
+            CommandGroup::Set => "set",
+            CommandGroup::SortedSet => "sorted-set",
+            CommandGroup::Hash => "hash",
+            CommandGroup::Connection => "connection",
+            CommandGroup::Server => "server",
+        }
+    }
+    /// 分组对应的 ACL 分类
+    fn acl_category(&self) -> Option<&'static str> {
+        match self {
+            CommandGroup::Generic => Some("@keyspace"),
+            CommandGroup::String => Some("@string"),
+            CommandGroup::Bitmap => Some("@bitmap"),
+            CommandGroup::List => Some("@list"),
+            CommandGroup::Set => Some("@set"),
+            CommandGroup::SortedSet => Some("@sortedset"),
+            CommandGroup::Hash => Some("@hash"),
+            CommandGroup::Connection => Some("@connection"),
+            CommandGroup::Server => None,
+        }
+    }
+}
+
+/// 命令表中的一项
+#[derive(Debug)]
+pub struct CommandSpec {
+    /// 小写命令名
+    pub name: &'static str,
+    /// 参数个数（包括命令名），负数表示至少 -arity 个
+    pub arity: i64,
+    pub group: CommandGroup,
+    pub flags: &'static [CommandFlag],
+    /// 第一个 key 的位置，没有 key 时为 0
+    pub first_key: i64,
+    /// 最后一个 key 的位置，负数从末尾倒数
+    pub last_key: i64,
+    /// 相邻 key 的间隔
+    pub key_step: i64,
+    /// 一句话说明，用于 COMMAND DOCS
+    pub summary: &'static str,
+    parse: fn(RespArray) -> Result<Command, CommandError>,
+}
+
+impl CommandSpec {
+    const fn new(
+        name: &'static str,
+        arity: i64,
+        group: CommandGroup,
+        parse: fn(RespArray) -> Result<Command, CommandError>,
+    ) -> Self {
+        CommandSpec {
+            name,
+            arity,
+            group,
+            flags: &[],
+            first_key: 0,
+            last_key: 0,
+            key_step: 0,
+            summary: "",
+            parse,
+        }
+    }
+    const fn flags(mut self, flags: &'static [CommandFlag]) -> Self {
+        self.flags = flags;
+        self
+    }
+    const fn keys(mut self, first_key: i64, last_key: i64, key_step: i64) -> Self {
+        self.first_key = first_key;
+        self.last_key = last_key;
+        self.key_step = key_step;
+        self
+    }
+    const fn summary(mut self, summary: &'static str) -> Self {
+        self.summary = summary;
+        self
+    }
+    pub fn has_flag(&self, flag: CommandFlag) -> bool {
+        self.flags.contains(&flag)
+    }
+    /// 是否会修改键空间
+    pub fn is_write(&self) -> bool {
+        self.has_flag(CommandFlag::Write)
+    }
+    /// 按 arity 校验参数个数（包括命令名）
+    pub fn check_arity(&self, argc: usize) -> Result<(), CommandError> {
+        let argc = argc as i64;
+        if (self.arity > 0 && argc != self.arity) || argc < -self.arity {
+            return Err(CommandError::InvalidNumberOfArguments(format!(
+                "wrong number of arguments for '{}' command",
+                self.name
+            )));
+        }
+        Ok(())
+    }
+    /// 解析为具体命令，调用方需要先确认命令名对应本项
+    /// 写命令的参数会长期保存在 backend 中，小参数先拷贝出来，避免小 key 一直占着整个读缓冲区；
+    /// 大参数本身占了缓冲区的大部分，拷贝的代价更高，保持零拷贝
+    pub fn parse(&self, mut value: RespArray) -> Result<Command, CommandError> {
+        if self.is_write()
+            && let Some(elements) = value.elements.as_mut()
+        {
+            for element in elements {
+                if let RespFrame::BulkString(BulkString {
+                    content: Some(bytes),
+                }) = element
+                    && bytes.len() < SHARED_ARG_MIN_LEN
+                {
+                    *bytes = Bytes::copy_from_slice(bytes);
+                }
+            }
+        }
+        (self.parse)(value)
+    }
+    /// ACL 分类，由读写、分组和标志推导
+    pub fn acl_categories(&self) -> Vec<&'static str> {
+        let mut categories = Vec::new();
+        if self.has_flag(CommandFlag::Write) {
+            categories.push("@write");
+        }
+        if self.has_flag(CommandFlag::ReadOnly) {
+            categories.push("@read");
+        }
+        categories.extend(self.group.acl_category());
+        categories.push(if self.has_flag(CommandFlag::Fast) {
+            "@fast"
+        } else {
+            "@slow"
+        });
+        if self.has_flag(CommandFlag::Blocking) {
+            categories.push("@blocking");
+        }
+        if self.has_flag(CommandFlag::Admin) {
+            categories.extend(["@admin", "@dangerous"]);
+        }
+        categories
+    }
+    /// COMMAND INFO 中的一项：
+    /// name arity flags first-key last-key step acl-categories tips key-specs subcommands
+    pub fn info(&self) -> RespFrame {
+        let simple_array = |items: Vec<&str>| -> RespFrame {
+            RespArray::from_vec(
+                items
+                    .into_iter()
+                    .map(|item| SimpleString::new(item).into())
+                    .collect(),
+            )
+            .into()
+        };
+        RespArray::from_vec(vec![
+            self.name.into(),
+            RespInteger::new(self.arity).into(),
+            simple_array(self.flags.iter().map(CommandFlag::as_str).collect()),
+            RespInteger::new(self.first_key).into(),
+            RespInteger::new(self.last_key).into(),
+            RespInteger::new(self.key_step).into(),
+            simple_array(self.acl_categories()),
+            RespArray::empty().into(),
+            RespArray::from_vec(self.key_specs()).into(),
+            RespArray::empty().into(),
+        ])
+        .into()
+    }
+    /// 键位置的描述，last_key 在 find_keys 中相对 first_key 计算
+    fn key_specs(&self) -> Vec<RespFrame> {
+        if self.first_key == 0 {
+            return vec![];
+        }
+        let mut begin = RespMap::default();
+        begin.insert("type".to_string(), "index".into());
+        let mut index = RespMap::default();
+        index.insert("index".to_string(), RespInteger::new(self.first_key).into());
+        begin.insert("spec".to_string(), index.into());
+
+        let mut find = RespMap::default();
+        find.insert("type".to_string(), "range".into());
+        let mut range = RespMap::default();
+        let last_key = if self.last_key < 0 {
+            self.last_key
+        } else {
+            self.last_key - self.first_key
+        };
+        range.insert("lastkey".to_string(), RespInteger::new(last_key).into());
+        range.insert(
+            "keystep".to_string(),
+            RespInteger::new(self.key_step).into(),
+        );
+        range.insert("limit".to_string(), RespInteger::new(0).into());
+        find.insert("spec".to_string(), range.into());
+
+        let flags = if self.is_write() { "RW" } else { "RO" };
+        let mut spec = RespMap::default();
+        spec.insert("begin_search".to_string(), begin.into());
+        spec.insert("find_keys".to_string(), find.into());
+        spec.insert(
+            "flags".to_string(),
+            RespArray::from_vec(vec![SimpleString::new(flags).into()]).into(),
+        );
+        vec![spec.into()]
+    }
+    /// COMMAND DOCS 中的一项
+    pub fn docs(&self) -> RespFrame {
+        let mut docs = RespMap::default();
+        docs.insert("summary".to_string(), self.summary.into());
+        docs.insert("group".to_string(), self.group.as_str().into());
+        docs.into()
+    }
+}
+
+use CommandFlag::*;
+
+/// 所有已注册的命令
+pub static COMMAND_TABLE: &[CommandSpec] = &[
+    CommandSpec::new("ping", 1, CommandGroup::Connection, |_| Ok(Command::Ping))
+        .flags(&[Fast, Stale])
+        .summary("Returns the server's liveliness response."),
+    CommandSpec::new("hello", -1, CommandGroup::Connection, |v| {
+        CommandHello::try_from(v).map(Command::Hello)
+    })
+    .flags(&[NoScript, Loading, Stale, Fast, NoAuth])
+    .summary("Handshakes with the Redis server."),
+    CommandSpec::new("command", -1, CommandGroup::Server, |v| {
+        CommandCommand::try_from(v).map(Command::Command)
+    })
+    .flags(&[Loading, Stale])
+    .summary("Returns detailed information about all commands."),
+    CommandSpec::new("get", 2, CommandGroup::String, |v| {
+        CommandGet::try_from(v).map(Command::Get)
+    })
+    .flags(&[ReadOnly, Fast])
+    .keys(1, 1, 1)
+    .summary("Returns the string value of a key."),
+    CommandSpec::new("set", -3, CommandGroup::String, |v| {
+        CommandSet::try_from(v).map(Command::Set)
+    })
+    .flags(&[Write, DenyOom])
+    .keys(1, 1, 1)
+    .summary("Sets the string value of a key, ignoring its type."),
+    CommandSpec::new("append", 3, CommandGroup::String, |v| {
+        CommandAppend::try_from(v).map(Command::Append)
+    })
+    .flags(&[Write, DenyOom, Fast])
+    .keys(1, 1, 1)
+    .summary("Appends a string to the value of a key. Creates the key if it doesn't exist."),
+    CommandSpec::new("strlen", 2, CommandGroup::String, |v| {
+        CommandStrLen::try_from(v).map(Command::StrLen)
+    })
+    .flags(&[ReadOnly, Fast])
+    .keys(1, 1, 1)
+    .summary("Returns the length of a string value."),
+    CommandSpec::new("getrange", 4, CommandGroup::String, |v| {
+        CommandGetRange::try_from(v).map(Command::GetRange)
+    })
+    .flags(&[ReadOnly])
+    .keys(1, 1, 1)
+    .summary("Returns a substring of the string stored at a key."),
+    CommandSpec::new("setrange", 4, CommandGroup::String, |v| {
+        CommandSetRange::try_from(v).map(Command::SetRange)
+    })
+    .flags(&[Write, DenyOom])
+    .keys(1, 1, 1)
+    .summary("Overwrites a part of a string value with another by an offset."),
+    CommandSpec::new("getset", 3, CommandGroup::String, |v| {
+        CommandGetSet::try_from(v).map(Command::GetSet)
+    })
+    .flags(&[Write, DenyOom, Fast])
+    .keys(1, 1, 1)
+    .summary("Returns the previous string value of a key after setting it to a new value."),
+    CommandSpec::new("getdel", 2, CommandGroup::String, |v| {
+        CommandGetDel::try_from(v).map(Command::GetDel)
+    })
+    .flags(&[Write, Fast])
+    .keys(1, 1, 1)
+    .summary("Returns the string value of a key after deleting the key."),
+    CommandSpec::new("getex", -2, CommandGroup::String, |v| {
+        CommandGetEx::try_from(v).map(Command::GetEx)
+    })
+    .flags(&[Write, Fast])
+    .keys(1, 1, 1)
+    .summary("Returns the string value of a key after setting its expiration time."),
+    CommandSpec::new("mset", -3, CommandGroup::String, |v| {
+        CommandMSet::try_from(v).map(Command::MSet)
+    })
+    .flags(&[Write, DenyOom])
+    .keys(1, -1, 2)
+    .summary("Atomically creates or modifies the string values of one or more keys."),
+    CommandSpec::new("mget", -2, CommandGroup::String, |v| {
+        CommandMGet::try_from(v).map(Command::MGet)
+    })
+    .flags(&[ReadOnly, Fast])
+    .keys(1, -1, 1)
+    .summary("Atomically returns the string values of one or more keys."),
+    CommandSpec::new("msetnx", -3, CommandGroup::String, |v| {
+        CommandMSetNx::try_from(v).map(Command::MSetNx)
+    })
+    .flags(&[Write, DenyOom])
+    .keys(1, -1, 2)
+    .summary("Atomically sets multiple keys only when none of them exist."),
+    CommandSpec::new("setnx", 3, CommandGroup::String, |v| {
+        CommandSetNx::try_from(v).map(Command::SetNx)
+    })
+    .flags(&[Write, DenyOom, Fast])
+    .keys(1, 1, 1)
+    .summary("Set the string value of a key only when the key doesn't exist."),
+    CommandSpec::new("incr", 2, CommandGroup::String, |v| {
+        CommandIncr::try_from(v).map(Command::Incr)
+    })
+    .flags(&[Write, DenyOom, Fast])
+    .keys(1, 1, 1)
+    .summary("Increments the integer value of a key by one."),
+    CommandSpec::new("decr", 2, CommandGroup::String, |v| {
+        CommandDecr::try_from(v).map(Command::Decr)
+    })
+    .flags(&[Write, DenyOom, Fast])
+    .keys(1, 1, 1)
+    .summary("Decrements the integer value of a key by one."),
+    CommandSpec::new("incrby", 3, CommandGroup::String, |v| {
+        CommandIncrBy::try_from(v).map(Command::IncrBy)
+    })
+    .flags(&[Write, DenyOom, Fast])
+    .keys(1, 1, 1)
+    .summary("Increments the integer value of a key by a number."),
+    CommandSpec::new("decrby", 3, CommandGroup::String, |v| {
+        CommandDecrBy::try_from(v).map(Command::DecrBy)
+    })
+    .flags(&[Write, DenyOom, Fast])
+    .keys(1, 1, 1)
+    .summary("Decrements a number from the integer value of a key."),
+    CommandSpec::new("incrbyfloat", 3, CommandGroup::String, |v| {
+        CommandIncrByFloat::try_from(v).map(Command::IncrByFloat)
+    })
+    .flags(&[Write, DenyOom, Fast])
+    .keys(1, 1, 1)
+    .summary("Increment the floating point value of a key by a number."),
+    CommandSpec::new("setbit", 4, CommandGroup::Bitmap, |v| {
+        CommandSetBit::try_from(v).map(Command::SetBit)
+    })
+    .flags(&[Write, DenyOom])
+    .keys(1, 1, 1)
+    .summary("Sets or clears the bit at offset of the string value."),
+    CommandSpec::new("getbit", 3, CommandGroup::Bitmap, |v| {
+        CommandGetBit::try_from(v).map(Command::GetBit)
+    })
+    .flags(&[ReadOnly, Fast])
+    .keys(1, 1, 1)
+    .summary("Returns a bit value by offset."),
+    CommandSpec::new("bitcount", -2, CommandGroup::Bitmap, |v| {
+        CommandBitCount::try_from(v).map(Command::BitCount)
+    })
+    .flags(&[ReadOnly])
+    .keys(1, 1, 1)
+    .summary("Counts the number of set bits (population counting) in a string."),
+    CommandSpec::new("bitpos", -3, CommandGroup::Bitmap, |v| {
+        CommandBitPos::try_from(v).map(Command::BitPos)
+    })
+    .flags(&[ReadOnly])
+    .keys(1, 1, 1)
+    .summary("Finds the first set (1) or clear (0) bit in a string."),
+    CommandSpec::new("bitop", -4, CommandGroup::Bitmap, |v| {
+        CommandBitOp::try_from(v).map(Command::BitOp)
+    })
+    .flags(&[Write, DenyOom])
+    .keys(2, -1, 1)
+    .summary("Performs bitwise operations on multiple strings, and stores the result."),
+    CommandSpec::new("bitfield", -2, CommandGroup::Bitmap, |v| {
+        CommandBitField::try_from(v).map(Command::BitField)
+    })
+    .flags(&[Write, DenyOom])
+    .keys(1, 1, 1)
+    .summary("Performs arbitrary bitfield integer operations on strings."),
+    CommandSpec::new("hget", 3, CommandGroup::Hash, |v| {
+        CommandHGet::try_from(v).map(Command::HGet)
+    })
+    .flags(&[ReadOnly, Fast])
+    .keys(1, 1, 1)
+    .summary("Returns the value of a field in a hash."),
+    CommandSpec::new("hgetall", 2, CommandGroup::Hash, |v| {
+        CommandHGetAll::try_from(v).map(Command::HGetAll)
+    })
+    .flags(&[ReadOnly])
+    .keys(1, 1, 1)
+    .summary("Returns all fields and values in a hash."),
+    CommandSpec::new("hset", -4, CommandGroup::Hash, |v| {
+        CommandHSet::try_from(v).map(Command::HSet)
+    })
+    .flags(&[Write, DenyOom, Fast])
+    .keys(1, 1, 1)
+    .summary("Creates or modifies the value of a field in a hash."),
+    CommandSpec::new("hsetnx", 4, CommandGroup::Hash, |v| {
+        CommandHSetNx::try_from(v).map(Command::HSetNx)
+    })
+    .flags(&[Write, DenyOom, Fast])
+    .keys(1, 1, 1)
+    .summary("Sets the value of a field in a hash only when the field doesn't exist."),
+    CommandSpec::new("hmget", -3, CommandGroup::Hash, |v| {
+        CommandHMGet::try_from(v).map(Command::HMGet)
+    })
+    .flags(&[ReadOnly, Fast])
+    .keys(1, 1, 1)
+    .summary("Returns the values of all fields in a hash."),
+    CommandSpec::new("hdel", -3, CommandGroup::Hash, |v| {
+        CommandHDel::try_from(v).map(Command::HDel)
+    })
+    .flags(&[Write, Fast])
+    .keys(1, 1, 1)
+    .summary("Deletes one or more fields and their values from a hash."),
+    CommandSpec::new("hexists", 3, CommandGroup::Hash, |v| {
+        CommandHExists::try_from(v).map(Command::HExists)
+    })
+    .flags(&[ReadOnly, Fast])
+    .keys(1, 1, 1)
+    .summary("Determines whether a field exists in a hash."),
+    CommandSpec::new("hlen", 2, CommandGroup::Hash, |v| {
+        CommandHLen::try_from(v).map(Command::HLen)
+    })
+    .flags(&[ReadOnly, Fast])
+    .keys(1, 1, 1)
+    .summary("Returns the number of fields in a hash."),
+    CommandSpec::new("hkeys", 2, CommandGroup::Hash, |v| {
+        CommandHKeys::try_from(v).map(Command::HKeys)
+    })
+    .flags(&[ReadOnly])
+    .keys(1, 1, 1)
+    .summary("Returns all fields in a hash."),
+    CommandSpec::new("hvals", 2, CommandGroup::Hash, |v| {
+        CommandHVals::try_from(v).map(Command::HVals)
+    })
+    .flags(&[ReadOnly])
+    .keys(1, 1, 1)
+    .summary("Returns all values in a hash."),
+    CommandSpec::new("hincrby", 4, CommandGroup::Hash, |v| {
+        CommandHIncrBy::try_from(v).map(Command::HIncrBy)
+    })
+    .flags(&[Write, DenyOom, Fast])
+    .keys(1, 1, 1)
+    .summary("Increments the integer value of a field in a hash by a number."),
+    CommandSpec::new("hincrbyfloat", 4, CommandGroup::Hash, |v| {
+        CommandHIncrByFloat::try_from(v).map(Command::HIncrByFloat)
+    })
+    .flags(&[Write, DenyOom, Fast])
+    .keys(1, 1, 1)
+    .summary("Increments the floating point value of a field by a number."),
+    CommandSpec::new("hstrlen", 3, CommandGroup::Hash, |v| {
+        CommandHStrLen::try_from(v).map(Command::HStrLen)
+    })
+    .flags(&[ReadOnly, Fast])
+    .keys(1, 1, 1)
+    .summary("Returns the length of the value of a field."),
+    CommandSpec::new("hrandfield", -2, CommandGroup::Hash, |v| {
+        CommandHRandField::try_from(v).map(Command::HRandField)
+    })
+    .flags(&[ReadOnly])
+    .keys(1, 1, 1)
+    .summary("Returns one or more random fields from a hash."),
+    CommandSpec::new("expire", -3, CommandGroup::Generic, |v| {
+        CommandExpire::try_from(v).map(Command::Expire)
+    })
+    .flags(&[Write, Fast])
+    .keys(1, 1, 1)
+    .summary("Sets the expiration time of a key in seconds."),
+    CommandSpec::new("pexpire", -3, CommandGroup::Generic, |v| {
+        CommandPExpire::try_from(v).map(Command::PExpire)
+    })
+    .flags(&[Write, Fast])
+    .keys(1, 1, 1)
+    .summary("Sets the expiration time of a key in milliseconds."),
+    CommandSpec::new("expireat", -3, CommandGroup::Generic, |v| {
+        CommandExpireAt::try_from(v).map(Command::ExpireAt)
+    })
+    .flags(&[Write, Fast])
+    .keys(1, 1, 1)
+    .summary("Sets the expiration time of a key to a Unix timestamp."),
+    CommandSpec::new("pexpireat", -3, CommandGroup::Generic, |v| {
+        CommandPExpireAt::try_from(v).map(Command::PExpireAt)
+    })
+    .flags(&[Write, Fast])
+    .keys(1, 1, 1)
+    .summary("Sets the expiration time of a key to a Unix milliseconds timestamp."),
+    CommandSpec::new("ttl", 2, CommandGroup::Generic, |v| {
+        CommandTtl::try_from(v).map(Command::Ttl)
+    })
+    .flags(&[ReadOnly, Fast])
+    .keys(1, 1, 1)
+    .summary("Returns the expiration time in seconds of a key."),
+    CommandSpec::new("pttl", 2, CommandGroup::Generic, |v| {
+        CommandPTtl::try_from(v).map(Command::PTtl)
+    })
+    .flags(&[ReadOnly, Fast])
+    .keys(1, 1, 1)
+    .summary("Returns the expiration time in milliseconds of a key."),
+    CommandSpec::new("persist", 2, CommandGroup::Generic, |v| {
+        CommandPersist::try_from(v).map(Command::Persist)
+    })
+    .flags(&[Write, Fast])
+    .keys(1, 1, 1)
+    .summary("Removes the expiration time of a key."),
+    CommandSpec::new("lpush", -3, CommandGroup::List, |v| {
+        CommandLPush::try_from(v).map(Command::LPush)
+    })
+    .flags(&[Write, DenyOom, Fast])
+    .keys(1, 1, 1)
+    .summary("Prepends one or more elements to a list. Creates the key if it doesn't exist."),
+    CommandSpec::new("rpush", -3, CommandGroup::List, |v| {
+        CommandRPush::try_from(v).map(Command::RPush)
+    })
+    .flags(&[Write, DenyOom, Fast])
+    .keys(1, 1, 1)
+    .summary("Appends one or more elements to a list. Creates the key if it doesn't exist."),
+    CommandSpec::new("lpop", -2, CommandGroup::List, |v| {
+        CommandLPop::try_from(v).map(Command::LPop)
+    })
+    .flags(&[Write, Fast])
+    .keys(1, 1, 1)
+    .summary("Returns the first elements in a list after removing it."),
+    CommandSpec::new("rpop", -2, CommandGroup::List, |v| {
+        CommandRPop::try_from(v).map(Command::RPop)
+    })
+    .flags(&[Write, Fast])
+    .keys(1, 1, 1)
+    .summary("Returns and removes the last elements of a list."),
+    CommandSpec::new("lrange", 4, CommandGroup::List, |v| {
+        CommandLRange::try_from(v).map(Command::LRange)
+    })
+    .flags(&[ReadOnly])
+    .keys(1, 1, 1)
+    .summary("Returns a range of elements from a list."),
+    CommandSpec::new("llen", 2, CommandGroup::List, |v| {
+        CommandLLen::try_from(v).map(Command::LLen)
+    })
+    .flags(&[ReadOnly, Fast])
+    .keys(1, 1, 1)
+    .summary("Returns the length of a list."),
+    CommandSpec::new("lindex", 3, CommandGroup::List, |v| {
+        CommandLIndex::try_from(v).map(Command::LIndex)
+    })
+    .flags(&[ReadOnly])
+    .keys(1, 1, 1)
+    .summary("Returns an element from a list by its index."),
+    CommandSpec::new("lset", 4, CommandGroup::List, |v| {
+        CommandLSet::try_from(v).map(Command::LSet)
+    })
+    .flags(&[Write, DenyOom])
+    .keys(1, 1, 1)
+    .summary("Sets the value of an element in a list by its index."),
+    CommandSpec::new("lrem", 4, CommandGroup::List, |v| {
+        CommandLRem::try_from(v).map(Command::LRem)
+    })
+    .flags(&[Write])
+    .keys(1, 1, 1)
+    .summary("Removes elements from a list."),
+    CommandSpec::new("ltrim", 4, CommandGroup::List, |v| {
+        CommandLTrim::try_from(v).map(Command::LTrim)
+    })
+    .flags(&[Write])
+    .keys(1, 1, 1)
+    .summary("Removes elements from both ends a list."),
+    CommandSpec::new("lmove", 5, CommandGroup::List, |v| {
+        CommandLMove::try_from(v).map(Command::LMove)
+    })
+    .flags(&[Write, DenyOom])
+    .keys(1, 2, 1)
+    .summary("Returns an element after popping it from one list and pushing it to another."),
+    CommandSpec::new("blpop", -3, CommandGroup::List, |v| {
+        CommandBLPop::try_from(v).map(Command::BLPop)
+    })
+    .flags(&[Write, Blocking])
+    .keys(1, -2, 1)
+    .summary("Removes and returns the first element in a list."),
+    CommandSpec::new("brpop", -3, CommandGroup::List, |v| {
+        CommandBRPop::try_from(v).map(Command::BRPop)
+    })
+    .flags(&[Write, Blocking])
+    .keys(1, -2, 1)
+    .summary("Removes and returns the last element in a list."),
+    CommandSpec::new("blmove", 6, CommandGroup::List, |v| {
+        CommandBLMove::try_from(v).map(Command::BLMove)
+    })
+    .flags(&[Write, DenyOom, Blocking])
+    .keys(1, 2, 1)
+    .summary("Pops an element from a list, pushes it to another list and returns it."),
+    CommandSpec::new("sadd", -3, CommandGroup::Set, |v| {
+        CommandSAdd::try_from(v).map(Command::SAdd)
+    })
+    .flags(&[Write, DenyOom, Fast])
+    .keys(1, 1, 1)
+    .summary("Adds one or more members to a set. Creates the key if it doesn't exist."),
+    CommandSpec::new("srem", -3, CommandGroup::Set, |v| {
+        CommandSRem::try_from(v).map(Command::SRem)
+    })
+    .flags(&[Write, Fast])
+    .keys(1, 1, 1)
+    .summary("Removes one or more members from a set."),
+    CommandSpec::new("smembers", 2, CommandGroup::Set, |v| {
+        CommandSMembers::try_from(v).map(Command::SMembers)
+    })
+    .flags(&[ReadOnly])
+    .keys(1, 1, 1)
+    .summary("Returns all members of a set."),
+    CommandSpec::new("sismember", 3, CommandGroup::Set, |v| {
+        CommandSIsMember::try_from(v).map(Command::SIsMember)
+    })
+    .flags(&[ReadOnly, Fast])
+    .keys(1, 1, 1)
+    .summary("Determines whether a member belongs to a set."),
+    CommandSpec::new("scard", 2, CommandGroup::Set, |v| {
+        CommandSCard::try_from(v).map(Command::SCard)
+    })
+    .flags(&[ReadOnly, Fast])
+    .keys(1, 1, 1)
+    .summary("Returns the number of members in a set."),
+    CommandSpec::new("sinter", -2, CommandGroup::Set, |v| {
+        CommandSetOperation::try_from(v).map(Command::SetOperation)
+    })
+    .flags(&[ReadOnly])
+    .keys(1, -1, 1)
+    .summary("Returns the intersect of multiple sets."),
+    CommandSpec::new("sunion", -2, CommandGroup::Set, |v| {
+        CommandSetOperation::try_from(v).map(Command::SetOperation)
+    })
+    .flags(&[ReadOnly])
+    .keys(1, -1, 1)
+    .summary("Returns the union of multiple sets."),
+    CommandSpec::new("sdiff", -2, CommandGroup::Set, |v| {
+        CommandSetOperation::try_from(v).map(Command::SetOperation)
+    })
+    .flags(&[ReadOnly])
+    .keys(1, -1, 1)
+    .summary("Returns the difference of multiple sets."),
+    CommandSpec::new("sinterstore", -3, CommandGroup::Set, |v| {
+        CommandSetOperationStore::try_from(v).map(Command::SetOperationStore)
+    })
+    .flags(&[Write, DenyOom])
+    .keys(1, -1, 1)
+    .summary("Stores the intersect of multiple sets in a key."),
+    CommandSpec::new("sunionstore", -3, CommandGroup::Set, |v| {
+        CommandSetOperationStore::try_from(v).map(Command::SetOperationStore)
+    })
+    .flags(&[Write, DenyOom])
+    .keys(1, -1, 1)
+    .summary("Stores the union of multiple sets in a key."),
+    CommandSpec::new("sdiffstore", -3, CommandGroup::Set, |v| {
+        CommandSetOperationStore::try_from(v).map(Command::SetOperationStore)
+    })
+    .flags(&[Write, DenyOom])
+    .keys(1, -1, 1)
+    .summary("Stores the difference of multiple sets in a key."),
+    CommandSpec::new("zadd", -4, CommandGroup::SortedSet, |v| {
+        CommandZAdd::try_from(v).map(Command::ZAdd)
+    })
+    .flags(&[Write, DenyOom, Fast])
+    .keys(1, 1, 1)
+    .summary("Adds one or more members to a sorted set, or updates their scores."),
+    CommandSpec::new("zrange", -4, CommandGroup::SortedSet, |v| {
+        CommandZRange::try_from(v).map(Command::ZRange)
+    })
+    .flags(&[ReadOnly])
+    .keys(1, 1, 1)
+    .summary("Returns members in a sorted set within a range of indexes."),
+    CommandSpec::new("zrangebyscore", -4, CommandGroup::SortedSet, |v| {
+        CommandZRange::try_from(v).map(Command::ZRange)
+    })
+    .flags(&[ReadOnly])
+    .keys(1, 1, 1)
+    .summary("Returns members in a sorted set within a range of scores."),
+    CommandSpec::new("zrank", -3, CommandGroup::SortedSet, |v| {
+        CommandZRank::try_from(v).map(Command::ZRank)
+    })
+    .flags(&[ReadOnly, Fast])
+    .keys(1, 1, 1)
+    .summary("Returns the index of a member in a sorted set ordered by ascending scores."),
+    CommandSpec::new("zrevrank", -3, CommandGroup::SortedSet, |v| {
+        CommandZRank::try_from(v).map(Command::ZRank)
+    })
+    .flags(&[ReadOnly, Fast])
+    .keys(1, 1, 1)
+    .summary("Returns the index of a member in a sorted set ordered by descending scores."),
+    CommandSpec::new("zincrby", 4, CommandGroup::SortedSet, |v| {
+        CommandZIncrBy::try_from(v).map(Command::ZIncrBy)
+    })
+    .flags(&[Write, DenyOom, Fast])
+    .keys(1, 1, 1)
+    .summary("Increments the score of a member in a sorted set."),
+    CommandSpec::new("zrem", -3, CommandGroup::SortedSet, |v| {
+        CommandZRem::try_from(v).map(Command::ZRem)
+    })
+    .flags(&[Write, Fast])
+    .keys(1, 1, 1)
+    .summary("Removes one or more members from a sorted set."),
+    CommandSpec::new("zscore", 3, CommandGroup::SortedSet, |v| {
+        CommandZScore::try_from(v).map(Command::ZScore)
+    })
+    .flags(&[ReadOnly, Fast])
+    .keys(1, 1, 1)
+    .summary("Returns the score of a member in a sorted set."),
+    CommandSpec::new("zcard", 2, CommandGroup::SortedSet, |v| {
+        CommandZCard::try_from(v).map(Command::ZCard)
+    })
+    .flags(&[ReadOnly, Fast])
+    .keys(1, 1, 1)
+    .summary("Returns the number of members in a sorted set."),
+    CommandSpec::new("type", 2, CommandGroup::Generic, |v| {
+        CommandType::try_from(v).map(Command::Type)
+    })
+    .flags(&[ReadOnly, Fast])
+    .keys(1, 1, 1)
+    .summary("Determines the type of value stored at a key."),
+    CommandSpec::new("del", -2, CommandGroup::Generic, |v| {
+        CommandDel::try_from(v).map(Command::Del)
+    })
+    .flags(&[Write])
+    .keys(1, -1, 1)
+    .summary("Deletes one or more keys."),
+    CommandSpec::new("unlink", -2, CommandGroup::Generic, |v| {
+        CommandUnlink::try_from(v).map(Command::Unlink)
+    })
+    .flags(&[Write, Fast])
+    .keys(1, -1, 1)
+    .summary("Asynchronously deletes one or more keys."),
+    CommandSpec::new("exists", -2, CommandGroup::Generic, |v| {
+        CommandExists::try_from(v).map(Command::Exists)
+    })
+    .flags(&[ReadOnly, Fast])
+    .keys(1, -1, 1)
+    .summary("Determines whether one or more keys exist."),
+    CommandSpec::new("rename", 3, CommandGroup::Generic, |v| {
+        CommandRename::try_from(v).map(Command::Rename)
+    })
+    .flags(&[Write])
+    .keys(1, 2, 1)
+    .summary("Renames a key and overwrites the destination."),
+    CommandSpec::new("keys", 2, CommandGroup::Generic, |v| {
+        CommandKeys::try_from(v).map(Command::Keys)
+    })
+    .flags(&[ReadOnly])
+    .summary("Returns all key names that match a pattern."),
+    CommandSpec::new("randomkey", 1, CommandGroup::Generic, |v| {
+        CommandRandomKey::try_from(v).map(Command::RandomKey)
+    })
+    .flags(&[ReadOnly])
+    .summary("Returns a random key name from the database."),
+    CommandSpec::new("dbsize", 1, CommandGroup::Server, |v| {
+        CommandDbSize::try_from(v).map(Command::DbSize)
+    })
+    .flags(&[ReadOnly, Fast])
+    .summary("Returns the number of keys in the database."),
+    CommandSpec::new("scan", -2, CommandGroup::Generic, |v| {
+        CommandScan::try_from(v).map(Command::Scan)
+    })
+    .flags(&[ReadOnly])
+    .summary("Incrementally iterates over the keys in the database."),
+    CommandSpec::new("hscan", -3, CommandGroup::Hash, |v| {
+        CommandHScan::try_from(v).map(Command::HScan)
+    })
+    .flags(&[ReadOnly])
+    .keys(1, 1, 1)
+    .summary("Iterates over fields and values of a hash."),
+    CommandSpec::new("sscan", -3, CommandGroup::Set, |v| {
+        CommandSScan::try_from(v).map(Command::SScan)
+    })
+    .flags(&[ReadOnly])
+    .keys(1, 1, 1)
+    .summary("Iterates over members of a set."),
+    CommandSpec::new("zscan", -3, CommandGroup::SortedSet, |v| {
+        CommandZScan::try_from(v).map(Command::ZScan)
+    })
+    .flags(&[ReadOnly])
+    .keys(1, 1, 1)
+    .summary("Iterates over members and scores of a sorted set."),
+    CommandSpec::new("save", 1, CommandGroup::Server, |v| {
+        CommandSave::try_from(v).map(Command::Save)
+    })
+    .flags(&[Admin, NoScript])
+    .summary("Synchronously saves the database(s) to disk."),
+    CommandSpec::new("bgsave", 1, CommandGroup::Server, |v| {
+        CommandBgSave::try_from(v).map(Command::BgSave)
+    })
+    .flags(&[Admin, NoScript])
+    .summary("Asynchronously saves the database(s) to disk."),
+    CommandSpec::new("bgrewriteaof", 1, CommandGroup::Server, |v| {
+        CommandBgRewriteAof::try_from(v).map(Command::BgRewriteAof)
+    })
+    .flags(&[Admin, NoScript])
+    .summary("Asynchronously rewrites the append-only file to disk."),
+];
+
+static COMMANDS: LazyLock<HashMap<&'static str, &'static CommandSpec>> =
+    LazyLock::new(|| COMMAND_TABLE.iter().map(|spec| (spec.name, spec)).collect());
+
+/// 按命令名查找，不区分大小写
+pub fn lookup_command(name: &[u8]) -> Option<&'static CommandSpec> {
+    if name.iter().any(u8::is_ascii_uppercase) {
+        let name = name.to_ascii_lowercase();
+        COMMANDS.get(str::from_utf8(&name).ok()?).copied()
+    } else {
+        COMMANDS.get(str::from_utf8(name).ok()?).copied()
+    }
+}
+
+/// 查找请求对应的命令并校验参数个数，返回命令和参数（不含命令名）
+pub fn resolve_command(
+    value: &RespArray,
+) -> Result<(&'static CommandSpec, Vec<&RespFrame>), CommandError> {
+    let elements = match value.as_ref() {
+        Some(elements) if !elements.is_empty() => elements,
+        _ => return Err(CommandError::InvalidCommand("empty command".to_string())),
+    };
+    let name = match &elements[0] {
+        RespFrame::BulkString(BulkString {
+            content: Some(name),
+        }) => name,
+        _ => {
+            return Err(CommandError::InvalidCommand(
+                "command name must be a bulk string".to_string(),
+            ));
+        }
+    };
+    let Some(spec) = lookup_command(name) else {
+        let args: String = elements[1..]
+            .iter()
+            .filter_map(|arg| match arg {
+                RespFrame::BulkString(BulkString { content: Some(arg) }) => {
+                    Some(format!("'{}' ", String::from_utf8_lossy(arg)))
+                }
+                _ => None,
+            })
+            .collect();
+        return Err(CommandError::InvalidCommand(format!(
+            "unknown command '{}', with args beginning with: {}",
+            String::from_utf8_lossy(name),
+            args
+        )));
+    };
+    spec.check_arity(elements.len())?;
+    Ok((spec, elements[1..].iter().collect()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn array(args: &[&str]) -> RespArray {
+        RespArray::from_vec(args.iter().map(|arg| (*arg).into()).collect())
+    }
+
+    #[test]
+    fn test_command_table() {
+        assert_eq!(COMMANDS.len(), COMMAND_TABLE.len());
+        for spec in COMMAND_TABLE {
+            assert_eq!(spec.name, spec.name.to_ascii_lowercase());
+            assert!(!(spec.has_flag(Write) && spec.has_flag(ReadOnly)));
+        }
+        assert_eq!(lookup_command(b"SeT").map(|spec| spec.name), Some("set"));
+        assert!(lookup_command(b"SET").is_some_and(CommandSpec::is_write));
+        assert!(lookup_command(b"nope").is_none());
+    }
+
+    #[test]
+    fn test_resolve_command() -> Result<(), CommandError> {
+        let request = array(&["GET", "key"]);
+        let (spec, args) = resolve_command(&request)?;
+        assert_eq!(spec.name, "get");
+        assert_eq!(args.len(), 1);
+        assert!(matches!(
+            Command::try_from(array(&["SET", "key", "value"]))?,
+            Command::Set(_)
+        ));
+        assert!(matches!(
+            Command::try_from(array(&["PiNg"]))?,
+            Command::Ping
+        ));
+
+        let err = resolve_command(&array(&["get"])).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "wrong number of arguments for 'get' command"
+        );
+        let err = resolve_command(&array(&["SET", "key"])).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "wrong number of arguments for 'set' command"
+        );
+        assert!(resolve_command(&array(&["del", "a", "b", "c"])).is_ok());
+        let err = resolve_command(&array(&["foo", "a", "b"])).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "unknown command 'foo', with args beginning with: 'a' 'b' "
+        );
+        assert!(resolve_command(&RespArray::empty()).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_write_arguments_detached_from_read_buffer() -> Result<(), CommandError> {
+        use bytes::BytesMut;
+
+        use crate::resp::RespDecode;
+
+        let mut buf = BytesMut::from("*3\r\n$3\r\nset\r\n$1\r\nk\r\n$1\r\nv\r\n");
+        let range = buf.as_ptr_range();
+        let request = RespArray::decode(&mut buf)?;
+        let backend = Backend::new();
+        Command::try_from(request)?.execute(&backend);
+        // 保存的 key 和 value 不再指向读缓冲区
+        let value = backend.get(b"k").unwrap().unwrap();
+        assert!(!range.contains(&value.as_ptr()));
+        let key = backend.keys(b"*").pop().unwrap();
+        assert!(!range.contains(&key.as_ptr()));
+
+        // 只读命令的参数不拷贝
+        let mut buf = BytesMut::from("*2\r\n$3\r\nget\r\n$1\r\nk\r\n");
+        let range = buf.as_ptr_range();
+        let request = RespArray::decode(&mut buf)?;
+        let Command::Get(get) = Command::try_from(request)? else {
+            panic!("expect GET");
+        };
+        assert!(range.contains(&get.key.as_ptr()));
+
+        // 大参数直接共享读缓冲区的内存
+        let payload = "x".repeat(SHARED_ARG_MIN_LEN);
+        let mut buf = BytesMut::from(
+            format!(
+                "*3\r\n$3\r\nset\r\n$3\r\nbig\r\n${}\r\n{}\r\n",
+                payload.len(),
+                payload
+            )
+            .as_str(),
+        );
+        let range = buf.as_ptr_range();
+        let request = RespArray::decode(&mut buf)?;
+        Command::try_from(request)?.execute(&backend);
+        let value = backend.get(b"big").unwrap().unwrap();
+        assert_eq!(value.len(), SHARED_ARG_MIN_LEN);
+        assert!(range.contains(&value.as_ptr()));
+        Ok(())
+    }
+}
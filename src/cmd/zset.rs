@@ -0,0 +1,630 @@
+use bytes::Bytes;
+
+use crate::{
+    backend::{Backend, ScoreBound, SortedSet},
+    cmd::{
+        CommandError, CommandExecutor, command_args, extract_bytes, extract_int, extract_string,
+        normalize_range,
+    },
+    resp::{BulkString, RespArray, RespDouble, RespFrame, RespInteger, RespNull, SimpleError},
+};
+
+// Redis命令与RESP协议格式对应表
+// | 命令          | 参数                          | 对应格式                                                         |
+// |---------------|-------------------------------|------------------------------------------------------------------|
+// | ZADD          | key [NX|XX] [GT|LT] [CH] [INCR] score mem .. | "*4\r\n$4\r\nzadd\r\n$4\r\nzset\r\n$1\r\n1\r\n$1\r\na\r\n" |
+// | ZRANGE        | key start stop [BYSCORE] [REV] [LIMIT o c] [WITHSCORES] | "*4\r\n$6\r\nzrange\r\n$4\r\nzset\r\n$1\r\n0\r\n$2\r\n-1\r\n" |
+// | ZRANGEBYSCORE | key min max [WITHSCORES] [LIMIT o c] | "*4\r\n$13\r\nzrangebyscore\r\n$4\r\nzset\r\n$4\r\n-inf\r\n$2\r\n(5\r\n" |
+// | ZRANK         | key member [WITHSCORE]        | "*3\r\n$5\r\nzrank\r\n$4\r\nzset\r\n$1\r\na\r\n"                  |
+// | ZREVRANK      | key member [WITHSCORE]        | "*3\r\n$8\r\nzrevrank\r\n$4\r\nzset\r\n$1\r\na\r\n"               |
+// | ZINCRBY       | key increment member          | "*4\r\n$7\r\nzincrby\r\n$4\r\nzset\r\n$1\r\n2\r\n$1\r\na\r\n"     |
+// | ZREM          | key member [member ...]       | "*3\r\n$4\r\nzrem\r\n$4\r\nzset\r\n$1\r\na\r\n"                   |
+// | ZSCORE        | key member                    | "*3\r\n$6\r\nzscore\r\n$4\r\nzset\r\n$1\r\na\r\n"                 |
+// | ZCARD         | key                           | "*2\r\n$5\r\nzcard\r\n$4\r\nzset\r\n"                             |
+
+/// ZADD 的选项
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct ZAddFlags {
+    /// 只添加新成员
+    nx: bool,
+    /// 只更新已有成员
+    xx: bool,
+    /// 只在新分数更大时更新
+    gt: bool,
+    /// 只在新分数更小时更新
+    lt: bool,
+    /// 返回值包含被更新分数的成员
+    ch: bool,
+    /// 作为 ZINCRBY 使用
+    incr: bool,
+}
+
+/// ZRANGE 的区间类型
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ZRangeBy {
+    Rank(i64, i64),
+    Score(ScoreBound, ScoreBound),
+}
+
+#[derive(Debug)]
+pub struct CommandZAdd {
+    key: Bytes,
+    flags: ZAddFlags,
+    pairs: Vec<(f64, Bytes)>,
+}
+/// ZRANGE/ZRANGEBYSCORE
+#[derive(Debug)]
+pub struct CommandZRange {
+    key: Bytes,
+    by: ZRangeBy,
+    rev: bool,
+    limit: Option<(i64, i64)>,
+    with_scores: bool,
+}
+/// ZRANK/ZREVRANK
+#[derive(Debug)]
+pub struct CommandZRank {
+    key: Bytes,
+    member: Bytes,
+    rev: bool,
+    with_score: bool,
+}
+#[derive(Debug)]
+pub struct CommandZIncrBy {
+    key: Bytes,
+    increment: f64,
+    member: Bytes,
+}
+#[derive(Debug)]
+pub struct CommandZRem {
+    key: Bytes,
+    members: Vec<Bytes>,
+}
+#[derive(Debug)]
+pub struct CommandZScore {
+    key: Bytes,
+    member: Bytes,
+}
+#[derive(Debug)]
+pub struct CommandZCard {
+    key: Bytes,
+}
+
+/// 按 flags 更新单个成员，返回更新后的分数（未更新返回 None）和是否为新成员
+fn zadd_one(
+    zset: &mut SortedSet,
+    flags: ZAddFlags,
+    score: f64,
+    member: &Bytes,
+) -> Result<(Option<f64>, bool), SimpleError> {
+    match zset.score(member) {
+        None if flags.xx => Ok((None, false)),
+        None => {
+            zset.insert(member.clone(), score);
+            Ok((Some(score), true))
+        }
+        Some(_) if flags.nx => Ok((None, false)),
+        Some(current) => {
+            let score = if flags.incr { current + score } else { score };
+            if score.is_nan() {
+                return Err(SimpleError::new(
+                    "ERR resulting score is not a number (NaN)",
+                ));
+            }
+            if (flags.gt && score <= current) || (flags.lt && score >= current) {
+                return Ok((None, false));
+            }
+            zset.insert(member.clone(), score);
+            Ok((Some(score), false))
+        }
+    }
+}
+
+/// 成员列表，带分数时每个成员后面跟一个 double
+fn members_reply(items: Vec<(Bytes, f64)>, with_scores: bool) -> RespFrame {
+    let mut ret = Vec::with_capacity(if with_scores {
+        items.len() * 2
+    } else {
+        items.len()
+    });
+    for (member, score) in items {
+        ret.push(member.into());
+        if with_scores {
+            ret.push(RespDouble::new(score).into());
+        }
+    }
+    RespArray::new(Some(ret)).into()
+}
+
+impl CommandExecutor for CommandZAdd {
+    fn execute(self, backend: &Backend) -> RespFrame {
+        let flags = self.flags;
+        let ret = backend.zset_update(&self.key, |zset| {
+            let (mut added, mut changed, mut last) = (0, 0, None);
+            for (score, member) in &self.pairs {
+                let (updated, is_new) = zadd_one(zset, flags, *score, member)?;
+                if is_new {
+                    added += 1;
+                } else if updated.is_some() {
+                    changed += 1;
+                }
+                last = updated;
+            }
+            Ok::<_, SimpleError>(((added, changed, last), (added + changed) as usize))
+        });
+        match ret {
+            Err(e) => e.into(),
+            Ok(Err(e)) => e.into(),
+            Ok(Ok((_, _, score))) if flags.incr => score
+                .map(|score| RespDouble::new(score).into())
+                .unwrap_or(RespFrame::RespNull(RespNull)),
+            Ok(Ok((added, changed, _))) if flags.ch => RespInteger::new(added + changed).into(),
+            Ok(Ok((added, _, _))) => RespInteger::new(added).into(),
+        }
+    }
+}
+impl CommandExecutor for CommandZRange {
+    fn execute(self, backend: &Backend) -> RespFrame {
+        let items = backend.zset_read(&self.key, |zset| match self.by {
+            ZRangeBy::Rank(start, stop) => match normalize_range(start, stop, zset.len()) {
+                Some((start, stop)) => zset.range_by_rank(start, stop, self.rev),
+                None => Vec::new(),
+            },
+            ZRangeBy::Score(min, max) => {
+                let (offset, count) = self.limit.unwrap_or((0, -1));
+                if offset < 0 {
+                    return Vec::new();
+                }
+                // count 为负数表示返回全部
+                let count = (count >= 0).then_some(count as usize);
+                zset.range_by_score(&min, &max, self.rev, offset as usize, count)
+            }
+        });
+        match items {
+            Ok(items) => members_reply(items.unwrap_or_default(), self.with_scores),
+            Err(e) => e.into(),
+        }
+    }
+}
+impl CommandExecutor for CommandZRank {
+    fn execute(self, backend: &Backend) -> RespFrame {
+        let ret = backend.zset_read(&self.key, |zset| {
+            let rank = zset.rank(&self.member, self.rev)?;
+            Some((rank, zset.score(&self.member)?))
+        });
+        match ret.map(Option::flatten) {
+            Err(e) => e.into(),
+            Ok(None) => RespFrame::RespNull(RespNull),
+            Ok(Some((rank, score))) if self.with_score => RespArray::new(Some(vec![
+                RespInteger::new(rank as i64).into(),
+                RespDouble::new(score).into(),
+            ]))
+            .into(),
+            Ok(Some((rank, _))) => RespInteger::new(rank as i64).into(),
+        }
+    }
+}
+impl CommandExecutor for CommandZIncrBy {
+    fn execute(self, backend: &Backend) -> RespFrame {
+        let flags = ZAddFlags {
+            incr: true,
+            ..Default::default()
+        };
+        let ret = backend.zset_update(&self.key, |zset| {
+            zadd_one(zset, flags, self.increment, &self.member)
+                .map(|ret| (ret, ret.0.is_some() as usize))
+        });
+        match ret {
+            Ok(Ok((Some(score), _))) => RespDouble::new(score).into(),
+            Ok(Ok((None, _))) => RespFrame::RespNull(RespNull),
+            Ok(Err(e)) => e.into(),
+            Err(e) => e.into(),
+        }
+    }
+}
+impl CommandExecutor for CommandZRem {
+    fn execute(self, backend: &Backend) -> RespFrame {
+        let removed = backend.update(&self.key, |zset: &mut SortedSet| {
+            let removed = self
+                .members
+                .iter()
+                .filter(|member| zset.remove(member))
+                .count();
+            (removed, removed)
+        });
+        match removed {
+            Ok(removed) => RespInteger::new(removed.unwrap_or(0) as i64).into(),
+            Err(e) => e.into(),
+        }
+    }
+}
+impl CommandExecutor for CommandZScore {
+    fn execute(self, backend: &Backend) -> RespFrame {
+        match backend.zset_read(&self.key, |zset| zset.score(&self.member)) {
+            Ok(score) => score
+                .flatten()
+                .map(|score| RespDouble::new(score).into())
+                .unwrap_or(RespFrame::RespNull(RespNull)),
+            Err(e) => e.into(),
+        }
+    }
+}
+impl CommandExecutor for CommandZCard {
+    fn execute(self, backend: &Backend) -> RespFrame {
+        match backend.zset_read(&self.key, |zset| zset.len()) {
+            Ok(len) => RespInteger::new(len.unwrap_or(0) as i64).into(),
+            Err(e) => e.into(),
+        }
+    }
+}
+
+/// 解析分数，支持 inf/+inf/-inf
+fn extract_score(frame: &RespFrame, name: &str) -> Result<f64, CommandError> {
+    extract_string(frame, name)?
+        .parse::<f64>()
+        .ok()
+        .filter(|score| !score.is_nan())
+        .ok_or_else(|| CommandError::InvalidArguments("value is not a valid float".to_string()))
+}
+
+/// 解析分数区间边界，"(" 开头表示不包含
+fn extract_bound(frame: &RespFrame, name: &str) -> Result<ScoreBound, CommandError> {
+    let bound = extract_string(frame, name)?;
+    let (value, exclusive) = match bound.strip_prefix('(') {
+        Some(value) => (value, true),
+        None => (bound.as_str(), false),
+    };
+    value
+        .parse::<f64>()
+        .ok()
+        .filter(|value| !value.is_nan())
+        .map(|value| ScoreBound::new(value, exclusive))
+        .ok_or_else(|| CommandError::InvalidArguments("min or max is not a float".to_string()))
+}
+
+/// 读取命令名（小写）
+fn command_name(value: &RespArray) -> Vec<u8> {
+    match value.as_ref().and_then(|elements| elements.first()) {
+        Some(RespFrame::BulkString(BulkString {
+            content: Some(name),
+        })) => name.to_ascii_lowercase(),
+        _ => Vec::new(),
+    }
+}
+
+impl TryFrom<RespArray> for CommandZAdd {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        let args = command_args(&value)?;
+        let key = extract_bytes(args[0], "ZADD")?;
+        let mut flags = ZAddFlags::default();
+        let mut rest = &args[1..];
+        while let Some((option, tail)) = rest.split_first() {
+            match extract_string(option, "ZADD")?
+                .to_ascii_uppercase()
+                .as_str()
+            {
+                "NX" => flags.nx = true,
+                "XX" => flags.xx = true,
+                "GT" => flags.gt = true,
+                "LT" => flags.lt = true,
+                "CH" => flags.ch = true,
+                "INCR" => flags.incr = true,
+                _ => break,
+            }
+            rest = tail;
+        }
+        if flags.nx && flags.xx {
+            return Err(CommandError::InvalidArguments(
+                "XX and NX options at the same time are not compatible".to_string(),
+            ));
+        }
+        if (flags.gt && flags.lt) || (flags.nx && (flags.gt || flags.lt)) {
+            return Err(CommandError::InvalidArguments(
+                "GT, LT, and/or NX options at the same time are not compatible".to_string(),
+            ));
+        }
+        if rest.is_empty() || rest.len() % 2 != 0 {
+            return Err(CommandError::InvalidArguments("syntax error".to_string()));
+        }
+        if flags.incr && rest.len() != 2 {
+            return Err(CommandError::InvalidArguments(
+                "INCR option supports a single increment-element pair".to_string(),
+            ));
+        }
+        let pairs = rest
+            .chunks(2)
+            .map(|pair| {
+                Ok((
+                    extract_score(pair[0], "ZADD")?,
+                    extract_bytes(pair[1], "ZADD")?,
+                ))
+            })
+            .collect::<Result<Vec<_>, CommandError>>()?;
+        Ok(CommandZAdd { key, flags, pairs })
+    }
+}
+impl TryFrom<RespArray> for CommandZRange {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        let by_score_command = command_name(&value) == b"zrangebyscore";
+        let name = if by_score_command {
+            "ZRANGEBYSCORE"
+        } else {
+            "ZRANGE"
+        };
+        let args = command_args(&value)?;
+        let key = extract_bytes(args[0], name)?;
+        let syntax_error = || CommandError::InvalidArguments("syntax error".to_string());
+        let (mut by_score, mut rev, mut limit, mut with_scores) =
+            (by_score_command, false, None, false);
+        let mut options = args[3..].iter();
+        while let Some(option) = options.next() {
+            match extract_string(option, name)?.to_ascii_uppercase().as_str() {
+                "BYSCORE" if !by_score_command => by_score = true,
+                "REV" if !by_score_command => rev = true,
+                "WITHSCORES" => with_scores = true,
+                "LIMIT" => {
+                    let offset = extract_int(options.next().ok_or_else(syntax_error)?, name)?;
+                    let count = extract_int(options.next().ok_or_else(syntax_error)?, name)?;
+                    limit = Some((offset, count));
+                }
+                _ => return Err(syntax_error()),
+            }
+        }
+        if limit.is_some() && !by_score {
+            return Err(CommandError::InvalidArguments(
+                "syntax error, LIMIT is only supported in combination with either BYSCORE or BYLEX"
+                    .to_string(),
+            ));
+        }
+        let by = if by_score {
+            let (start, stop) = (extract_bound(args[1], name)?, extract_bound(args[2], name)?);
+            // REV 时参数顺序为 max min
+            if rev {
+                ZRangeBy::Score(stop, start)
+            } else {
+                ZRangeBy::Score(start, stop)
+            }
+        } else {
+            ZRangeBy::Rank(extract_int(args[1], name)?, extract_int(args[2], name)?)
+        };
+        Ok(CommandZRange {
+            key,
+            by,
+            rev,
+            limit,
+            with_scores,
+        })
+    }
+}
+impl TryFrom<RespArray> for CommandZRank {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        let rev = command_name(&value) == b"zrevrank";
+        let name = if rev { "ZREVRANK" } else { "ZRANK" };
+        let args = command_args(&value)?;
+        let with_score = match args.get(2) {
+            None => false,
+            Some(option) if args.len() == 3 => {
+                if !extract_string(option, name)?.eq_ignore_ascii_case("WITHSCORE") {
+                    return Err(CommandError::InvalidArguments("syntax error".to_string()));
+                }
+                true
+            }
+            Some(_) => {
+                return Err(CommandError::InvalidArguments("syntax error".to_string()));
+            }
+        };
+        Ok(CommandZRank {
+            key: extract_bytes(args[0], name)?,
+            member: extract_bytes(args[1], name)?,
+            rev,
+            with_score,
+        })
+    }
+}
+impl TryFrom<RespArray> for CommandZIncrBy {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        let args = command_args(&value)?;
+        Ok(CommandZIncrBy {
+            key: extract_bytes(args[0], "ZINCRBY")?,
+            increment: extract_score(args[1], "ZINCRBY")?,
+            member: extract_bytes(args[2], "ZINCRBY")?,
+        })
+    }
+}
+impl TryFrom<RespArray> for CommandZRem {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        let args = command_args(&value)?;
+        Ok(CommandZRem {
+            key: extract_bytes(args[0], "ZREM")?,
+            members: args[1..]
+                .iter()
+                .map(|arg| extract_bytes(arg, "ZREM"))
+                .collect::<Result<Vec<_>, _>>()?,
+        })
+    }
+}
+impl TryFrom<RespArray> for CommandZScore {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        let args = command_args(&value)?;
+        Ok(CommandZScore {
+            key: extract_bytes(args[0], "ZSCORE")?,
+            member: extract_bytes(args[1], "ZSCORE")?,
+        })
+    }
+}
+impl TryFrom<RespArray> for CommandZCard {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        let args = command_args(&value)?;
+        Ok(CommandZCard {
+            key: extract_bytes(args[0], "ZCARD")?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::BytesMut;
+
+    use super::*;
+    use crate::resp::RespDecode;
+
+    fn zadd(backend: &Backend, args: &[&str]) -> Result<RespFrame, CommandError> {
+        let mut frames: Vec<RespFrame> = vec!["ZADD".into(), "zset".into()];
+        frames.extend(args.iter().map(|arg| (*arg).into()));
+        Ok(CommandZAdd::try_from(RespArray::from(frames))?.execute(backend))
+    }
+    fn zrange(backend: &Backend, args: &[&str]) -> Result<RespFrame, CommandError> {
+        let mut frames: Vec<RespFrame> = vec!["ZRANGE".into(), "zset".into()];
+        frames.extend(args.iter().map(|arg| (*arg).into()));
+        Ok(CommandZRange::try_from(RespArray::from(frames))?.execute(backend))
+    }
+    fn array(items: Vec<RespFrame>) -> RespFrame {
+        RespArray::from(items).into()
+    }
+
+    #[test]
+    fn test_command_zset_try_from() -> Result<(), CommandError> {
+        let mut buf = BytesMut::from(
+            "*6\r\n$4\r\nzadd\r\n$4\r\nzset\r\n$2\r\nCH\r\n$1\r\n1\r\n$1\r\na\r\n$4\r\n-inf\r\n",
+        );
+        // score 与 member 不成对
+        assert!(CommandZAdd::try_from(RespArray::decode(&mut buf)?).is_err());
+
+        let mut buf = BytesMut::from(
+            "*4\r\n$13\r\nzrangebyscore\r\n$4\r\nzset\r\n$4\r\n-inf\r\n$2\r\n(5\r\n",
+        );
+        let cmd = CommandZRange::try_from(RespArray::decode(&mut buf)?)?;
+        assert_eq!(
+            cmd.by,
+            ZRangeBy::Score(
+                ScoreBound::new(f64::NEG_INFINITY, false),
+                ScoreBound::new(5.0, true)
+            )
+        );
+
+        let cmd: RespArray = vec![
+            "ZADD".into(),
+            "zset".into(),
+            "NX".into(),
+            "GT".into(),
+            "1".into(),
+            "a".into(),
+        ]
+        .into();
+        assert!(CommandZAdd::try_from(cmd).is_err());
+        let cmd: RespArray = vec![
+            "ZRANGE".into(),
+            "zset".into(),
+            "0".into(),
+            "1".into(),
+            "LIMIT".into(),
+            "0".into(),
+            "1".into(),
+        ]
+        .into();
+        assert!(CommandZRange::try_from(cmd).is_err());
+        Ok(())
+    }
+    #[test]
+    fn test_zadd_flags() -> Result<(), CommandError> {
+        let backend = Backend::new();
+        assert_eq!(
+            zadd(&backend, &["1", "a", "2", "b"])?,
+            RespInteger::new(2).into()
+        );
+        assert_eq!(
+            zadd(&backend, &["NX", "10", "a", "3", "c"])?,
+            RespInteger::new(1).into()
+        );
+        assert_eq!(
+            zadd(&backend, &["XX", "CH", "5", "a", "4", "d"])?,
+            RespInteger::new(1).into()
+        );
+        assert_eq!(
+            zadd(&backend, &["GT", "CH", "1", "a", "6", "b"])?,
+            RespInteger::new(1).into()
+        );
+        assert_eq!(
+            zadd(&backend, &["INCR", "2.5", "a"])?,
+            RespDouble::new(7.5).into()
+        );
+        assert_eq!(zadd(&backend, &["LT", "INCR", "1", "a"])?, RespNull.into());
+        assert_eq!(
+            zrange(&backend, &["0", "-1", "WITHSCORES"])?,
+            array(vec![
+                "c".into(),
+                RespDouble::new(3.0).into(),
+                "b".into(),
+                RespDouble::new(6.0).into(),
+                "a".into(),
+                RespDouble::new(7.5).into(),
+            ])
+        );
+        Ok(())
+    }
+    #[test]
+    fn test_zrange_commands() -> Result<(), CommandError> {
+        let backend = Backend::new();
+        zadd(&backend, &["1", "a", "2", "b", "3", "c", "4", "d"])?;
+        assert_eq!(
+            zrange(&backend, &["1", "2", "REV"])?,
+            array(vec!["c".into(), "b".into()])
+        );
+        assert_eq!(
+            zrange(&backend, &["(1", "+inf", "BYSCORE", "LIMIT", "1", "2"])?,
+            array(vec!["c".into(), "d".into()])
+        );
+        assert_eq!(
+            zrange(&backend, &["3", "-inf", "BYSCORE", "REV"])?,
+            array(vec!["c".into(), "b".into(), "a".into()])
+        );
+
+        let cmd = CommandZRank {
+            key: "zset".into(),
+            member: "c".into(),
+            rev: true,
+            with_score: true,
+        };
+        assert_eq!(
+            cmd.execute(&backend),
+            array(vec![
+                RespInteger::new(1).into(),
+                RespDouble::new(3.0).into()
+            ])
+        );
+        let cmd = CommandZIncrBy {
+            key: "zset".into(),
+            increment: -10.0,
+            member: "d".into(),
+        };
+        assert_eq!(cmd.execute(&backend), RespDouble::new(-6.0).into());
+        let cmd = CommandZRank {
+            key: "zset".into(),
+            member: "d".into(),
+            rev: false,
+            with_score: false,
+        };
+        assert_eq!(cmd.execute(&backend), RespInteger::new(0).into());
+
+        let cmd = CommandZRem {
+            key: "zset".into(),
+            members: vec!["a".into(), "x".into()],
+        };
+        assert_eq!(cmd.execute(&backend), RespInteger::new(1).into());
+        let cmd = CommandZScore {
+            key: "zset".into(),
+            member: "a".into(),
+        };
+        assert_eq!(cmd.execute(&backend), RespNull.into());
+        let cmd = CommandZCard { key: "zset".into() };
+        assert_eq!(cmd.execute(&backend), RespInteger::new(3).into());
+        Ok(())
+    }
+}
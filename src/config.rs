@@ -0,0 +1,257 @@
+use std::path::PathBuf;
+
+use anyhow::{Context, bail};
+
+use crate::{backend::FsyncPolicy, resp::RespLimits};
+
+/// 服务器配置，从命令行参数读取，格式与 redis-server 相同：--name value
+#[derive(Debug, Clone, PartialEq)]
+pub struct Config {
+    pub bind: String,
+    pub port: u16,
+    /// 快照所在目录
+    pub dir: PathBuf,
+    /// 快照文件名
+    pub dbfilename: String,
+    /// 自动快照规则，为空时不自动保存
+    /// 默认为空，避免在工作目录下意外写出 dump.rdb；需要时用 --save 开启，
+    /// 如 --save "3600 1 300 100 60 10000" 与 redis-server 的默认规则相同
+    pub save: Vec<SaveRule>,
+    /// 是否开启 AOF，开启后启动时从 AOF 而不是快照恢复
+    pub appendonly: bool,
+    /// AOF 文件名，与快照在同一目录
+    pub appendfilename: String,
+    pub appendfsync: FsyncPolicy,
+    /// AOF 末尾的命令不完整时是否截掉后继续启动
+    pub aof_load_truncated: bool,
+    /// 每个连接最多缓存的回复条数，达到后先写出再继续执行流水线中的请求
+    pub pipeline_max_replies: usize,
+    /// 请求中单个 bulk string 的最大字节数
+    pub proto_max_bulk_len: usize,
+    /// 请求中聚合类型的最大嵌套层数
+    pub proto_max_nesting: usize,
+    /// 请求中单个聚合类型的最大元素个数
+    pub proto_max_multibulk_len: usize,
+}
+
+/// save <seconds> <changes>：距上次保存超过 seconds 秒且至少有 changes 次修改时保存
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SaveRule {
+    pub seconds: u64,
+    pub changes: u64,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        let limits = RespLimits::default();
+        Self {
+            bind: "127.0.0.1".to_string(),
+            port: 6379,
+            dir: PathBuf::from("."),
+            dbfilename: "dump.rdb".to_string(),
+            save: Vec::new(),
+            appendonly: false,
+            appendfilename: "appendonly.aof".to_string(),
+            appendfsync: FsyncPolicy::EverySec,
+            aof_load_truncated: true,
+            pipeline_max_replies: 1024,
+            proto_max_bulk_len: limits.max_bulk_len,
+            proto_max_nesting: limits.max_depth,
+            proto_max_multibulk_len: limits.max_elements,
+        }
+    }
+}
+
+impl SaveRule {
+    pub fn new(seconds: u64, changes: u64) -> Self {
+        Self { seconds, changes }
+    }
+    /// 解析 "<seconds> <changes> [<seconds> <changes> ...]"，空字符串表示关闭
+    pub fn parse_list(s: &str) -> anyhow::Result<Vec<SaveRule>> {
+        let args = s.split_whitespace().collect::<Vec<_>>();
+        if args.len() % 2 != 0 {
+            bail!("save expects pairs of <seconds> <changes>, got {:?}", s);
+        }
+        args.chunks(2)
+            .map(|pair| {
+                Ok(SaveRule::new(
+                    pair[0].parse().context("invalid save seconds")?,
+                    pair[1].parse().context("invalid save changes")?,
+                ))
+            })
+            .collect()
+    }
+}
+
+impl Config {
+    /// 从命令行参数解析（不包含程序名），未出现的选项使用默认值
+    /// --save 可以出现多次，规则依次累加
+    pub fn from_args(args: impl IntoIterator<Item = String>) -> anyhow::Result<Self> {
+        let mut config = Config::default();
+        let mut args = args.into_iter();
+        while let Some(name) = args.next() {
+            let Some(option) = name.strip_prefix("--") else {
+                bail!("unexpected argument: {}", name);
+            };
+            let value = args
+                .next()
+                .with_context(|| format!("missing value for {}", name))?;
+            match option.to_ascii_lowercase().as_str() {
+                "bind" => config.bind = value,
+                "port" => config.port = value.parse().context("invalid port")?,
+                "dir" => config.dir = PathBuf::from(value),
+                "dbfilename" => config.dbfilename = value,
+                "save" => config.save.extend(SaveRule::parse_list(&value)?),
+                "appendonly" => config.appendonly = parse_bool(&value)?,
+                "appendfilename" => config.appendfilename = value,
+                "appendfsync" => config.appendfsync = value.parse()?,
+                "aof-load-truncated" => config.aof_load_truncated = parse_bool(&value)?,
+                "pipeline-max-replies" => {
+                    config.pipeline_max_replies = value
+                        .parse()
+                        .ok()
+                        .filter(|max| *max > 0)
+                        .context("pipeline-max-replies must be a positive integer")?;
+                }
+                "proto-max-bulk-len" => config.proto_max_bulk_len = parse_memory(&value)?,
+                "proto-max-nesting" => {
+                    config.proto_max_nesting = parse_positive(&value, "proto-max-nesting")?
+                }
+                "proto-max-multibulk-len" => {
+                    config.proto_max_multibulk_len =
+                        parse_positive(&value, "proto-max-multibulk-len")?
+                }
+                _ => bail!("unknown option: {}", name),
+            }
+        }
+        Ok(config)
+    }
+    pub fn addr(&self) -> String {
+        format!("{}:{}", self.bind, self.port)
+    }
+    pub fn rdb_path(&self) -> PathBuf {
+        self.dir.join(&self.dbfilename)
+    }
+    pub fn aof_path(&self) -> PathBuf {
+        self.dir.join(&self.appendfilename)
+    }
+    /// 解析客户端请求时的限制
+    pub fn resp_limits(&self) -> RespLimits {
+        RespLimits {
+            max_bulk_len: self.proto_max_bulk_len,
+            max_depth: self.proto_max_nesting,
+            max_elements: self.proto_max_multibulk_len,
+        }
+    }
+}
+
+fn parse_positive(value: &str, name: &str) -> anyhow::Result<usize> {
+    value
+        .parse()
+        .ok()
+        .filter(|n| *n > 0)
+        .with_context(|| format!("{} must be a positive integer", name))
+}
+
+/// 与 redis.conf 相同的内存单位：k/m/g 是 1000 的倍数，kb/mb/gb 是 1024 的倍数
+fn parse_memory(value: &str) -> anyhow::Result<usize> {
+    let lower = value.to_ascii_lowercase();
+    let split = lower
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(lower.len());
+    let (number, unit) = lower.split_at(split);
+    let multiplier: usize = match unit {
+        "" | "b" => 1,
+        "k" => 1000,
+        "kb" => 1024,
+        "m" => 1000 * 1000,
+        "mb" => 1024 * 1024,
+        "g" => 1000 * 1000 * 1000,
+        "gb" => 1024 * 1024 * 1024,
+        _ => bail!("invalid memory unit: {:?}", value),
+    };
+    number
+        .parse::<usize>()
+        .ok()
+        .and_then(|n| n.checked_mul(multiplier))
+        .filter(|n| *n > 0)
+        .with_context(|| format!("invalid memory size: {:?}", value))
+}
+
+fn parse_bool(value: &str) -> anyhow::Result<bool> {
+    match value.to_ascii_lowercase().as_str() {
+        "yes" => Ok(true),
+        "no" => Ok(false),
+        _ => bail!("argument must be 'yes' or 'no', got {:?}", value),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> anyhow::Result<Config> {
+        Config::from_args(args.iter().map(|arg| arg.to_string()))
+    }
+
+    #[test]
+    fn test_config_from_args() -> anyhow::Result<()> {
+        assert_eq!(parse(&[])?, Config::default());
+        assert!(Config::default().save.is_empty());
+        let config = parse(&[
+            "--port", "6380", "--dir", "/tmp", "--save", "900 1", "--save", "60 10000",
+        ])?;
+        assert_eq!(config.addr(), "127.0.0.1:6380");
+        assert_eq!(config.rdb_path(), PathBuf::from("/tmp/dump.rdb"));
+        assert_eq!(
+            config.save,
+            vec![SaveRule::new(900, 1), SaveRule::new(60, 10000)]
+        );
+        assert!(parse(&["--save", ""])?.save.is_empty());
+        assert_eq!(
+            parse(&["--save", "3600 1 300 100 60 10000"])?.save,
+            vec![
+                SaveRule::new(3600, 1),
+                SaveRule::new(300, 100),
+                SaveRule::new(60, 10000)
+            ]
+        );
+        let config = parse(&["--appendonly", "yes", "--appendfsync", "always"])?;
+        assert!(config.appendonly);
+        assert_eq!(config.appendfsync, FsyncPolicy::Always);
+        assert_eq!(config.aof_path(), PathBuf::from("./appendonly.aof"));
+        assert!(parse(&["--appendonly", "maybe"]).is_err());
+        assert_eq!(
+            parse(&["--pipeline-max-replies", "16"])?.pipeline_max_replies,
+            16
+        );
+        assert!(parse(&["--pipeline-max-replies", "0"]).is_err());
+        let config = parse(&[
+            "--proto-max-bulk-len",
+            "1mb",
+            "--proto-max-nesting",
+            "8",
+            "--proto-max-multibulk-len",
+            "1000",
+        ])?;
+        assert_eq!(
+            config.resp_limits(),
+            RespLimits {
+                max_bulk_len: 1024 * 1024,
+                max_depth: 8,
+                max_elements: 1000,
+            }
+        );
+        assert_eq!(
+            parse(&["--proto-max-bulk-len", "2k"])?.proto_max_bulk_len,
+            2000
+        );
+        assert!(parse(&["--proto-max-bulk-len", "1tb"]).is_err());
+        assert!(parse(&["--proto-max-nesting", "0"]).is_err());
+        assert!(parse(&["--appendfsync", "sometimes"]).is_err());
+        assert!(parse(&["--save", "900"]).is_err());
+        assert!(parse(&["--port"]).is_err());
+        assert!(parse(&["--unknown", "1"]).is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,118 @@
+// Redis 风格的 glob 匹配，用于 KEYS 和 SCAN 的 MATCH 选项
+// | 模式      | 含义                                   |
+// |-----------|----------------------------------------|
+// | `*`       | 任意长度的任意字节（包括空）           |
+// | `?`       | 任意一个字节                           |
+// | `[abc]`   | 括号内的任意一个字节                   |
+// | `[^abc]`  | 不在括号内的任意一个字节               |
+// | `[a-z]`   | 范围内的任意一个字节，两端可以颠倒     |
+// | `\x`      | 字面量 x                               |
+
+/// 整个 string 是否匹配 pattern，按字节比较
+pub fn glob_match(pattern: &[u8], string: &[u8]) -> bool {
+    let (mut p, mut s) = (0, 0);
+    // 最近一个 * 之后的模式位置，以及它当前吞到的字符串位置
+    let mut star: Option<(usize, usize)> = None;
+    while s < string.len() {
+        if let Some(&c) = pattern.get(p) {
+            let matched = match c {
+                b'*' => {
+                    while pattern.get(p) == Some(&b'*') {
+                        p += 1;
+                    }
+                    if p == pattern.len() {
+                        return true;
+                    }
+                    star = Some((p, s));
+                    continue;
+                }
+                b'?' => Some(1),
+                b'[' => match_class(&pattern[p + 1..], string[s]).map(|len| len + 1),
+                b'\\' if p + 1 < pattern.len() => (pattern[p + 1] == string[s]).then_some(2),
+                c => (c == string[s]).then_some(1),
+            };
+            if let Some(len) = matched {
+                p += len;
+                s += 1;
+                continue;
+            }
+        }
+        // 当前字节不匹配，让最近的 * 多吞一个字节后重试
+        match star {
+            Some((star_p, star_s)) => {
+                p = star_p;
+                s = star_s + 1;
+                star = Some((star_p, s));
+            }
+            None => return false,
+        }
+    }
+    pattern[p..].iter().all(|&c| c == b'*')
+}
+
+/// 匹配 `[` 之后的字符类，匹配时返回字符类占用的模式长度（包括 `]`）
+/// 缺少 `]` 时字符类延伸到模式末尾，与 Redis 一致
+fn match_class(class: &[u8], c: u8) -> Option<usize> {
+    let negate = class.first() == Some(&b'^');
+    let mut i = negate as usize;
+    let mut matched = false;
+    while i < class.len() && class[i] != b']' {
+        if class[i] == b'\\' && i + 1 < class.len() {
+            matched |= class[i + 1] == c;
+            i += 2;
+        } else if i + 2 < class.len() && class[i + 1] == b'-' {
+            let (start, end) = (class[i].min(class[i + 2]), class[i].max(class[i + 2]));
+            matched |= (start..=end).contains(&c);
+            i += 3;
+        } else {
+            matched |= class[i] == c;
+            i += 1;
+        }
+    }
+    let len = (i + 1).min(class.len());
+    (matched != negate).then_some(len)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn matches(pattern: &str, string: &str) -> bool {
+        glob_match(pattern.as_bytes(), string.as_bytes())
+    }
+
+    #[test]
+    fn test_glob_match() {
+        assert!(matches("*", ""));
+        assert!(matches("*", "anything"));
+        assert!(matches("h?llo", "hello"));
+        assert!(!matches("h?llo", "hllo"));
+        assert!(matches("h*llo", "heeeello"));
+        assert!(matches("h*llo", "hllo"));
+        assert!(matches("user:*:name", "user:42:name"));
+        assert!(!matches("user:*:name", "user:42:age"));
+        assert!(matches("*a*b*c*", "xxaxxbxxcxx"));
+        assert!(!matches("*a*b*c*", "xxcxxbxxaxx"));
+        assert!(!matches("abc", "abcd"));
+        assert!(!matches("abcd", "abc"));
+    }
+
+    #[test]
+    fn test_glob_class_and_escape() {
+        assert!(matches("h[ae]llo", "hello"));
+        assert!(matches("h[ae]llo", "hallo"));
+        assert!(!matches("h[ae]llo", "hillo"));
+        assert!(matches("h[^e]llo", "hallo"));
+        assert!(!matches("h[^e]llo", "hello"));
+        assert!(matches("h[a-b]llo", "hbllo"));
+        assert!(matches("h[b-a]llo", "hallo"));
+        assert!(!matches("h[a-b]llo", "hcllo"));
+        assert!(matches("[\\]]", "]"));
+        assert!(!matches("[]", "a"));
+        assert!(matches("h[ab", "ha"));
+        assert!(matches("\\*", "*"));
+        assert!(!matches("\\*", "a"));
+        assert!(matches("a\\", "a\\"));
+        assert!(matches("\\?\\[", "?["));
+    }
+}
@@ -1,4 +1,6 @@
 pub mod backend;
 pub mod cmd;
+pub mod config;
+pub mod glob;
 pub mod network;
 pub mod resp;
@@ -1,21 +1,63 @@
-use simple_redis::backend::Backend;
+use std::{sync::Arc, time::Duration};
+
+use simple_redis::{backend::Backend, cmd, config::Config};
 use tokio::net::TcpListener;
 use tracing::{info, warn};
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
     tracing_subscriber::fmt::init();
-    let addr = "127.0.0.1:6379";
-    let listener = TcpListener::bind(addr).await?;
+    let config = Config::from_args(std::env::args().skip(1))?;
+    let backend = Backend::with_rdb_path(config.rdb_path());
+    // 先恢复数据再接受连接
+    if config.appendonly {
+        let path = config.aof_path();
+        let replayed = cmd::load_aof(&backend, &path, config.aof_load_truncated)?;
+        match replayed {
+            Some(count) => info!("Dredis: replayed {} commands from {:?}", count, path),
+            // 第一次开启 AOF：从快照恢复后生成初始的 AOF
+            None => load_rdb(&backend, &config)?,
+        }
+        backend.open_aof(&path, config.appendfsync)?;
+        if replayed.is_none() {
+            backend.rewrite_aof()?;
+        }
+        tokio::spawn(backend.clone().run_aof_fsync());
+    } else {
+        load_rdb(&backend, &config)?;
+    }
+    let addr = config.addr();
+    let listener = TcpListener::bind(&addr).await?;
     info!("Dredis: listening  on {}", addr);
-    let backend = Backend::new();
+    tokio::spawn(
+        backend
+            .clone()
+            .run_expire_sweeper(Duration::from_millis(100)),
+    );
+    if !config.save.is_empty() {
+        tokio::spawn(backend.clone().run_save_scheduler(config.save.clone()));
+    }
+    let config = Arc::new(config);
     loop {
         let (stream, addr) = listener.accept().await?;
         info!("Dredis: accepted connection from {}", addr);
         let backend = backend.clone();
+        let config = config.clone();
         tokio::spawn(async move {
-            if let Err(e) = simple_redis::network::stream_handler(stream, backend).await {
+            if let Err(e) =
+                simple_redis::network::stream_handler(stream, addr, backend, config).await
+            {
                 warn!("process redis error: {:?}", e);
             }
         });
     }
 }
+
+fn load_rdb(backend: &Backend, config: &Config) -> anyhow::Result<()> {
+    let loaded = backend.load()?;
+    info!(
+        "Dredis: loaded {} keys from {:?}",
+        loaded,
+        config.rdb_path()
+    );
+    Ok(())
+}
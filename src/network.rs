@@ -1,40 +1,130 @@
+use std::{net::SocketAddr, sync::Arc};
+
 use bytes::BytesMut;
-use futures::SinkExt;
-use tokio::net::TcpStream;
+use futures::{FutureExt, SinkExt};
+use tokio::io::{AsyncRead, AsyncWrite};
 use tokio_stream::StreamExt;
 use tokio_util::codec::{Decoder, Encoder, Framed};
 use tracing::{info, warn};
 
 use crate::{
-    backend::Backend,
-    cmd::{Command, CommandExecutor},
-    resp::{RespDecode, RespEncode, RespError, RespFrame},
+    backend::{Backend, ClientHandle},
+    cmd::{Command, CommandFlag, lookup_command},
+    config::Config,
+    resp::{
+        BulkString, RespArray, RespEncode, RespFrame, RespLimits, RespParser, SimpleError,
+        is_inline, parse_inline,
+    },
 };
 
-#[derive(Debug)]
-pub struct RespFrameCodec;
+/// 帧不完整时解析进度保存在 parser 中，下次收到数据后继续
+#[derive(Debug, Default)]
+pub struct RespFrameCodec {
+    parser: RespParser,
+}
 
-pub async fn stream_handler(stream: TcpStream, backend: Backend) -> anyhow::Result<()> {
-    let mut framed = Framed::new(stream, RespFrameCodec);
+impl RespFrameCodec {
+    pub fn new(limits: RespLimits) -> Self {
+        Self {
+            parser: RespParser::with_limits(limits),
+        }
+    }
+}
+
+pub async fn stream_handler<S>(
+    stream: S,
+    addr: SocketAddr,
+    backend: Backend,
+    config: Arc<Config>,
+) -> anyhow::Result<()>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    let client = backend.register_client(addr);
+    let mut framed = Framed::new(stream, RespFrameCodec::new(config.resp_limits()));
     loop {
-        match framed.next().await {
-            Some(frame) => {
-                let frame = frame?;
-
-                let request = RedisRequest {
-                    frame,
-                    backend: backend.clone(),
-                };
-                let response = request_handler(request).await?;
-                info!("write response: {:?}", response);
-                framed.send(response.frame).await?;
-            }
+        let mut frame = match framed.next().await {
+            Some(Ok(frame)) => frame,
+            Some(Err(e)) => return protocol_error(&mut framed, e).await,
             None => {
-                warn!("client closed connection");
+                info!("client {} closed connection", addr);
+                return Ok(());
             }
         };
+        let mut buffered = 0;
+        // 流水线：读缓冲区中已经完整的请求依次执行，回复攒到最后一起写出
+        loop {
+            let name = command_name(&frame);
+            // 阻塞命令可能一直等待，先把之前攒下的回复写出
+            if buffered > 0 && name.as_deref().is_some_and(is_blocking) {
+                framed.flush().await?;
+                buffered = 0;
+            }
+            client.touch(name);
+            let request = RedisRequest {
+                frame,
+                backend: backend.clone(),
+            };
+            let response = request_handler(request, &client).await;
+            info!("write response: {:?}", response);
+            framed.feed(response.frame).await?;
+            buffered += 1;
+            // 客户端读得慢时阻塞在写出上，不会无限缓存回复
+            if buffered >= config.pipeline_max_replies {
+                framed.flush().await?;
+                buffered = 0;
+            }
+            // 只取已经到达的请求，没有时不等待，先写出回复
+            match framed.next().now_or_never() {
+                Some(Some(Ok(next))) => frame = next,
+                Some(Some(Err(e))) => return protocol_error(&mut framed, e).await,
+                Some(None) => {
+                    framed.flush().await?;
+                    info!("client {} closed connection", addr);
+                    return Ok(());
+                }
+                None => break,
+            }
+        }
+        framed.flush().await?;
+    }
+}
+
+/// 读缓冲区已无法按 RESP 解析，回复错误（连同之前攒下的回复一起写出）后关闭连接
+async fn protocol_error<S>(
+    framed: &mut Framed<S, RespFrameCodec>,
+    e: anyhow::Error,
+) -> anyhow::Result<()>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    warn!("protocol error, closing connection: {:?}", e);
+    let reply = SimpleError::new(format!("ERR Protocol error: {}", e));
+    // 对端可能已经断开，写不出去也直接关闭
+    let _ = framed.send(reply.into()).await;
+    Ok(())
+}
+
+/// 请求的命令名（小写），用于记录连接状态
+fn command_name(frame: &RespFrame) -> Option<String> {
+    match frame {
+        RespFrame::Array(RespArray {
+            elements: Some(elements),
+        }) => match elements.first() {
+            Some(RespFrame::BulkString(BulkString {
+                content: Some(name),
+            })) => Some(String::from_utf8_lossy(name).to_ascii_lowercase()),
+            _ => None,
+        },
+        _ => None,
     }
 }
+
+/// 是否为阻塞命令（BLPOP / BRPOP / BLMOVE）
+fn is_blocking(name: &str) -> bool {
+    lookup_command(name.as_bytes()).is_some_and(|spec| spec.has_flag(CommandFlag::Blocking))
+}
+
 #[derive(Debug)]
 pub struct RedisRequest {
     frame: RespFrame,
@@ -46,12 +136,20 @@ pub struct RedisResponse {
     frame: RespFrame,
 }
 
-async fn request_handler(request: RedisRequest) -> anyhow::Result<RedisResponse> {
+/// 命令解析或参数错误回复 -ERR，连接保持打开
+async fn request_handler(request: RedisRequest, client: &ClientHandle) -> RedisResponse {
     let RedisRequest { frame, backend } = request;
-    let command: Command = frame.try_into()?;
-    info!("execute command: {:?}", command);
-    let frame = command.execute(&backend);
-    Ok(RedisResponse { frame })
+    info!("execute request: {:?}", frame);
+    let frame = match Command::execute_request(frame, &backend, client).await {
+        Ok(frame) => frame,
+        Err(e) => SimpleError::new(format!("ERR {}", e)).into(),
+    };
+    // 按连接协商的协议回复（HELLO 的回复使用切换后的协议），RESP2 客户端需要降级 RESP3 类型
+    let frame = match client.protocol() {
+        2 => frame.into_resp2(),
+        _ => frame,
+    };
+    RedisResponse { frame }
 }
 
 impl Encoder<RespFrame> for RespFrameCodec {
@@ -67,10 +165,268 @@ impl Decoder for RespFrameCodec {
     type Item = RespFrame;
     type Error = anyhow::Error;
     fn decode(&mut self, src: &mut BytesMut) -> Result<Option<Self::Item>, Self::Error> {
-        match RespFrame::decode(src) {
-            Ok(frame) => Ok(Some(frame)),
-            Err(RespError::NotComplete) => Ok(None),
-            Err(e) => Err(e.into()),
+        // 解析到一半的 RESP 帧中间不会出现内联命令
+        while !self.parser.is_partial() && is_inline(src) {
+            match parse_inline(src)? {
+                // 空行直接忽略，不回复
+                Some(RespArray {
+                    elements: Some(args),
+                }) if args.is_empty() => continue,
+                Some(array) => return Ok(Some(array.into())),
+                None => return Ok(None),
+            }
+        }
+        Ok(self.parser.parse(src)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        pin::Pin,
+        sync::atomic::{AtomicUsize, Ordering},
+        task::{Context, Poll},
+        time::Duration,
+    };
+
+    use tokio::io::{AsyncReadExt, AsyncWriteExt, DuplexStream, ReadBuf, duplex};
+
+    use super::*;
+
+    /// 记录 flush 次数的连接
+    struct CountingStream {
+        inner: DuplexStream,
+        flushes: Arc<AtomicUsize>,
+    }
+    impl AsyncRead for CountingStream {
+        fn poll_read(
+            mut self: Pin<&mut Self>,
+            cx: &mut Context<'_>,
+            buf: &mut ReadBuf<'_>,
+        ) -> Poll<std::io::Result<()>> {
+            Pin::new(&mut self.inner).poll_read(cx, buf)
+        }
+    }
+    impl AsyncWrite for CountingStream {
+        fn poll_write(
+            mut self: Pin<&mut Self>,
+            cx: &mut Context<'_>,
+            buf: &[u8],
+        ) -> Poll<std::io::Result<usize>> {
+            Pin::new(&mut self.inner).poll_write(cx, buf)
+        }
+        fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+            self.flushes.fetch_add(1, Ordering::Relaxed);
+            Pin::new(&mut self.inner).poll_flush(cx)
+        }
+        fn poll_shutdown(
+            mut self: Pin<&mut Self>,
+            cx: &mut Context<'_>,
+        ) -> Poll<std::io::Result<()>> {
+            Pin::new(&mut self.inner).poll_shutdown(cx)
         }
     }
+
+    fn test_addr() -> SocketAddr {
+        "127.0.0.1:40000".parse().unwrap()
+    }
+
+    /// 一次写入 n 条 PING，返回读到的回复和 flush 次数
+    async fn pipeline(n: usize, max_replies: usize) -> anyhow::Result<(String, usize)> {
+        let (mut client, server) = duplex(64 * 1024);
+        let flushes = Arc::new(AtomicUsize::new(0));
+        let stream = CountingStream {
+            inner: server,
+            flushes: flushes.clone(),
+        };
+        let config = Arc::new(Config {
+            pipeline_max_replies: max_replies,
+            ..Default::default()
+        });
+        tokio::spawn(stream_handler(stream, test_addr(), Backend::new(), config));
+        client
+            .write_all("*1\r\n$4\r\nping\r\n".repeat(n).as_bytes())
+            .await?;
+        let mut replies = vec![0; "+PONG\r\n".len() * n];
+        client.read_exact(&mut replies).await?;
+        Ok((String::from_utf8(replies)?, flushes.load(Ordering::Relaxed)))
+    }
+
+    #[tokio::test]
+    async fn test_pipeline_single_flush() -> anyhow::Result<()> {
+        let (replies, flushes) = pipeline(100, 1024).await?;
+        assert_eq!(replies, "+PONG\r\n".repeat(100));
+        assert_eq!(flushes, 1);
+
+        // 超过上限时分批写出
+        let (replies, flushes) = pipeline(100, 30).await?;
+        assert_eq!(replies, "+PONG\r\n".repeat(100));
+        assert_eq!(flushes, 4);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_pipeline_flushes_before_blocking() -> anyhow::Result<()> {
+        let (mut client, server) = duplex(64 * 1024);
+        tokio::spawn(stream_handler(
+            server,
+            test_addr(),
+            Backend::new(),
+            Arc::new(Config::default()),
+        ));
+        // BLPOP 一直阻塞，之前的回复也要先写出
+        client
+            .write_all(b"*3\r\n$3\r\nset\r\n$1\r\na\r\n$1\r\n1\r\n*3\r\n$5\r\nblpop\r\n$1\r\nq\r\n$1\r\n0\r\n")
+            .await?;
+        let mut reply = vec![0; "+OK\r\n".len()];
+        tokio::time::timeout(Duration::from_secs(1), client.read_exact(&mut reply)).await??;
+        assert_eq!(reply, b"+OK\r\n");
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_connection_lifecycle() -> anyhow::Result<()> {
+        let backend = Backend::new();
+        let (mut client, server) = duplex(64 * 1024);
+        let handle = tokio::spawn(stream_handler(
+            server,
+            test_addr(),
+            backend.clone(),
+            Arc::new(Config::default()),
+        ));
+        // 参数错误回复 -ERR，连接继续可用
+        client
+            .write_all(b"*1\r\n$3\r\nget\r\nset k v nx xx\r\n*1\r\n$4\r\nping\r\n")
+            .await?;
+        let mut buf = vec![0; 1024];
+        let mut replies = String::new();
+        while !replies.ends_with("+PONG\r\n") {
+            let n = client.read(&mut buf).await?;
+            replies.push_str(std::str::from_utf8(&buf[..n])?);
+        }
+        // 错误信息与 Redis 完全一致
+        assert_eq!(
+            replies,
+            "-ERR wrong number of arguments for 'get' command\r\n-ERR syntax error\r\n+PONG\r\n"
+        );
+        let clients = backend.clients();
+        assert_eq!(clients.len(), 1);
+        assert_eq!(clients[0].addr, test_addr());
+        assert_eq!(clients[0].commands, 3);
+        assert_eq!(clients[0].last_command.as_deref(), Some("ping"));
+
+        // 对端关闭后处理任务退出，连接从客户端表中移除
+        drop(client);
+        handle.await??;
+        assert!(backend.clients().is_empty());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_protocol_error_closes_connection() -> anyhow::Result<()> {
+        let backend = Backend::new();
+        let (mut client, server) = duplex(64 * 1024);
+        let handle = tokio::spawn(stream_handler(
+            server,
+            test_addr(),
+            backend.clone(),
+            Arc::new(Config::default()),
+        ));
+        // 之前的请求照常回复，然后回复协议错误并关闭
+        client
+            .write_all(b"*1\r\n$4\r\nping\r\n*1\r\n$4\r\nping\r\n*x\r\n")
+            .await?;
+        let mut replies = String::new();
+        client.read_to_string(&mut replies).await?;
+        assert!(replies.starts_with("+PONG\r\n+PONG\r\n-ERR Protocol error: "));
+        handle.await??;
+        assert!(backend.clients().is_empty());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_hello_negotiates_protocol() -> anyhow::Result<()> {
+        let (mut client, server) = duplex(64 * 1024);
+        tokio::spawn(stream_handler(
+            server,
+            test_addr(),
+            Backend::new(),
+            Arc::new(Config::default()),
+        ));
+        let mut buf = vec![0; 1024];
+        // RESP2 客户端读不存在的 key 得到 $-1
+        client.write_all(b"*2\r\n$3\r\nget\r\n$1\r\nk\r\n").await?;
+        let n = client.read(&mut buf).await?;
+        assert_eq!(&buf[..n], b"$-1\r\n");
+
+        client
+            .write_all(b"*2\r\n$5\r\nhello\r\n$1\r\n3\r\n")
+            .await?;
+        let n = client.read(&mut buf).await?;
+        assert!(buf[..n].starts_with(b"%7\r\n"));
+        client.write_all(b"*2\r\n$3\r\nget\r\n$1\r\nk\r\n").await?;
+        let n = client.read(&mut buf).await?;
+        assert_eq!(&buf[..n], b"_\r\n");
+
+        // 切回 RESP2 时 HELLO 的回复已经是展开的数组
+        client
+            .write_all(b"*2\r\n$5\r\nhello\r\n$1\r\n2\r\n")
+            .await?;
+        let n = client.read(&mut buf).await?;
+        assert!(buf[..n].starts_with(b"*14\r\n"));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_protocol_limits_close_connection() -> anyhow::Result<()> {
+        let (mut client, server) = duplex(64 * 1024);
+        let config = Config {
+            proto_max_bulk_len: 16,
+            ..Default::default()
+        };
+        let handle = tokio::spawn(stream_handler(
+            server,
+            test_addr(),
+            Backend::new(),
+            Arc::new(config),
+        ));
+        // 声明的长度超限，不等数据发完就回复错误并关闭
+        client
+            .write_all(b"*3\r\n$3\r\nset\r\n$1\r\nk\r\n$1000000\r\n")
+            .await?;
+        let mut replies = String::new();
+        client.read_to_string(&mut replies).await?;
+        assert_eq!(
+            replies,
+            "-ERR Protocol error: invalid bulk length: 1000000 exceeds proto-max-bulk-len 16\r\n"
+        );
+        handle.await??;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_inline_commands() -> anyhow::Result<()> {
+        let (mut client, server) = duplex(64 * 1024);
+        tokio::spawn(stream_handler(
+            server,
+            test_addr(),
+            Backend::new(),
+            Arc::new(Config::default()),
+        ));
+        // 内联命令与 RESP 请求可以混在一起，空行不回复
+        client
+            .write_all(b"PING\r\n\r\nSET k \"a b\"\n*2\r\n$3\r\nget\r\n$1\r\nk\r\nget k\n")
+            .await?;
+        let expected = "+PONG\r\n+OK\r\n$3\r\na b\r\n$3\r\na b\r\n";
+        let mut replies = vec![0; expected.len()];
+        client.read_exact(&mut replies).await?;
+        assert_eq!(String::from_utf8(replies)?, expected);
+
+        // 引号不匹配按协议错误处理
+        client.write_all(b"set k \"a\n").await?;
+        let mut replies = String::new();
+        client.read_to_string(&mut replies).await?;
+        assert!(replies.starts_with("-ERR Protocol error: "));
+        Ok(())
+    }
 }
@@ -41,23 +41,17 @@ impl RespDecode for RespArray {
     const PREFIX: &'static str = "*";
     const TYPE: &'static str = "RespArray";
     fn decode(buf: &mut BytesMut) -> Result<Self, RespError> {
-        let len = check_len(buf, Self::PREFIX, Self::TYPE)?;
-        match len {
-            0 => Ok(RespArray::null()),
-            _ => {
-                let mut elements = Vec::with_capacity(len);
-                for _ in 0..len {
-                    let element = RespFrame::decode(buf)?;
-                    elements.push(element);
-                }
-                Ok(RespArray::from_vec(elements))
-            }
+        match decode_frame(buf, Self::PREFIX, Self::TYPE)? {
+            RespFrame::Array(array) => Ok(array),
+            RespFrame::RespNullArray(_) => Ok(RespArray::null()),
+            frame => Err(RespError::InvalidFrameType(format!(
+                "{} expect: {} , but got {:?}",
+                Self::TYPE,
+                Self::PREFIX,
+                frame
+            ))),
         }
     }
-    fn expect_length(buf: &[u8]) -> Result<usize, RespError> {
-        let (end, len) = extract_len(buf, Self::PREFIX, Self::TYPE)?;
-        calc_total_length(buf, end, len, Self::PREFIX)
-    }
 }
 
 impl RespEncode for RespArray {
@@ -112,9 +106,6 @@ impl RespDecode for RespNullArray {
         extract_fix(buf, "*-1\r\n", Self::TYPE)?;
         Ok(RespNullArray)
     }
-    fn expect_length(_buf: &[u8]) -> Result<usize, RespError> {
-        Ok(Self::PREFIX.len())
-    }
 }
 #[cfg(test)]
 mod tests {
@@ -0,0 +1,76 @@
+use std::collections::BTreeMap;
+
+use super::{SimpleString, preludes::*};
+
+/// 附加在回复之前的辅助信息，格式与 map 相同，客户端可以忽略
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct RespAttribute {
+    pub pairs: BTreeMap<String, RespFrame>,
+}
+impl Deref for RespAttribute {
+    type Target = BTreeMap<String, RespFrame>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.pairs
+    }
+}
+impl DerefMut for RespAttribute {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.pairs
+    }
+}
+
+// | Attribute     | `|`    | "|<count>\r\n<key1><val1>...<keyN><valN>"                      |
+impl RespDecode for RespAttribute {
+    const PREFIX: &'static str = "|";
+    const TYPE: &'static str = "RespAttribute";
+    fn decode(buf: &mut BytesMut) -> Result<Self, RespError> {
+        match decode_frame(buf, Self::PREFIX, Self::TYPE)? {
+            RespFrame::Attribute(attribute) => Ok(attribute),
+            frame => Err(RespError::InvalidFrameType(format!(
+                "{} expect: {} , but got {:?}",
+                Self::TYPE,
+                Self::PREFIX,
+                frame
+            ))),
+        }
+    }
+}
+
+impl RespEncode for RespAttribute {
+    fn encode(&self) -> Vec<u8> {
+        let count_str = self.pairs.len().to_string();
+        let mut result = Vec::with_capacity(1 + count_str.len() + 2);
+        result.extend_from_slice(b"|");
+        result.extend_from_slice(count_str.as_bytes());
+        result.extend_from_slice(b"\r\n");
+        for (key, value) in &self.pairs {
+            result.extend_from_slice(&SimpleString::new(key.clone()).encode());
+            result.extend_from_slice(&value.encode());
+        }
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{super::*, *};
+    #[test]
+    fn test_attribute_round_trip() {
+        let mut attribute = RespAttribute::default();
+        attribute.insert("key-popularity".to_string(), RespDouble::new(0.1923).into());
+        attribute.insert("ttl".to_string(), RespInteger::new(3600).into());
+        let frame: RespFrame = attribute.into();
+        let encoded = frame.encode();
+        assert_eq!(
+            encoded,
+            b"|2\r\n+key-popularity\r\n,+0.1923\r\n+ttl\r\n:3600\r\n"
+        );
+        let mut buf = BytesMut::from(&encoded[..]);
+        assert_eq!(RespFrame::decode(&mut buf), Ok(frame));
+        assert!(buf.is_empty());
+
+        let mut buf = BytesMut::from("|1\r\n+ttl\r\n");
+        assert_eq!(RespAttribute::decode(&mut buf), Err(RespError::NotComplete));
+    }
+}
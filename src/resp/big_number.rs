@@ -0,0 +1,72 @@
+use super::preludes::*;
+
+/// 超出 i64 范围的整数，按十进制字符串保存
+#[derive(Debug, Clone, PartialEq)]
+pub struct RespBigNumber {
+    pub value: String,
+}
+
+impl RespBigNumber {
+    pub fn new(value: impl Into<String>) -> Self {
+        Self {
+            value: value.into(),
+        }
+    }
+}
+
+// | Big Number    | `(`    | "([+|-]<number>\r\n"                                           |
+impl RespDecode for RespBigNumber {
+    const PREFIX: &'static str = "(";
+    const TYPE: &'static str = "RespBigNumber";
+    fn decode(buf: &mut BytesMut) -> Result<Self, RespError> {
+        let end = compute_end_with_crlf(buf, Self::PREFIX, Self::TYPE)?;
+        let value = str::from_utf8(&buf[Self::PREFIX.len()..end - 2])?;
+        let digits = value.strip_prefix(['+', '-']).unwrap_or(value);
+        if digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_digit()) {
+            return Err(RespError::InvalidFrame(format!(
+                "{} expect digits, but got {:?}",
+                Self::TYPE,
+                value
+            )));
+        }
+        Ok(RespBigNumber::new(extract_end_string(
+            buf,
+            Self::PREFIX,
+            Self::TYPE,
+        )?))
+    }
+}
+
+impl RespEncode for RespBigNumber {
+    fn encode(&self) -> Vec<u8> {
+        let mut result = Vec::with_capacity(1 + self.value.len() + 2);
+        result.extend_from_slice(b"(");
+        result.extend_from_slice(self.value.as_bytes());
+        result.extend_from_slice(b"\r\n");
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn test_big_number_round_trip() {
+        let frame: RespFrame =
+            RespBigNumber::new("3492890328409238509324850943850943825024385").into();
+        let encoded = frame.encode();
+        assert_eq!(encoded, b"(3492890328409238509324850943850943825024385\r\n");
+        let mut buf = BytesMut::from(&encoded[..]);
+        assert_eq!(RespFrame::decode(&mut buf), Ok(frame));
+
+        let mut buf = BytesMut::from("(-12345678901234567890\r\n");
+        assert_eq!(
+            RespBigNumber::decode(&mut buf),
+            Ok(RespBigNumber::new("-12345678901234567890"))
+        );
+        let mut buf = BytesMut::from("(12a\r\n");
+        assert!(RespBigNumber::decode(&mut buf).is_err());
+        let mut buf = BytesMut::from("(-\r\n");
+        assert!(RespBigNumber::decode(&mut buf).is_err());
+    }
+}
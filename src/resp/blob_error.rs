@@ -0,0 +1,55 @@
+use super::preludes::*;
+
+/// 二进制安全的错误，内容可以包含 \r\n
+#[derive(Debug, Clone, PartialEq)]
+pub struct RespBlobError {
+    pub content: Bytes,
+}
+
+impl RespBlobError {
+    pub fn new(content: impl Into<Bytes>) -> Self {
+        Self {
+            content: content.into(),
+        }
+    }
+}
+
+// | Blob Error    | `!`    | "!<length>\r\n<error>\r\n"                                     |
+impl RespDecode for RespBlobError {
+    const PREFIX: &'static str = "!";
+    const TYPE: &'static str = "RespBlobError";
+    fn decode(buf: &mut BytesMut) -> Result<Self, RespError> {
+        Ok(RespBlobError::new(extract_blob(
+            buf,
+            Self::PREFIX,
+            Self::TYPE,
+        )?))
+    }
+}
+
+impl RespEncode for RespBlobError {
+    fn encode(&self) -> Vec<u8> {
+        let len_str = self.content.len().to_string();
+        let mut result = Vec::with_capacity(1 + len_str.len() + 2 + self.content.len() + 2);
+        result.extend_from_slice(b"!");
+        result.extend_from_slice(len_str.as_bytes());
+        result.extend_from_slice(b"\r\n");
+        result.extend_from_slice(&self.content);
+        result.extend_from_slice(b"\r\n");
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn test_blob_error_round_trip() {
+        let frame: RespFrame = RespBlobError::new("SYNTAX invalid\r\nsyntax").into();
+        let encoded = frame.encode();
+        assert_eq!(encoded, b"!22\r\nSYNTAX invalid\r\nsyntax\r\n");
+        let mut buf = BytesMut::from(&encoded[..]);
+        assert_eq!(RespFrame::decode(&mut buf), Ok(frame));
+        assert!(buf.is_empty());
+    }
+}
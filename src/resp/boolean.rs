@@ -29,9 +29,6 @@ impl RespDecode for RespBoolean {
         };
         Ok(RespBoolean::new(value))
     }
-    fn expect_length(_buf: &[u8]) -> Result<usize, RespError> {
-        Ok(4)
-    }
 }
 
 impl RespEncode for RespBoolean {
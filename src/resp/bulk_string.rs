@@ -1,14 +1,16 @@
+use bytes::Bytes;
+
 use super::preludes::*;
 
 #[derive(Debug, Clone, PartialEq, PartialOrd)]
 pub struct BulkString {
-    pub content: Option<Vec<u8>>,
+    pub content: Option<Bytes>,
 }
 
 impl BulkString {
-    pub fn new(content: Vec<u8>) -> Self {
+    pub fn new(content: impl Into<Bytes>) -> Self {
         Self {
-            content: Some(content),
+            content: Some(content.into()),
         }
     }
 
@@ -16,9 +18,10 @@ impl BulkString {
         Self { content: None }
     }
 
-    pub fn from_slice(content: impl Into<Vec<u8>>) -> Self {
+    /// 拷贝一份数据
+    pub fn from_slice(content: impl AsRef<[u8]>) -> Self {
         Self {
-            content: Some(content.into()),
+            content: Some(Bytes::copy_from_slice(content.as_ref())),
         }
     }
 }
@@ -27,27 +30,11 @@ impl BulkString {
 impl RespDecode for BulkString {
     const PREFIX: &'static str = "$";
     fn decode(buf: &mut BytesMut) -> Result<Self, RespError> {
-        let (len_end, len) = extract_len(buf, Self::PREFIX, Self::TYPE)?;
-        let remain = &buf[len_end + CRLF.len()..];
-        if remain.len() < len_end + CRLF.len() {
-            return Err(RespError::NotComplete);
-        }
-        let end = compute_end(&buf[len_end + CRLF.len()..], "", Self::TYPE)?;
-        if end != len {
-            return Err(RespError::InvalidFrameLength(format!(
-                "{} expect: {:?} , but got {:?}",
-                Self::TYPE,
-                len,
-                end
-            )));
-        }
-        buf.advance(len_end + CRLF.len());
-
-        Ok(BulkString::new(split_vec(buf, len)?))
-    }
-    fn expect_length(buf: &[u8]) -> Result<usize, RespError> {
-        let (end, len) = extract_len(buf, Self::PREFIX, Self::TYPE)?;
-        Ok(end + CRLF.len() + len + CRLF.len())
+        Ok(BulkString::new(extract_blob(
+            buf,
+            Self::PREFIX,
+            Self::TYPE,
+        )?))
     }
 }
 
@@ -85,25 +72,28 @@ impl RespDecode for NullBulkString {
         extract_fix(buf, Self::PREFIX, Self::TYPE)?;
         Ok(NullBulkString)
     }
-    fn expect_length(_buf: &[u8]) -> Result<usize, RespError> {
-        Ok(Self::PREFIX.len())
-    }
 }
 impl From<&str> for RespFrame {
     fn from(s: &str) -> Self {
-        BulkString::new(s.as_bytes().to_vec()).into()
+        BulkString::from_slice(s).into()
+    }
+}
+
+impl From<Bytes> for RespFrame {
+    fn from(s: Bytes) -> Self {
+        BulkString::new(s).into()
     }
 }
 
 impl From<&[u8]> for RespFrame {
     fn from(s: &[u8]) -> Self {
-        BulkString::new(s.to_vec()).into()
+        BulkString::from_slice(s).into()
     }
 }
 
 impl<const N: usize> From<&[u8; N]> for RespFrame {
     fn from(s: &[u8; N]) -> Self {
-        BulkString::new(s.to_vec()).into()
+        BulkString::from_slice(s).into()
     }
 }
 
@@ -130,6 +120,63 @@ mod tests {
         assert_eq!(bulk_string, BulkString::from_slice(b"hello"));
     }
     #[test]
+    fn test_bulk_string_decode_zero_copy() {
+        let mut buf = BytesMut::from("$5\r\nhello\r\n+OK\r\n");
+        let start = buf.as_ptr() as usize;
+        let end = start + buf.len();
+        let content = BulkString::decode(&mut buf).unwrap().content.unwrap();
+        // 数据指向原来的读缓冲区
+        assert_eq!(content, "hello");
+        assert!((start..end).contains(&(content.as_ptr() as usize)));
+        assert_eq!(buf.as_ref(), b"+OK\r\n");
+    }
+    #[test]
+    fn test_bulk_string_decode_edge_cases() {
+        // 空串和内容里带 CRLF 的二进制数据
+        let mut buf = BytesMut::from("$0\r\n\r\n$4\r\n\r\n\r\n\r\n");
+        assert_eq!(
+            BulkString::decode(&mut buf).unwrap(),
+            BulkString::from_slice(b"")
+        );
+        assert_eq!(
+            BulkString::decode(&mut buf).unwrap(),
+            BulkString::from_slice(b"\r\n\r\n")
+        );
+        assert!(buf.is_empty());
+
+        // 数据不完整时不消耗缓冲区
+        let mut buf = BytesMut::from("$5\r\nhel");
+        assert!(matches!(
+            BulkString::decode(&mut buf),
+            Err(RespError::NotComplete)
+        ));
+        assert_eq!(buf.as_ref(), b"$5\r\nhel");
+
+        // 长度与数据不符
+        let mut buf = BytesMut::from("$3\r\nhello\r\n");
+        assert!(matches!(
+            BulkString::decode(&mut buf),
+            Err(RespError::InvalidFrameLength(_))
+        ));
+        let mut buf = BytesMut::from("$x\r\nhello\r\n");
+        assert!(BulkString::decode(&mut buf).is_err());
+    }
+    #[test]
+    fn test_array_decode_shares_buffer() {
+        let mut buf = BytesMut::from("*2\r\n$3\r\nget\r\n$3\r\nkey\r\n");
+        let start = buf.as_ptr() as usize;
+        let end = start + buf.len();
+        let array = RespArray::decode(&mut buf).unwrap();
+        for frame in array.elements.unwrap() {
+            let RespFrame::BulkString(bulk) = frame else {
+                panic!("expect bulk string");
+            };
+            let content = bulk.content.unwrap();
+            assert!((start..end).contains(&(content.as_ptr() as usize)));
+        }
+        assert!(buf.is_empty());
+    }
+    #[test]
     fn test_null_bulk_string_decode() {
         let mut buf = BytesMut::new();
         buf.extend_from_slice(b"$-1\r\n".as_ref());
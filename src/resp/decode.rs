@@ -1,28 +1,42 @@
-use super::{SimpleString, preludes::*};
+use super::{RespParser, preludes::*};
 pub const CRLF: &[u8] = b"\r\n";
 pub const CRLF_LEN: usize = CRLF.len();
 use bytes::Bytes;
 
+/// 提取一行并解析为字符串（integer、double 等需要按文本解析的类型），消耗buf中的数据
 pub fn extract_end_string(
     buf: &mut BytesMut,
     prefix: &str,
     type_name: &str,
 ) -> Result<String, RespError> {
-    Ok(String::from_utf8(extract_end_vec(buf, prefix, type_name)?)?)
-}
-/// 提取字节数组，消耗buf中的数据，不包含前缀和CRLF
-pub fn extract_end_vec(
-    buf: &mut BytesMut,
-    prefix: &str,
-    type_name: &str,
-) -> Result<Vec<u8>, RespError> {
     let s = consume_byte_mut(buf, prefix, type_name)?;
-    Ok(str::from_utf8(s.as_ref())?.as_bytes().to_vec())
+    Ok(str::from_utf8(&s)?.to_string())
 }
-/// bulk_string: 提取字节数组，消耗buf中的数据，不包含前缀和CRLF
-pub fn split_vec(buf: &mut BytesMut, len: usize) -> Result<Vec<u8>, RespError> {
-    let data: BytesMut = buf.split_to(len + CRLF_LEN);
-    Ok(data[..len].to_vec())
+/// bulk_string: 切出长度为 len 的数据，消耗buf中的数据，不包含CRLF
+/// 返回的 Bytes 与读缓冲区共享内存，不拷贝；需要长期保存时由调用方拷贝
+pub fn split_bytes(buf: &mut BytesMut, len: usize) -> Bytes {
+    let mut data = buf.split_to(len + CRLF_LEN);
+    data.truncate(len);
+    data.freeze()
+}
+/// 提取带长度前缀的二进制数据（bulk string、blob error、verbatim string），消耗buf中的数据
+/// 按声明的长度截取，数据中可以包含 \r\n
+pub fn extract_blob(buf: &mut BytesMut, prefix: &str, type_name: &str) -> Result<Bytes, RespError> {
+    let (len_end, len) = extract_len(buf, prefix, type_name)?;
+    let remain = &buf[len_end + CRLF_LEN..];
+    if remain.len() < len + CRLF_LEN {
+        return Err(RespError::NotComplete);
+    }
+    if !remain[len..].starts_with(CRLF) {
+        return Err(RespError::InvalidFrameLength(format!(
+            "{} expect: {:?} , but got {:?}",
+            type_name,
+            len,
+            compute_end(remain, "", type_name).unwrap_or(remain.len())
+        )));
+    }
+    buf.advance(len_end + CRLF_LEN);
+    Ok(split_bytes(buf, len))
 }
 /// 提取length，不消耗buf中的数据
 /// 返回
@@ -36,18 +50,15 @@ pub fn extract_len(buf: &[u8], prefix: &str, type_name: &str) -> Result<(usize,
     ))
 }
 
-/// 提取长度并判断是否满足期望大小
-/// 返回
-/// 结束位置\r'
-/// 长度
-pub fn check_len(buf: &mut BytesMut, prefix: &str, type_name: &str) -> Result<usize, RespError> {
-    let (end, len) = extract_len(buf, prefix, type_name)?;
-    let total_length = calc_total_length(buf, end, len, prefix)?;
-    if buf.len() < total_length {
-        return Err(RespError::NotComplete);
-    }
-    buf.advance(end + CRLF_LEN);
-    Ok(len)
+/// 解析一个完整的帧，先检查前缀，聚合类型与连接上的 RespParser 共用同一套解析逻辑
+/// 数据不完整时返回 NotComplete，buf 中已解析的部分会被消耗，增量解析应使用 RespParser
+pub fn decode_frame(
+    buf: &mut BytesMut,
+    prefix: &str,
+    type_name: &str,
+) -> Result<RespFrame, RespError> {
+    starts_with(buf, prefix, type_name)?;
+    RespParser::new().parse(buf)?.ok_or(RespError::NotComplete)
 }
 
 /// 提取固定长度的字节数组，消耗buf中的数据，不包含前缀和CRLF
@@ -90,7 +101,7 @@ pub fn compute_end_with_crlf(
     let end = compute_end(buf, prefix, type_name)?;
     Ok(end + CRLF_LEN)
 }
-/// 提取字节数组，消耗buf中的数据，不包含前缀和CRLF
+/// 提取一行，消耗buf中的数据，不包含前缀和CRLF，不拷贝
 pub fn consume_byte_mut(
     buf: &mut BytesMut,
     prefix: &str,
@@ -110,43 +121,23 @@ pub fn extract_byte_slice<'a>(
     Ok((end, &buf[0..end + CRLF_LEN]))
 }
 
-pub fn calc_total_length(
-    buf: &[u8],
-    end: usize,
-    len: usize,
-    prefix: &str,
-) -> Result<usize, RespError> {
-    let mut total = end + CRLF_LEN;
-    let mut data = &buf[total..];
-    match prefix {
-        "*" | "~" => {
-            for _ in 0..len {
-                let len = RespFrame::expect_length(data)?;
-                data = &data[len..];
-                total += len;
-            }
-            Ok(total)
-        }
-        "%" => {
-            for _ in 0..len {
-                let len = SimpleString::expect_length(data)?;
-
-                data = &data[len..];
-                total += len;
-
-                let len = RespFrame::expect_length(data)?;
-                data = &data[len..];
-                total += len;
-            }
-            Ok(total)
-        }
-        _ => Ok(len + CRLF_LEN),
-    }
-}
 #[cfg(test)]
 mod tests {
     use super::*;
     #[test]
+    fn test_decode_frame_incomplete() {
+        let mut buf = BytesMut::from("*2\r\n$3\r\nget\r\n$5\r\nhel");
+        assert_eq!(
+            decode_frame(&mut buf, "*", "RespArray"),
+            Err(RespError::NotComplete)
+        );
+        let mut buf = BytesMut::from("+OK\r\n");
+        assert!(matches!(
+            decode_frame(&mut buf, "*", "RespArray"),
+            Err(RespError::InvalidFrameType(_))
+        ));
+    }
+    #[test]
     fn test_extract_end_string() {
         let mut buf = BytesMut::from("+OK\r\n");
         let end = extract_end_string(&mut buf, "+", "SimpleString");
@@ -26,9 +26,6 @@ impl RespDecode for RespDouble {
 
         Ok(RespDouble::new(value))
     }
-    fn expect_length(buf: &[u8]) -> Result<usize, RespError> {
-        compute_end_with_crlf(buf, Self::PREFIX, Self::TYPE)
-    }
 }
 
 impl RespEncode for RespDouble {
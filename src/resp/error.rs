@@ -2,12 +2,14 @@ use super::preludes::*;
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct SimpleError {
-    pub msg: String,
+    pub msg: Bytes,
 }
 
 impl SimpleError {
     pub fn new(msg: impl Into<String>) -> Self {
-        Self { msg: msg.into() }
+        Self {
+            msg: Bytes::from(msg.into()),
+        }
     }
 }
 // | Error         | `-`    | "-Error message\r\n"                                           |
@@ -15,14 +17,9 @@ impl RespDecode for SimpleError {
     const PREFIX: &'static str = "-";
     const TYPE: &'static str = "SimpleError";
     fn decode(buf: &mut BytesMut) -> Result<Self, RespError> {
-        Ok(SimpleError::new(extract_end_string(
-            buf,
-            Self::PREFIX,
-            Self::TYPE,
-        )?))
-    }
-    fn expect_length(buf: &[u8]) -> Result<usize, RespError> {
-        compute_end_with_crlf(buf, Self::PREFIX, Self::TYPE)
+        Ok(SimpleError {
+            msg: consume_byte_mut(buf, Self::PREFIX, Self::TYPE)?,
+        })
     }
 }
 
@@ -30,7 +27,7 @@ impl RespEncode for SimpleError {
     fn encode(&self) -> Vec<u8> {
         let mut result = Vec::with_capacity(1 + self.msg.len() + 2); // - + msg + \r\n
         result.extend_from_slice(b"-");
-        result.extend_from_slice(self.msg.as_bytes());
+        result.extend_from_slice(&self.msg);
         result.extend_from_slice(b"\r\n");
         result
     }
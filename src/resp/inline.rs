@@ -0,0 +1,158 @@
+use super::{BulkString, RespArray, preludes::*};
+
+/// RESP 各类型的起始符，其他字节开头的请求按内联命令解析
+const TYPE_BYTES: &[u8] = b"+-:$*#,%~_=(!|>";
+/// 内联命令一行的最大长度
+const MAX_INLINE_LEN: usize = 64 * 1024;
+
+/// 是否是内联命令（telnet、redis-cli 内联模式直接输入的一行文本）
+pub fn is_inline(buf: &[u8]) -> bool {
+    buf.first().is_some_and(|b| !TYPE_BYTES.contains(b))
+}
+
+/// 解析以 \n 或 \r\n 结尾的一行内联命令，转换为 bulk string 数组
+/// 数据不完整时返回 None；空行被消耗并返回空数组，调用方应忽略
+pub fn parse_inline(buf: &mut BytesMut) -> Result<Option<RespArray>, RespError> {
+    let Some(end) = buf.iter().position(|b| *b == b'\n') else {
+        if buf.len() > MAX_INLINE_LEN {
+            return Err(RespError::LimitExceeded("too big inline request".into()));
+        }
+        return Ok(None);
+    };
+    let line = buf.split_to(end + 1);
+    let line = line[..end].strip_suffix(b"\r").unwrap_or(&line[..end]);
+    let args = split_args(line)?;
+    Ok(Some(RespArray::from_vec(
+        args.into_iter()
+            .map(|arg| BulkString::new(arg).into())
+            .collect(),
+    )))
+}
+
+/// 按空白分割参数，与 Redis 的规则相同：
+/// 双引号内支持 \n \r \t \b \a \\ \" 和 \xHH 转义，单引号内只支持 \'，
+/// 右引号后必须是空白或行尾
+pub fn split_args(line: &[u8]) -> Result<Vec<Vec<u8>>, RespError> {
+    let unbalanced = || RespError::InvalidFrame("unbalanced quotes in request".into());
+    let mut args = Vec::new();
+    let mut i = 0;
+    loop {
+        while line.get(i).is_some_and(u8::is_ascii_whitespace) {
+            i += 1;
+        }
+        if i >= line.len() {
+            return Ok(args);
+        }
+        let mut arg = Vec::new();
+        match line[i] {
+            quote @ (b'"' | b'\'') => {
+                i += 1;
+                loop {
+                    match (line.get(i), line.get(i + 1)) {
+                        (None, _) => return Err(unbalanced()),
+                        (Some(b'\\'), Some(b'x'))
+                            if quote == b'"'
+                                && line.get(i + 2).is_some_and(u8::is_ascii_hexdigit)
+                                && line.get(i + 3).is_some_and(u8::is_ascii_hexdigit) =>
+                        {
+                            let hex = str::from_utf8(&line[i + 2..i + 4])?;
+                            arg.push(u8::from_str_radix(hex, 16)?);
+                            i += 4;
+                        }
+                        (Some(b'\\'), Some(&c)) if quote == b'"' => {
+                            arg.push(match c {
+                                b'n' => b'\n',
+                                b'r' => b'\r',
+                                b't' => b'\t',
+                                b'b' => 0x08,
+                                b'a' => 0x07,
+                                c => c,
+                            });
+                            i += 2;
+                        }
+                        (Some(b'\\'), Some(b'\'')) if quote == b'\'' => {
+                            arg.push(b'\'');
+                            i += 2;
+                        }
+                        (Some(&c), next) if c == quote => {
+                            if next.is_some_and(|b| !b.is_ascii_whitespace()) {
+                                return Err(unbalanced());
+                            }
+                            i += 1;
+                            break;
+                        }
+                        (Some(&c), _) => {
+                            arg.push(c);
+                            i += 1;
+                        }
+                    }
+                }
+            }
+            _ => {
+                while let Some(&c) = line.get(i) {
+                    if c.is_ascii_whitespace() {
+                        break;
+                    }
+                    arg.push(c);
+                    i += 1;
+                }
+            }
+        }
+        args.push(arg);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn split(line: &str) -> Result<Vec<String>, RespError> {
+        Ok(split_args(line.as_bytes())?
+            .into_iter()
+            .map(|arg| String::from_utf8_lossy(&arg).to_string())
+            .collect())
+    }
+
+    #[test]
+    fn test_split_args() -> Result<(), RespError> {
+        assert_eq!(split("  set   key value ")?, ["set", "key", "value"]);
+        assert_eq!(
+            split(r#"set "a b" 'c d' "\x41\n\"" 'it\'s' """#)?,
+            ["set", "a b", "c d", "A\n\"", "it's", ""]
+        );
+        assert!(split("").unwrap().is_empty());
+        assert!(split(r#"set "abc"#).is_err());
+        assert!(split(r#"set "a"b"#).is_err());
+        assert!(split("set 'abc").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_inline() -> Result<(), RespError> {
+        let mut buf = BytesMut::from("PING\r\nset a 'b c'\n\r\nGET");
+        assert!(is_inline(&buf));
+        assert_eq!(
+            parse_inline(&mut buf)?,
+            Some(RespArray::from_vec(vec!["PING".into()]))
+        );
+        assert_eq!(
+            parse_inline(&mut buf)?,
+            Some(RespArray::from_vec(vec![
+                "set".into(),
+                "a".into(),
+                "b c".into()
+            ]))
+        );
+        assert_eq!(parse_inline(&mut buf)?, Some(RespArray::empty()));
+        assert_eq!(parse_inline(&mut buf)?, None);
+        assert_eq!(buf.as_ref(), b"GET");
+
+        assert!(!is_inline(b"*1\r\n$4\r\nping\r\n"));
+        let mut buf = BytesMut::from("x".repeat(MAX_INLINE_LEN + 1).as_str());
+        assert!(matches!(
+            parse_inline(&mut buf),
+            Err(RespError::LimitExceeded(_))
+        ));
+        Ok(())
+    }
+}
@@ -21,9 +21,6 @@ impl RespDecode for RespInteger {
                 .unwrap(),
         ))
     }
-    fn expect_length(buf: &[u8]) -> Result<usize, RespError> {
-        compute_end_with_crlf(buf, Self::PREFIX, Self::TYPE)
-    }
 }
 
 impl RespEncode for RespInteger {
@@ -23,22 +23,15 @@ impl RespDecode for RespMap {
     const PREFIX: &'static str = "%";
     const TYPE: &'static str = "RespMaps";
     fn decode(buf: &mut BytesMut) -> Result<Self, RespError> {
-        let len = check_len(buf, Self::PREFIX, Self::TYPE)?;
-
-        let mut pairs = RespMap::default();
-
-        for _ in 0..len {
-            let key = SimpleString::decode(buf)?; // Keys are encoded as strings in the map
-            let value = RespFrame::decode(buf)?;
-
-            pairs.insert(key.content, value);
+        match decode_frame(buf, Self::PREFIX, Self::TYPE)? {
+            RespFrame::Map(map) => Ok(map),
+            frame => Err(RespError::InvalidFrameType(format!(
+                "{} expect: {} , but got {:?}",
+                Self::TYPE,
+                Self::PREFIX,
+                frame
+            ))),
         }
-        Ok(pairs)
-    }
-    // todo
-    fn expect_length(buf: &[u8]) -> Result<usize, RespError> {
-        let (end, size) = extract_len(buf, Self::PREFIX, Self::TYPE)?;
-        calc_total_length(buf, size, end, Self::PREFIX)
     }
 }
 // | Map           | `%`    | "%<count>\r\n<key1><val1>...<keyN><valN>"                      |
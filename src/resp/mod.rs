@@ -5,13 +5,17 @@
 // | Error         | `-`    | "-Error message\r\n"                                           |
 // | Integer       | `:`    | ":[<+|->]<value>\r\n"                                          |
 // | Bulk String   | `$`    | "$<length>\r\n<data>\r\n"                                      |
-// | Null Bulk Str | `!`    | "!<length>\r\n<error>\r\n"                                     |
+// | Blob Error    | `!`    | "!<length>\r\n<error>\r\n"                                     |
+// | Verbatim Str  | `=`    | "=<length>\r\n<format>:<data>\r\n"                             |
+// | Big Number    | `(`    | "([+|-]<number>\r\n"                                           |
 // | Null          | `_`    | "_\r\n"                                                        |
 // | Array         | `*`    | "*<count>\r\n<element-1>...<element-n>"                        |
 // | Boolean       | `#`    | "#t\r\n" (true) 或 "#f\r\n" (false)                            |
 // | Map           | `%`    | "%<count>\r\n<key1><val1>...<keyN><valN>"                      |
 // | Set           | `~`    | "~<count>\r\n<element-1>...<element-n>"                        |
 // | Double        | `,`    | ",[<+|->]<integral>[.<fractional>][<E|e>[sign]<exponent>]\r\n" |
+// | Attribute     | `|`    | "|<count>\r\n<key1><val1>...<keyN><valN>"                      |
+// | Push          | `>`    | "><count>\r\n<element-1>...<element-n>"                        |
 // * 示例 (GET hello): "*2\r\n$3\r\nget\r\n$5\r\nhello\r\n"
 use bytes::BytesMut;
 use enum_dispatch::enum_dispatch;
@@ -19,28 +23,42 @@ mod decode;
 mod preludes;
 // 添加新模块
 mod array;
+mod attribute;
+mod big_number;
+mod blob_error;
 mod boolean;
 mod bulk_string;
 mod double;
 mod error;
+mod inline;
 mod integer;
 mod map;
 mod null;
+mod parser;
+mod push;
 mod resp_frame;
 mod set;
 mod simple_string;
+mod verbatim_string;
 // 导出新模块中的结构体
 pub use array::RespArray;
+pub use attribute::RespAttribute;
+pub use big_number::RespBigNumber;
+pub use blob_error::RespBlobError;
 pub use boolean::RespBoolean;
 pub use bulk_string::{BulkString, NullBulkString};
 pub use double::RespDouble;
 pub use error::SimpleError;
+pub use inline::{is_inline, parse_inline, split_args};
 pub use integer::RespInteger;
 pub use map::RespMap;
 pub use null::RespNull;
+pub use parser::{RespLimits, RespParser};
+pub use push::RespPush;
 pub use resp_frame::{RespFrame, RespNullArray};
 pub use set::RespSet;
 pub use simple_string::SimpleString;
+pub use verbatim_string::RespVerbatimString;
 
 #[enum_dispatch]
 pub trait RespEncode {
@@ -51,7 +69,6 @@ pub trait RespDecode: Sized {
     const PREFIX: &'static str = "";
     const TYPE: &'static str = "";
     fn decode(buf: &mut BytesMut) -> Result<Self, RespError>;
-    fn expect_length(buf: &[u8]) -> Result<usize, RespError>;
 }
 #[derive(Debug, thiserror::Error, PartialEq)]
 pub enum RespError {
@@ -63,6 +80,9 @@ pub enum RespError {
     InvalidFrameLength(String),
     #[error("not complete frame")]
     NotComplete,
+    /// 超出 RespLimits 的限制，连接应回复错误后关闭
+    #[error("{0}")]
+    LimitExceeded(String),
     #[error("from utf8 error: {0}")]
     Utf8Error(#[from] std::str::Utf8Error),
     #[error("parse int error: {0}")]
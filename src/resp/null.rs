@@ -11,9 +11,6 @@ impl RespDecode for RespNull {
         extract_fix(buf, Self::PREFIX, Self::TYPE)?;
         Ok(Self)
     }
-    fn expect_length(_buf: &[u8]) -> Result<usize, RespError> {
-        Ok(Self::PREFIX.len())
-    }
 }
 impl RespEncode for RespNull {
     fn encode(&self) -> Vec<u8> {
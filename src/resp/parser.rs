@@ -0,0 +1,415 @@
+use super::{
+    BulkString, NullBulkString, RespArray, RespAttribute, RespBigNumber, RespBlobError,
+    RespBoolean, RespDouble, RespInteger, RespMap, RespNull, RespNullArray, RespPush, RespSet,
+    RespVerbatimString, SimpleError, SimpleString, preludes::*,
+};
+
+/// 按声明的元素个数预分配的上限，避免恶意的长度直接占用大量内存
+const PREALLOC_MAX: usize = 1024;
+/// 长度行、整数等单行数据的最大长度
+const MAX_LINE_LEN: usize = 64 * 1024;
+
+/// 解析时的大小限制，防止恶意客户端耗尽内存或栈
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RespLimits {
+    /// 单个 bulk string（以及 verbatim string、blob error）的最大字节数
+    pub max_bulk_len: usize,
+    /// 聚合类型的最大嵌套层数
+    pub max_depth: usize,
+    /// 单个聚合类型的最大元素个数，map 按键值对计
+    pub max_elements: usize,
+}
+
+impl Default for RespLimits {
+    fn default() -> Self {
+        Self {
+            max_bulk_len: 512 * 1024 * 1024,
+            max_depth: 64,
+            max_elements: 1024 * 1024,
+        }
+    }
+}
+
+/// 可恢复的单遍 RESP 解析器
+/// 标量（字符串、整数等）完整到达后才从 buf 中消耗，聚合类型的进度保存在栈上，
+/// 帧不完整时下次 parse 从断点继续，已解析的元素不会再扫描
+#[derive(Debug, Default)]
+pub struct RespParser {
+    /// 未完成的聚合类型，栈顶是最内层
+    stack: Vec<Pending>,
+    limits: RespLimits,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Aggregate {
+    Array,
+    Set,
+    Map,
+    Attribute,
+    Push,
+}
+
+#[derive(Debug)]
+struct Pending {
+    kind: Aggregate,
+    /// 还缺的元素个数，map 和 attribute 的键和值各算一个
+    remaining: usize,
+    elements: Vec<RespFrame>,
+}
+
+/// buf 开头的一个完整标量，或者一个聚合类型的头部
+enum Token {
+    Frame(RespFrame),
+    Aggregate(Aggregate, usize),
+}
+
+impl RespParser {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    pub fn with_limits(limits: RespLimits) -> Self {
+        Self {
+            stack: Vec::new(),
+            limits,
+        }
+    }
+    /// 解析一个完整的帧，数据不完整时返回 None
+    /// 出错后丢弃已解析的部分，调用方应关闭连接
+    pub fn parse(&mut self, buf: &mut BytesMut) -> Result<Option<RespFrame>, RespError> {
+        let ret = self.parse_frame(buf);
+        if ret.is_err() {
+            self.stack.clear();
+        }
+        ret
+    }
+    /// 是否有解析到一半的帧
+    pub fn is_partial(&self) -> bool {
+        !self.stack.is_empty()
+    }
+
+    fn parse_frame(&mut self, buf: &mut BytesMut) -> Result<Option<RespFrame>, RespError> {
+        loop {
+            let mut frame = match next_token(buf, &self.limits) {
+                Ok(Token::Frame(frame)) => frame,
+                Ok(Token::Aggregate(kind, 0)) => kind.finish(Vec::new())?,
+                Ok(Token::Aggregate(kind, len)) => {
+                    if self.stack.len() >= self.limits.max_depth {
+                        return Err(RespError::LimitExceeded(format!(
+                            "nesting depth exceeds {}",
+                            self.limits.max_depth
+                        )));
+                    }
+                    let remaining = kind.item_count(len)?;
+                    self.stack.push(Pending {
+                        kind,
+                        remaining,
+                        elements: Vec::with_capacity(remaining.min(PREALLOC_MAX)),
+                    });
+                    continue;
+                }
+                // 单行数据和长度行都不能超过 MAX_LINE_LEN，
+                // bulk string 的数据部分已经按声明的长度检查过
+                Err(RespError::NotComplete) if unterminated_line(buf) => {
+                    return Err(RespError::LimitExceeded(format!(
+                        "line exceeds {} bytes without CRLF",
+                        MAX_LINE_LEN
+                    )));
+                }
+                Err(RespError::NotComplete) => return Ok(None),
+                Err(e) => return Err(e),
+            };
+            // 逐层填入外层的聚合类型，填满的聚合类型作为元素继续向外填
+            loop {
+                let Some(top) = self.stack.last_mut() else {
+                    return Ok(Some(frame));
+                };
+                top.elements.push(frame);
+                top.remaining -= 1;
+                if top.remaining > 0 {
+                    break;
+                }
+                let pending = self.stack.pop().expect("stack is not empty");
+                frame = pending.kind.finish(pending.elements)?;
+            }
+        }
+    }
+}
+
+impl Aggregate {
+    fn item_count(self, len: usize) -> Result<usize, RespError> {
+        match self {
+            Aggregate::Map | Aggregate::Attribute => len
+                .checked_mul(2)
+                .ok_or_else(|| RespError::InvalidFrameLength(format!("too many pairs: {}", len))),
+            _ => Ok(len),
+        }
+    }
+    fn finish(self, elements: Vec<RespFrame>) -> Result<RespFrame, RespError> {
+        Ok(match self {
+            Aggregate::Array => RespArray::from_vec(elements).into(),
+            Aggregate::Set => RespSet::new(elements).into(),
+            Aggregate::Push => RespPush::new(elements).into(),
+            Aggregate::Map => {
+                let mut map = RespMap::default();
+                for (key, value) in pairs(elements)? {
+                    map.insert(key, value);
+                }
+                map.into()
+            }
+            Aggregate::Attribute => {
+                let mut attribute = RespAttribute::default();
+                for (key, value) in pairs(elements)? {
+                    attribute.insert(key, value);
+                }
+                attribute.into()
+            }
+        })
+    }
+}
+
+/// map 的键只支持字符串
+fn pairs(elements: Vec<RespFrame>) -> Result<Vec<(String, RespFrame)>, RespError> {
+    let mut iter = elements.into_iter();
+    let mut pairs = Vec::with_capacity(iter.len() / 2);
+    while let (Some(key), Some(value)) = (iter.next(), iter.next()) {
+        let key = match &key {
+            RespFrame::SimpleString(SimpleString { content })
+            | RespFrame::BulkString(BulkString {
+                content: Some(content),
+            }) => str::from_utf8(content)?.to_string(),
+            _ => {
+                return Err(RespError::InvalidFrame(format!(
+                    "map key expect string, but got {:?}",
+                    key
+                )));
+            }
+        };
+        pairs.push((key, value));
+    }
+    Ok(pairs)
+}
+
+/// buf 开头的一行超过 MAX_LINE_LEN 仍没有 \r\n
+/// 只在 buf 足够长时扫描，正常的长度行在开头几个字节就能找到 \r\n
+fn unterminated_line(buf: &[u8]) -> bool {
+    buf.len() > MAX_LINE_LEN
+        && !buf[..=MAX_LINE_LEN]
+            .windows(2)
+            .any(|window| window == b"\r\n")
+}
+
+/// 解析聚合类型和 bulk string 的长度行，返回 (长度, 长度行的字节数)，不消耗buf中的数据
+fn header(buf: &[u8]) -> Result<(i64, usize), RespError> {
+    let end = buf
+        .windows(2)
+        .position(|window| window == b"\r\n")
+        .ok_or(RespError::NotComplete)?;
+    Ok((str::from_utf8(&buf[1..end])?.parse()?, end + 2))
+}
+
+fn aggregate(buf: &mut BytesMut, kind: Aggregate, limits: &RespLimits) -> Result<Token, RespError> {
+    let (len, consumed) = header(buf)?;
+    if usize::try_from(len).is_ok_and(|len| len > limits.max_elements) {
+        return Err(RespError::LimitExceeded(format!(
+            "invalid multibulk length: {} exceeds {}",
+            len, limits.max_elements
+        )));
+    }
+    buf.advance(consumed);
+    match (kind, len) {
+        (Aggregate::Array, -1) => Ok(Token::Frame(RespNullArray.into())),
+        (_, len) if len < 0 => Err(RespError::InvalidFrameLength(format!(
+            "{:?} length: {}",
+            kind, len
+        ))),
+        (_, len) => Ok(Token::Aggregate(kind, len as usize)),
+    }
+}
+
+/// 在等待数据之前检查声明的长度，超长的 bulk string 不会被缓存
+fn check_bulk_len(buf: &[u8], limits: &RespLimits) -> Result<i64, RespError> {
+    let (len, _) = header(buf)?;
+    if usize::try_from(len).is_ok_and(|len| len > limits.max_bulk_len) {
+        return Err(RespError::LimitExceeded(format!(
+            "invalid bulk length: {} exceeds proto-max-bulk-len {}",
+            len, limits.max_bulk_len
+        )));
+    }
+    Ok(len)
+}
+
+fn next_token(buf: &mut BytesMut, limits: &RespLimits) -> Result<Token, RespError> {
+    let frame: RespFrame = match buf.first() {
+        None => return Err(RespError::NotComplete),
+        Some(b'*') => return aggregate(buf, Aggregate::Array, limits),
+        Some(b'~') => return aggregate(buf, Aggregate::Set, limits),
+        Some(b'%') => return aggregate(buf, Aggregate::Map, limits),
+        Some(b'|') => return aggregate(buf, Aggregate::Attribute, limits),
+        Some(b'>') => return aggregate(buf, Aggregate::Push, limits),
+        Some(b'$') => match check_bulk_len(buf, limits)? {
+            -1 => {
+                buf.advance(header(buf)?.1);
+                NullBulkString.into()
+            }
+            _ => BulkString::decode(buf)?.into(),
+        },
+        Some(b'+') => SimpleString::decode(buf)?.into(),
+        Some(b'-') => SimpleError::decode(buf)?.into(),
+        Some(b':') => RespInteger::decode(buf)?.into(),
+        Some(b'#') => RespBoolean::decode(buf)?.into(),
+        Some(b',') => RespDouble::decode(buf)?.into(),
+        Some(b'_') if buf.len() < 3 => return Err(RespError::NotComplete),
+        Some(b'_') => RespNull::decode(buf)?.into(),
+        Some(b'=') => {
+            check_bulk_len(buf, limits)?;
+            RespVerbatimString::decode(buf)?.into()
+        }
+        Some(b'(') => RespBigNumber::decode(buf)?.into(),
+        Some(b'!') => {
+            check_bulk_len(buf, limits)?;
+            RespBlobError::decode(buf)?.into()
+        }
+        // 错误信息会回复给客户端，只带上首字节
+        Some(&byte) => {
+            return Err(RespError::InvalidFrameType(format!(
+                "RespFrame expect: +, -, :, $, *, #, ,, %, ~, _, =, (, !, |, >, but got {:?}",
+                byte as char
+            )));
+        }
+    };
+    Ok(Token::Frame(frame))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_resumes_partial_frames() -> anyhow::Result<()> {
+        let frame: RespFrame = RespArray::from_vec(vec![
+            "set".into(),
+            "key".into(),
+            RespArray::from_vec(vec![RespInteger::new(1).into(), RespNull.into()]).into(),
+            RespSet::new(vec![]).into(),
+            NullBulkString.into(),
+        ])
+        .into();
+        let encoded = frame.encode();
+        // 每次只多给一个字节，结果与一次给全相同
+        let mut parser = RespParser::new();
+        let mut buf = BytesMut::new();
+        for (i, byte) in encoded.iter().enumerate() {
+            buf.extend_from_slice(&[*byte]);
+            match parser.parse(&mut buf)? {
+                Some(parsed) => {
+                    assert_eq!(i, encoded.len() - 1);
+                    assert_eq!(parsed, frame);
+                }
+                None => assert!(i < encoded.len() - 1),
+            }
+        }
+        assert!(buf.is_empty());
+        assert!(!parser.is_partial());
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_pipelined_frames() -> anyhow::Result<()> {
+        let mut buf = BytesMut::from(
+            "*1\r\n$4\r\nping\r\n%1\r\n+a\r\n:1\r\n*-1\r\n*0\r\n$-1\r\n|1\r\n$3\r\nttl\r\n#t\r\n",
+        );
+        let mut parser = RespParser::new();
+        let mut frames = vec![];
+        while let Some(frame) = parser.parse(&mut buf)? {
+            frames.push(frame);
+        }
+        let mut map = RespMap::default();
+        map.insert("a".to_string(), RespInteger::new(1).into());
+        let mut attribute = RespAttribute::default();
+        attribute.insert("ttl".to_string(), RespBoolean::new(true).into());
+        assert_eq!(
+            frames,
+            vec![
+                RespArray::from_vec(vec!["ping".into()]).into(),
+                map.into(),
+                RespNullArray.into(),
+                RespArray::empty().into(),
+                NullBulkString.into(),
+                attribute.into(),
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_invalid_frames() {
+        for input in ["?x\r\n", "*-2\r\n", "%1\r\n:1\r\n:2\r\n", "*x\r\n"] {
+            let mut parser = RespParser::new();
+            let mut buf = BytesMut::from(input);
+            assert!(parser.parse(&mut buf).is_err(), "{:?}", input);
+            assert!(!parser.is_partial());
+        }
+    }
+
+    #[test]
+    fn test_parse_limits() {
+        let limits = RespLimits {
+            max_bulk_len: 8,
+            max_depth: 2,
+            max_elements: 3,
+        };
+        let cases = [
+            // 声明的长度超限时不等数据到齐就报错
+            "$9\r\n",
+            "!100\r\n",
+            "*4\r\n",
+            "%4\r\n",
+            "*1\r\n*1\r\n*1\r\n",
+            // 迟迟没有 \r\n 的单行数据
+            &format!("+{}", "x".repeat(MAX_LINE_LEN + 8)),
+        ];
+        for input in cases {
+            let mut parser = RespParser::with_limits(limits);
+            let mut buf = BytesMut::from(input);
+            assert!(
+                matches!(parser.parse(&mut buf), Err(RespError::LimitExceeded(_))),
+                "{:?}",
+                &input[..input.len().min(16)]
+            );
+        }
+
+        let mut parser = RespParser::with_limits(limits);
+        let mut buf = BytesMut::from("*3\r\n$8\r\n12345678\r\n*1\r\n:1\r\n$-1\r\n");
+        assert!(matches!(parser.parse(&mut buf), Ok(Some(_))));
+    }
+
+    #[test]
+    fn test_parse_unterminated_line_default_limits() {
+        // 默认限制下没有 \r\n 的单行数据和长度行也只能缓存 MAX_LINE_LEN 字节
+        let long = "1".repeat(MAX_LINE_LEN + 8);
+        for prefix in ["+", "-", ":", "*", "%", "$", ",", "("] {
+            let mut parser = RespParser::new();
+            let mut buf = BytesMut::from(format!("{}{}", prefix, long).as_str());
+            assert!(
+                matches!(parser.parse(&mut buf), Err(RespError::LimitExceeded(_))),
+                "{:?}",
+                prefix
+            );
+        }
+        // 在聚合类型内部同样生效
+        let mut parser = RespParser::new();
+        let mut buf = BytesMut::from(format!("*2\r\n:1\r\n+{}", long).as_str());
+        assert!(matches!(
+            parser.parse(&mut buf),
+            Err(RespError::LimitExceeded(_))
+        ));
+
+        // 长度合法的 bulk string 数据还没到齐时继续等待
+        let mut parser = RespParser::new();
+        let mut buf = BytesMut::from(format!("$100000\r\n{}", long).as_str());
+        assert!(matches!(parser.parse(&mut buf), Ok(None)));
+        buf.extend_from_slice("1".repeat(100000 - long.len()).as_bytes());
+        buf.extend_from_slice(b"\r\n");
+        assert!(matches!(parser.parse(&mut buf), Ok(Some(_))));
+    }
+}
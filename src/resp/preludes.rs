@@ -1,12 +1,12 @@
 pub use std::ops::{Deref, DerefMut};
 
-pub use bytes::{Buf, BytesMut};
+pub use bytes::{Buf, Bytes, BytesMut};
 
 pub use super::{
     RespDecode, RespEncode, RespFrame,
     decode::{
-        CRLF, calc_total_length, check_len, compute_end, compute_end_with_crlf, consume_byte_mut,
-        extract_end_string, extract_fix, extract_len, split_vec,
+        compute_end_with_crlf, consume_byte_mut, decode_frame, extract_blob, extract_end_string,
+        extract_fix,
     },
 };
 pub use crate::resp::RespError;
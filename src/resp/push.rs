@@ -0,0 +1,73 @@
+use super::preludes::*;
+
+/// 服务端主动推送的数据（如 pub/sub 消息），第一个元素是消息类型
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct RespPush {
+    pub elements: Vec<RespFrame>,
+}
+
+impl Deref for RespPush {
+    type Target = Vec<RespFrame>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.elements
+    }
+}
+
+impl RespPush {
+    pub fn new(elements: Vec<RespFrame>) -> Self {
+        Self { elements }
+    }
+}
+
+// | Push          | `>`    | "><count>\r\n<element-1>...<element-n>"                        |
+impl RespDecode for RespPush {
+    const PREFIX: &'static str = ">";
+    const TYPE: &'static str = "RespPush";
+    fn decode(buf: &mut BytesMut) -> Result<Self, RespError> {
+        match decode_frame(buf, Self::PREFIX, Self::TYPE)? {
+            RespFrame::Push(push) => Ok(push),
+            frame => Err(RespError::InvalidFrameType(format!(
+                "{} expect: {} , but got {:?}",
+                Self::TYPE,
+                Self::PREFIX,
+                frame
+            ))),
+        }
+    }
+}
+
+impl RespEncode for RespPush {
+    fn encode(&self) -> Vec<u8> {
+        let count_str = self.elements.len().to_string();
+        let mut result = Vec::with_capacity(1 + count_str.len() + 2);
+        result.extend_from_slice(b">");
+        result.extend_from_slice(count_str.as_bytes());
+        result.extend_from_slice(b"\r\n");
+        for element in &self.elements {
+            result.extend_from_slice(&element.encode());
+        }
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn test_push_round_trip() {
+        let frame: RespFrame =
+            RespPush::new(vec!["message".into(), "news".into(), "hello".into()]).into();
+        let encoded = frame.encode();
+        assert_eq!(
+            encoded,
+            b">3\r\n$7\r\nmessage\r\n$4\r\nnews\r\n$5\r\nhello\r\n"
+        );
+        let mut buf = BytesMut::from(&encoded[..]);
+        assert_eq!(RespFrame::decode(&mut buf), Ok(frame));
+        assert!(buf.is_empty());
+
+        let mut buf = BytesMut::from(">2\r\n$7\r\nmessage\r\n");
+        assert_eq!(RespPush::decode(&mut buf), Err(RespError::NotComplete));
+    }
+}
@@ -4,6 +4,9 @@ use super::preludes::*;
 // 导出新模块中的结构体
 pub use super::{
     array::{RespArray, RespNullArray},
+    attribute::RespAttribute,
+    big_number::RespBigNumber,
+    blob_error::RespBlobError,
     boolean::RespBoolean,
     bulk_string::{BulkString, NullBulkString},
     double::RespDouble,
@@ -11,8 +14,10 @@ pub use super::{
     integer::RespInteger,
     map::RespMap,
     null::RespNull,
+    push::RespPush,
     set::RespSet,
     simple_string::SimpleString,
+    verbatim_string::RespVerbatimString,
 };
 #[enum_dispatch(RespEncode)]
 #[derive(Debug, Clone, PartialEq)]
@@ -30,82 +35,116 @@ pub enum RespFrame {
     Set(RespSet),
     RespNull(RespNull),
     RespNullArray(RespNullArray),
+    VerbatimString(RespVerbatimString),
+    BigNumber(RespBigNumber),
+    BlobError(RespBlobError),
+    Attribute(RespAttribute),
+    Push(RespPush),
+}
+
+impl RespFrame {
+    /// 转换为 RESP2 客户端能识别的帧：set 转为 array，map 展开为 [k1, v1, k2, v2, ...]，
+    /// null 转为 $-1，boolean 转为 1/0，double 转为字符串
+    pub fn into_resp2(self) -> RespFrame {
+        match self {
+            RespFrame::Set(set) => RespArray::from_vec(
+                set.elements
+                    .into_iter()
+                    .map(RespFrame::into_resp2)
+                    .collect(),
+            )
+            .into(),
+            RespFrame::Array(RespArray {
+                elements: Some(elements),
+            }) => RespArray::from_vec(elements.into_iter().map(RespFrame::into_resp2).collect())
+                .into(),
+            RespFrame::Map(map) => RespArray::from_vec(
+                map.pairs
+                    .into_iter()
+                    .flat_map(|(key, value)| {
+                        [BulkString::from_slice(key).into(), value.into_resp2()]
+                    })
+                    .collect(),
+            )
+            .into(),
+            // RESP2 没有 double 类型，用字符串表示
+            RespFrame::Double(double) => BulkString::from_slice(double.value.to_string()).into(),
+            RespFrame::Boolean(boolean) => RespInteger::new(boolean.value as i64).into(),
+            RespFrame::RespNull(_) => NullBulkString.into(),
+            RespFrame::VerbatimString(verbatim) => BulkString::new(verbatim.content).into(),
+            RespFrame::BigNumber(number) => BulkString::from_slice(number.value).into(),
+            // simple error 不能包含换行
+            RespFrame::BlobError(error) => {
+                SimpleError::new(String::from_utf8_lossy(&error.content).replace(['\r', '\n'], " "))
+                    .into()
+            }
+            RespFrame::Push(push) => RespArray::from_vec(
+                push.elements
+                    .into_iter()
+                    .map(RespFrame::into_resp2)
+                    .collect(),
+            )
+            .into(),
+            RespFrame::Attribute(attribute) => RespArray::from_vec(
+                attribute
+                    .pairs
+                    .into_iter()
+                    .flat_map(|(key, value)| {
+                        [BulkString::from_slice(key).into(), value.into_resp2()]
+                    })
+                    .collect(),
+            )
+            .into(),
+            frame => frame,
+        }
+    }
 }
 
 impl RespDecode for RespFrame {
     const PREFIX: &'static str = "";
     fn decode(buf: &mut BytesMut) -> Result<Self, RespError> {
-        let mut iter = buf.iter().peekable();
-        match iter.peek() {
-            Some(b'+') => {
-                let frame = SimpleString::decode(buf)?;
-                Ok(frame.into())
-            }
-            Some(b'-') => {
-                let frame = SimpleError::decode(buf)?;
-                Ok(frame.into())
-            }
-            Some(b':') => {
-                let frame = RespInteger::decode(buf)?;
-                Ok(frame.into())
-            }
-            Some(b'$') => match NullBulkString::decode(buf) {
-                Ok(frame) => Ok(frame.into()),
-                Err(RespError::NotComplete) => Err(RespError::NotComplete),
-                Err(_) => {
-                    let frame = BulkString::decode(buf)?;
-                    Ok(frame.into())
-                }
-            },
-            Some(b'*') => match RespNullArray::decode(buf) {
-                Ok(frame) => Ok(frame.into()),
-                Err(RespError::NotComplete) => Err(RespError::NotComplete),
-                Err(_) => {
-                    let frame = RespArray::decode(buf)?;
-                    Ok(frame.into())
-                }
-            },
-            Some(b'#') => {
-                let frame = RespBoolean::decode(buf)?;
-                Ok(frame.into())
-            }
-            Some(b',') => {
-                let frame = RespDouble::decode(buf)?;
-                Ok(frame.into())
-            }
-            Some(b'%') => {
-                let frame = RespMap::decode(buf)?;
-                Ok(frame.into())
-            }
-            Some(b'~') => {
-                let frame = RespSet::decode(buf)?;
-                Ok(frame.into())
-            }
-            Some(b'_') => {
-                let frame = RespNull::decode(buf)?;
-                Ok(frame.into())
-            }
-            None => Err(RespError::NotComplete),
-            _ => Err(RespError::InvalidFrameType(format!(
-                "RespFrame expect: +, -, :, $, *, #, ,, %, ~, _, but got {:?}",
-                buf
-            ))),
-        }
+        decode_frame(buf, Self::PREFIX, "RespFrame")
     }
-    fn expect_length(buf: &[u8]) -> Result<usize, RespError> {
-        let mut iter = buf.iter().peekable();
-        match iter.peek() {
-            Some(b'+') => SimpleString::expect_length(buf),
-            Some(b'-') => SimpleError::expect_length(buf),
-            Some(b':') => RespInteger::expect_length(buf),
-            Some(b'$') => BulkString::expect_length(buf),
-            Some(b'*') => RespArray::expect_length(buf),
-            Some(b'#') => RespBoolean::expect_length(buf),
-            Some(b',') => RespDouble::expect_length(buf),
-            Some(b'%') => RespMap::expect_length(buf),
-            Some(b'~') => RespSet::expect_length(buf),
-            Some(b'_') => RespNull::expect_length(buf),
-            _ => Err(RespError::NotComplete),
-        }
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_into_resp2() {
+        let set: RespFrame = RespSet::new(vec!["a".into(), "b".into()]).into();
+        let array: RespFrame = RespArray::from_vec(vec!["a".into(), "b".into()]).into();
+        assert_eq!(set.into_resp2(), array);
+
+        let nested: RespFrame =
+            RespArray::from_vec(vec![RespSet::new(vec!["a".into()]).into()]).into();
+        let expected: RespFrame =
+            RespArray::from_vec(vec![RespArray::from_vec(vec!["a".into()]).into()]).into();
+        assert_eq!(nested.into_resp2(), expected);
+
+        let double: RespFrame = RespDouble::new(1.5).into();
+        assert_eq!(double.into_resp2(), BulkString::from_slice("1.5").into());
+
+        let mut map = RespMap::default();
+        map.insert("a".to_string(), RespBoolean::new(true).into());
+        map.insert("b".to_string(), RespNull.into());
+        let expected: RespFrame = RespArray::from_vec(vec![
+            "a".into(),
+            RespInteger::new(1).into(),
+            "b".into(),
+            NullBulkString.into(),
+        ])
+        .into();
+        assert_eq!(RespFrame::from(map).into_resp2(), expected);
+
+        let verbatim: RespFrame = RespVerbatimString::text("hello").into();
+        assert_eq!(verbatim.into_resp2(), "hello".into());
+        let error: RespFrame = RespBlobError::new("ERR a\r\nb").into();
+        assert_eq!(error.into_resp2(), SimpleError::new("ERR a  b").into());
+        let push: RespFrame = RespPush::new(vec![RespNull.into()]).into();
+        assert_eq!(
+            push.into_resp2(),
+            RespArray::from_vec(vec![NullBulkString.into()]).into()
+        );
     }
 }
@@ -34,18 +34,15 @@ impl RespDecode for RespSet {
     const PREFIX: &'static str = "~";
     const TYPE: &'static str = "RespSet";
     fn decode(buf: &mut BytesMut) -> Result<Self, RespError> {
-        let len = check_len(buf, Self::PREFIX, Self::TYPE)?;
-        let mut set = RespSet::with_capacity(len);
-        for _ in 0..len {
-            let element = RespFrame::decode(buf)?;
-            set.push(element);
+        match decode_frame(buf, Self::PREFIX, Self::TYPE)? {
+            RespFrame::Set(set) => Ok(set),
+            frame => Err(RespError::InvalidFrameType(format!(
+                "{} expect: {} , but got {:?}",
+                Self::TYPE,
+                Self::PREFIX,
+                frame
+            ))),
         }
-
-        Ok(set)
-    }
-    fn expect_length(buf: &[u8]) -> Result<usize, RespError> {
-        let (end, len) = extract_len(buf, Self::PREFIX, Self::TYPE)?;
-        calc_total_length(buf, end, len, Self::PREFIX)
     }
 }
 
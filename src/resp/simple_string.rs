@@ -2,18 +2,18 @@ use super::preludes::*;
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct SimpleString {
-    pub content: String,
+    pub content: Bytes,
 }
 
 impl SimpleString {
     pub fn new(content: impl Into<String>) -> Self {
         Self {
-            content: content.into(),
+            content: Bytes::from(content.into()),
         }
     }
 }
 impl Deref for SimpleString {
-    type Target = str;
+    type Target = [u8];
     fn deref(&self) -> &Self::Target {
         &self.content
     }
@@ -29,14 +29,9 @@ impl RespDecode for SimpleString {
     const PREFIX: &'static str = "+";
     const TYPE: &'static str = "SimpleString";
     fn decode(buf: &mut BytesMut) -> Result<Self, RespError> {
-        Ok(SimpleString::new(extract_end_string(
-            buf,
-            Self::PREFIX,
-            Self::TYPE,
-        )?))
-    }
-    fn expect_length(buf: &[u8]) -> Result<usize, RespError> {
-        compute_end_with_crlf(buf, Self::PREFIX, Self::TYPE)
+        Ok(SimpleString {
+            content: consume_byte_mut(buf, Self::PREFIX, Self::TYPE)?,
+        })
     }
 }
 
@@ -44,7 +39,7 @@ impl RespEncode for SimpleString {
     fn encode(&self) -> Vec<u8> {
         let mut result = Vec::with_capacity(2 + self.content.len() + 2); // + + content + \r\n
         result.extend_from_slice(b"+");
-        result.extend_from_slice(self.content.as_bytes());
+        result.extend_from_slice(&self.content);
         result.extend_from_slice(b"\r\n");
         result
     }
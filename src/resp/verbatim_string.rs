@@ -0,0 +1,79 @@
+use super::preludes::*;
+
+/// 带格式的字符串，format 固定 3 个字符，如 txt、mkd
+#[derive(Debug, Clone, PartialEq)]
+pub struct RespVerbatimString {
+    pub format: String,
+    pub content: Bytes,
+}
+
+impl RespVerbatimString {
+    pub fn new(format: impl Into<String>, content: impl Into<Bytes>) -> Self {
+        Self {
+            format: format.into(),
+            content: content.into(),
+        }
+    }
+    /// 纯文本
+    pub fn text(content: impl Into<Bytes>) -> Self {
+        Self::new("txt", content)
+    }
+}
+
+// | Verbatim Str  | `=`    | "=<length>\r\n<format>:<data>\r\n"                             |
+impl RespDecode for RespVerbatimString {
+    const PREFIX: &'static str = "=";
+    const TYPE: &'static str = "RespVerbatimString";
+    fn decode(buf: &mut BytesMut) -> Result<Self, RespError> {
+        let data = extract_blob(buf, Self::PREFIX, Self::TYPE)?;
+        // 长度包含 "<format>:" 这 4 个字节
+        if data.len() < 4 || data[3] != b':' {
+            return Err(RespError::InvalidFrame(format!(
+                "{} expect <format>:<data>, but got {:?}",
+                Self::TYPE,
+                data
+            )));
+        }
+        Ok(RespVerbatimString {
+            format: str::from_utf8(&data[..3])?.to_string(),
+            content: data.slice(4..),
+        })
+    }
+}
+
+impl RespEncode for RespVerbatimString {
+    fn encode(&self) -> Vec<u8> {
+        let len_str = (self.format.len() + 1 + self.content.len()).to_string();
+        let mut result = Vec::with_capacity(1 + len_str.len() + 2 + 4 + self.content.len() + 2);
+        result.extend_from_slice(b"=");
+        result.extend_from_slice(len_str.as_bytes());
+        result.extend_from_slice(b"\r\n");
+        result.extend_from_slice(self.format.as_bytes());
+        result.extend_from_slice(b":");
+        result.extend_from_slice(&self.content);
+        result.extend_from_slice(b"\r\n");
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn test_verbatim_string_round_trip() {
+        let frame: RespFrame = RespVerbatimString::text("Some string").into();
+        let encoded = frame.encode();
+        assert_eq!(encoded, b"=15\r\ntxt:Some string\r\n");
+        let mut buf = BytesMut::from(&encoded[..]);
+        assert_eq!(RespFrame::decode(&mut buf), Ok(frame));
+        assert!(buf.is_empty());
+
+        let mut buf = BytesMut::from("=3\r\ntxt\r\n");
+        assert!(RespVerbatimString::decode(&mut buf).is_err());
+        let mut buf = BytesMut::from("=15\r\ntxt:Some");
+        assert_eq!(
+            RespVerbatimString::decode(&mut buf),
+            Err(RespError::NotComplete)
+        );
+    }
+}